@@ -17,7 +17,8 @@ use swc_ecma_ast::{ClassMember, ModuleItem};
 use swc_ecma_parser::{lexer::Lexer, Capturing, Parser, StringInput, Syntax};
 
 struct WktGen {
-    types: Vec<(String, String)>,
+    types: Vec<(bool, String, String)>,
+    preludes: Vec<(bool, String)>,
     contents: String,
 }
 
@@ -25,6 +26,7 @@ impl WktGen {
     pub fn new() -> Self {
         WktGen {
             types: vec![],
+            preludes: vec![],
             contents: String::new(),
         }
     }
@@ -60,7 +62,7 @@ impl WktGen {
         body
     }
 
-    fn json_methods(&self, ast: ModuleItem) -> (String, ClassMember, ClassMember) {
+    fn class_members(&self, ast: ModuleItem) -> (String, ClassMember, ClassMember, Vec<ClassMember>) {
         let decl = ast
             .as_stmt()
             .expect("expected stmt")
@@ -70,6 +72,7 @@ impl WktGen {
             .expect("expected class");
         let mut from_json: Option<ClassMember> = None;
         let mut to_json: Option<ClassMember> = None;
+        let mut members: Vec<ClassMember> = vec![];
         for b in decl.class.body.clone() {
             if b.is_method() {
                 let method = b.as_method().unwrap();
@@ -83,7 +86,11 @@ impl WktGen {
                     from_json = Some(b);
                 } else if keyname == "toJson" {
                     to_json = Some(b);
+                } else {
+                    members.push(b);
                 }
+            } else {
+                members.push(b);
             }
         }
         if from_json.is_none() || to_json.is_none() {
@@ -93,13 +100,28 @@ impl WktGen {
             decl.ident.sym.to_string(),
             from_json.unwrap(),
             to_json.unwrap(),
+            members,
         );
     }
 
-    fn gen_wkt(&mut self, name: String, types: Vec<(String, String, String)>) {
+    fn gen_wkt(
+        &mut self,
+        name: String,
+        prelude: String,
+        types: Vec<(String, String, String, String)>,
+    ) {
         self.contents.push_str(&format!("pub mod r#{} {{", name));
+        self.contents.push_str(
+            &"
+    const PRELUDE: &str = r#\"prelude_src\"#;
+    pub fn prelude() -> Vec<swc_ecma_ast::ModuleItem> {
+        serde_json::from_str::<Vec<swc_ecma_ast::ModuleItem>>(PRELUDE).unwrap()
+    }
+"
+            .replace("prelude_src", &prelude),
+        );
 
-        for (name, from_json, to_json) in types {
+        for (name, from_json, to_json, members) in types {
             self.contents.push_str(
                 &"
 pub mod r#[name] {
@@ -113,11 +135,17 @@ pub mod r#[name] {
     pub fn to_json() -> ClassMember {
         serde_json::from_str::<ClassMember>(TO_JSON).unwrap()
     }
+
+    const MEMBERS: &str = r#\"members_src\"#;
+    pub fn members() -> Vec<ClassMember> {
+        serde_json::from_str::<Vec<ClassMember>>(MEMBERS).unwrap()
+    }
 }
 "
                 .replace("[name]", &name)
                 .replace("from_json_src", &from_json)
-                .replace("to_json_src", &to_json),
+                .replace("to_json_src", &to_json)
+                .replace("members_src", &members),
             )
         }
 
@@ -125,24 +153,47 @@ pub mod r#[name] {
     }
 
     pub fn build(&mut self, name: String, source: String) -> &mut Self {
+        self.build_variant(false, name, source)
+    }
+
+    // a variant of a file used with arkts_strict, it replaces the prelude and the classes it declares.
+    pub fn build_strict(&mut self, name: String, source: String) -> &mut Self {
+        self.build_variant(true, name, source)
+    }
+
+    fn build_variant(&mut self, strict: bool, name: String, source: String) -> &mut Self {
         let mast = self.ast(source);
+        let module = if strict {
+            format!("strict_{}", name)
+        } else {
+            name.clone()
+        };
 
-        let mut types: Vec<(String, String, String)> = vec![];
+        let mut types: Vec<(String, String, String, String)> = vec![];
+        // exported declarations are emitted at the top of the generated file as is.
+        let mut prelude: Vec<ModuleItem> = vec![];
 
         for m in mast {
-            let (type_name, from_json, to_json) = self.json_methods(m);
+            if m.is_module_decl() {
+                prelude.push(m);
+                continue;
+            }
+            let (type_name, from_json, to_json, members) = self.class_members(m);
             self.types.push((
+                strict,
                 format!("{}.proto#{}", name, type_name),
-                format!("r#{}::r#{}", name, type_name.to_lowercase()),
+                format!("r#{}::r#{}", module, type_name.to_lowercase()),
             ));
             types.push((
                 type_name.to_lowercase(),
                 serde_json::to_string(&from_json).unwrap(),
                 serde_json::to_string(&to_json).unwrap(),
+                serde_json::to_string(&members).unwrap(),
             ));
         }
 
-        self.gen_wkt(name, types);
+        self.preludes.push((strict, name));
+        self.gen_wkt(module, serde_json::to_string(&prelude).unwrap(), types);
         self
     }
 
@@ -151,26 +202,74 @@ pub mod r#[name] {
         let wkt_dir: std::path::PathBuf = Path::new(&out_dir).join("wkt");
         std::fs::create_dir_all(&wkt_dir).expect("failed to create wkt dir");
         let dest_path = wkt_dir.join("mod.rs");
-        let mut arms = String::from("");
 
-        for (t1, t2) in self.types.clone() {
+        // strict variants come first, everything they do not declare falls back to the default.
+        let mut types = self.types.clone();
+        types.sort_by_key(|(strict, _, _)| !*strict);
+        let mut preludes = self.preludes.clone();
+        preludes.sort_by_key(|(strict, _)| !*strict);
+        let pattern = |strict: bool| if strict { "true" } else { "_" };
+
+        let mut arms = String::from("");
+        for (strict, t1, t2) in types.clone() {
             arms.push_str("\t\t");
             arms.push_str(&format!(
-                r#""google/protobuf/{}#from_json" => Some({}::from_json()),"#,
-                t1, t2
+                r#"({}, "google/protobuf/{}#from_json") => Some({}::from_json()),"#,
+                pattern(strict), t1, t2
             ));
             arms.push_str("\n");
             arms.push_str("\t\t");
             arms.push_str(&format!(
-                r#""google/protobuf/{}#to_json" => Some({}::to_json()),"#,
-                t1, t2
+                r#"({}, "google/protobuf/{}#to_json") => Some({}::to_json()),"#,
+                pattern(strict), t1, t2
             ));
             arms.push_str("\n");
         }
+        let mut members_arms = String::from("");
+        for (strict, t1, t2) in types {
+            members_arms.push_str(&format!(
+                "\t\t({}, \"google/protobuf/{}\") => {}::members(),\n",
+                pattern(strict), t1, t2
+            ));
+        }
+        self.contents.push_str(&format!(
+            r#"
+pub fn get_members(proto: &str, type_name: &str, strict: bool) -> Vec<swc_ecma_ast::ClassMember> {{
+    match (strict, format!("{{}}#{{}}", proto, type_name).as_str()) {{
+{}
+        _ => vec![],
+    }}
+}}
+"#,
+            members_arms
+        ));
+        let mut prelude_arms = String::from("");
+        for (strict, name) in preludes {
+            let module = if strict {
+                format!("strict_{}", name)
+            } else {
+                name.clone()
+            };
+            prelude_arms.push_str(&format!(
+                "\t\t({}, \"google/protobuf/{}.proto\") => r#{}::prelude(),\n",
+                pattern(strict), name, module
+            ));
+        }
+        self.contents.push_str(&format!(
+            r#"
+pub fn get_prelude(proto: &str, strict: bool) -> Vec<swc_ecma_ast::ModuleItem> {{
+    match (strict, proto) {{
+{}
+        _ => vec![],
+    }}
+}}
+"#,
+            prelude_arms
+        ));
         self.contents.push_str(&format!(
             r#"
-pub fn get_member(proto: &str, type_name: &str, member: &str) -> Option<swc_ecma_ast::ClassMember> {{
-    match format!("{{}}#{{}}#{{}}", proto, type_name, member).as_str() {{
+pub fn get_member(proto: &str, type_name: &str, member: &str, strict: bool) -> Option<swc_ecma_ast::ClassMember> {{
+    match (strict, format!("{{}}#{{}}#{{}}", proto, type_name, member).as_str()) {{
 {}
         _ => None,
     }}
@@ -185,9 +284,14 @@ pub fn get_member(proto: &str, type_name: &str, member: &str) -> Option<swc_ecma
 pub const STRUCT: &str = include_str!("./js/runtime/google_protobuf/struct.ts");
 pub const ANY: &str = include_str!("./js/runtime/google_protobuf/any.ts");
 pub const WRAPPERS: &str = include_str!("./js/runtime/google_protobuf/wrappers.ts");
-// pub const TIMESTAMP: &str = include_str!("./js/runtime/google_protobuf/timestamp.ts");
-// pub const DURATION: &str = include_str!("./js/runtime/google_protobuf/duration.ts");
+pub const TIMESTAMP: &str = include_str!("./js/runtime/google_protobuf/timestamp.ts");
+pub const DURATION: &str = include_str!("./js/runtime/google_protobuf/duration.ts");
 pub const FIELD_MASK: &str = include_str!("./js/runtime/google_protobuf/field_mask.ts");
+pub const DESCRIPTOR: &str = include_str!("./js/runtime/google_protobuf/descriptor.ts");
+pub const STRICT_STRUCT: &str = include_str!("./js/runtime/google_protobuf/strict/struct.ts");
+pub const STRICT_ANY: &str = include_str!("./js/runtime/google_protobuf/strict/any.ts");
+pub const STRICT_WRAPPERS: &str = include_str!("./js/runtime/google_protobuf/strict/wrappers.ts");
+pub const STRICT_FIELD_MASK: &str = include_str!("./js/runtime/google_protobuf/strict/field_mask.ts");
 
 fn main() {
     Codegen::new()
@@ -202,8 +306,13 @@ fn main() {
         .build("struct".to_string(), STRUCT.to_string())
         .build("any".to_string(), ANY.to_string())
         .build("wrappers".to_string(), WRAPPERS.to_string())
-        // .build("timestamp".to_string(), TIMESTAMP.to_string())
-        // .build("duration".to_string(), DURATION.to_string())
+        .build("timestamp".to_string(), TIMESTAMP.to_string())
+        .build("duration".to_string(), DURATION.to_string())
         .build("field_mask".to_string(), FIELD_MASK.to_string())
+        .build("descriptor".to_string(), DESCRIPTOR.to_string())
+        .build_strict("struct".to_string(), STRICT_STRUCT.to_string())
+        .build_strict("any".to_string(), STRICT_ANY.to_string())
+        .build_strict("wrappers".to_string(), STRICT_WRAPPERS.to_string())
+        .build_strict("field_mask".to_string(), STRICT_FIELD_MASK.to_string())
         .yield_mod();
 }
@@ -11,43 +11,16 @@ import * as field_mask from "../../tests/well_known/gen/google/protobuf/field_ma
 import { Buffer } from "https://deno.land/std@0.136.0/node/buffer.ts";
 import * as base64 from "https://deno.land/std@0.202.0/encoding/base64.ts";
 
-globalThis.protobuf = new (class TypeRegistry {
-  registry = new Map<string, unknown>();
-  get(name: string) {
-    return this.registry.get(name);
-  }
-  append(message) {
-    this.registry.set(message.type, message);
-  }
-})();
-
-const messages = [
-  proto2.protobuf_test_messages_proto2_TestAllTypesProto2,
-  proto3.protobuf_test_messages_proto3_TestAllTypesProto3,
-  conformance.conformance_ConformanceRequest,
-  conformance.conformance_ConformanceResponse,
-  conformance.conformance_FailureSet,
-  conformance.conformance_JspbEncodingConfig,
-  any.google_protobuf_Any,
-  duration.google_protobuf_Duration,
-  timestamp.google_protobuf_Timestamp,
-  struct.google_protobuf_Struct,
-  struct.google_protobuf_ListValue,
-  struct.google_protobuf_Value,
-  wrappers.google_protobuf_BoolValue,
-  wrappers.google_protobuf_BytesValue,
-  wrappers.google_protobuf_DoubleValue,
-  wrappers.google_protobuf_FloatValue,
-  wrappers.google_protobuf_Int32Value,
-  wrappers.google_protobuf_Int64Value,
-  wrappers.google_protobuf_StringValue,
-  wrappers.google_protobuf_UInt32Value,
-  wrappers.google_protobuf_UInt64Value,
-  field_mask.google_protobuf_FieldMask,
-];
-
-for (const message of messages) {
-  globalThis.protobuf.append(message);
+// importing the generated files registers their messages, the well-known
+// types are imported explicitly since Any payloads may refer to any of them.
+for (const register of [
+  duration.register_google_protobuf_duration,
+  timestamp.register_google_protobuf_timestamp,
+  struct.register_google_protobuf_struct,
+  wrappers.register_google_protobuf_wrappers,
+  field_mask.register_google_protobuf_field_mask,
+]) {
+  register();
 }
 
 while (true) {
@@ -61,12 +34,12 @@ while (true) {
   const req = conformance.conformance_ConformanceRequest.fromBinary(requestBuffer);
   const res = new conformance.conformance_ConformanceResponse();
 
-  let message = globalThis.protobuf.get(req.message_type);
+  let message = any.type_registry.get(req.message_type);
 
   if (!message) {
     res.runtime_error = `unknown message ${req.message_type}`;
   } else {
-    let i: InstanceType<typeof message> | undefined = undefined;
+    let i: any.RegisteredMessage | undefined = undefined;
 
     try {
       if (req.json_payload) {
@@ -0,0 +1,71 @@
+// options of the generated toBinary methods.
+export interface BinaryWriteOptions {
+  // write map entries ordered by key, so that equal messages always encode to the same bytes.
+  deterministic?: boolean;
+}
+
+// the entries of a map ordered by key when deterministic is set, in insertion order otherwise.
+export function orderedEntries<K, V>(
+  entries: IterableIterator<[K, V]>,
+  deterministic: boolean,
+  compare: (a: K, b: K) => number
+): IterableIterator<[K, V]> {
+  if (!deterministic) {
+    return entries;
+  }
+  const sorted: Array<[K, V]> = [];
+  for (const entry of entries) {
+    sorted.push(entry);
+  }
+  sorted.sort((a: [K, V], b: [K, V]): number => compare(a[0], b[0]));
+  return sorted.values();
+}
+
+// int32, sint32 and sfixed32 keys.
+export function compareNumbers(a: number, b: number): number {
+  return a < b ? -1 : a > b ? 1 : 0;
+}
+
+// uint32 and fixed32 keys.
+export function compareUnsignedNumbers(a: number, b: number): number {
+  return compareNumbers(a >>> 0, b >>> 0);
+}
+
+// int64, sint64 and sfixed64 keys.
+export function compareBigints(a: bigint, b: bigint): number {
+  return a < b ? -1 : a > b ? 1 : 0;
+}
+
+// uint64 and fixed64 keys.
+export function compareUnsignedBigints(a: bigint, b: bigint): number {
+  return compareBigints(BigInt.asUintN(64, a), BigInt.asUintN(64, b));
+}
+
+// false before true.
+export function compareBooleans(a: boolean, b: boolean): number {
+  return a === b ? 0 : a ? 1 : -1;
+}
+
+// string keys in the order of their utf-8 bytes, which is code point order.
+// utf-16 code units differ from it only for surrogates, which have to sort after U+E000..U+FFFF.
+export function compareStrings(a: string, b: string): number {
+  const length: number = Math.min(a.length, b.length);
+  for (let i: number = 0; i < length; i++) {
+    const x: number = a.charCodeAt(i);
+    const y: number = b.charCodeAt(i);
+    if (x !== y) {
+      return codePointOrder(x) - codePointOrder(y);
+    }
+  }
+  return a.length - b.length;
+}
+
+function codePointOrder(unit: number): number {
+  if (unit >= 0xe000) {
+    return unit - 0x800;
+  }
+  if (unit >= 0xd800) {
+    return unit + 0x2000;
+  }
+  return unit;
+}
@@ -0,0 +1,157 @@
+import { BinaryDecoder, BinaryEncoder } from "google-protobuf";
+
+// the generated message classes, read back by the delimited readers.
+export interface DelimitedMessageType<T> {
+  fromBinary(bytes: Uint8Array): T;
+}
+
+// the largest length prefix accepted, protobuf messages are limited to 2GiB.
+const MAX_LENGTH: number = 0x7fffffff;
+// the most bytes a length prefix of at most MAX_LENGTH takes.
+const MAX_PREFIX_SIZE: number = 5;
+
+// prefixes the encoded message with its length as a varint, like writeDelimitedTo of the other runtimes.
+export function writeDelimited(bytes: Uint8Array): Uint8Array {
+  const encoder: BinaryEncoder = new BinaryEncoder();
+  encoder.writeUnsignedVarint32(bytes.length);
+  const prefix: number[] = encoder.end();
+  const framed: Uint8Array = new Uint8Array(prefix.length + bytes.length);
+  framed.set(prefix, 0);
+  framed.set(bytes, prefix.length);
+  return framed;
+}
+
+// the length prefix found at the start of a buffer.
+class Prefix {
+  length: number;
+  size: number;
+
+  constructor(length: number, size: number) {
+    this.length = length;
+    this.size = size;
+  }
+}
+
+// reads the length prefix at offset, null when the buffer ends inside of it.
+function readPrefix(bytes: Uint8Array, offset: number): Prefix | null {
+  // the varint ends at the first byte without the continuation bit.
+  let end: number = offset;
+  while (end < bytes.length && (bytes[end] & 0x80) !== 0) {
+    end++;
+  }
+  // the fifth byte holds bits 28 to 34 of the length, of which only 28 to 30 fit below MAX_LENGTH.
+  const size: number = end - offset + 1;
+  if (size > MAX_PREFIX_SIZE || (size === MAX_PREFIX_SIZE && end < bytes.length && bytes[end] > 0x07)) {
+    throw new Error("delimited message length exceeds " + MAX_LENGTH + " bytes");
+  }
+  if (end === bytes.length) {
+    return null;
+  }
+  const decoder: BinaryDecoder = BinaryDecoder.alloc(bytes, offset, size);
+  const length: number = decoder.readUnsignedVarint32();
+  decoder.free();
+  return new Prefix(length, size);
+}
+
+// the encoded message of the first length delimited message in bytes, the bytes following it are ignored.
+export function readDelimited(bytes: Uint8Array): Uint8Array {
+  const prefix: Prefix | null = readPrefix(bytes, 0);
+  if (prefix === null || prefix.size + prefix.length > bytes.length) {
+    throw new Error("truncated delimited message");
+  }
+  return bytes.subarray(prefix.size, prefix.size + prefix.length);
+}
+
+// reads back to back length delimited messages, from a buffer or from the chunks of a stream.
+//
+//   const reader = new DelimitedReader<pkg_Msg>(pkg_Msg)
+//   readable.on("data", (chunk) => reader.push(chunk).forEach(handle))
+//   readable.on("end", () => reader.end())
+export class DelimitedReader<T> {
+  private type: DelimitedMessageType<T>;
+  // the chunks holding a message split across them, joined once the message is complete.
+  private chunks: Uint8Array[] = [];
+  private buffered: number = 0;
+  // the size of the split message with its prefix, -1 until its prefix is complete.
+  private needed: number = -1;
+
+  constructor(type: DelimitedMessageType<T>) {
+    this.type = type;
+  }
+
+  // decodes the messages completed by chunk, a trailing partial message waits for the next chunk.
+  push(chunk: Uint8Array): T[] {
+    if (this.buffered === 0) {
+      return this.read(chunk);
+    }
+    this.chunks.push(chunk);
+    this.buffered += chunk.length;
+    if (this.needed < 0) {
+      // the chunks hold at most the first bytes of a prefix besides chunk, joining them is cheap.
+      const prefix: Prefix | null = readPrefix(this.join(), 0);
+      if (prefix === null) {
+        return [];
+      }
+      this.needed = prefix.size + prefix.length;
+    }
+    if (this.buffered < this.needed) {
+      return [];
+    }
+    const bytes: Uint8Array = this.join();
+    this.chunks = [];
+    this.buffered = 0;
+    this.needed = -1;
+    return this.read(bytes);
+  }
+
+  // checks that the stream did not end inside of a message.
+  end(): void {
+    if (this.buffered > 0) {
+      throw new Error("truncated delimited message");
+    }
+  }
+
+  // decodes the complete messages of bytes and keeps the partial message following them.
+  private read(bytes: Uint8Array): T[] {
+    const messages: T[] = [];
+    let offset: number = 0;
+    while (offset < bytes.length) {
+      const prefix: Prefix | null = readPrefix(bytes, offset);
+      if (prefix === null || offset + prefix.size + prefix.length > bytes.length) {
+        if (prefix !== null) {
+          this.needed = prefix.size + prefix.length;
+        }
+        // copied so the reader does not keep the whole chunk alive.
+        this.chunks = [bytes.slice(offset)];
+        this.buffered = bytes.length - offset;
+        break;
+      }
+      const start: number = offset + prefix.size;
+      messages.push(this.type.fromBinary(bytes.subarray(start, start + prefix.length)));
+      offset = start + prefix.length;
+    }
+    return messages;
+  }
+
+  // the buffered chunks as one buffer, which replaces them.
+  private join(): Uint8Array {
+    if (this.chunks.length > 1) {
+      const bytes: Uint8Array = new Uint8Array(this.buffered);
+      let offset: number = 0;
+      for (const chunk of this.chunks) {
+        bytes.set(chunk, offset);
+        offset += chunk.length;
+      }
+      this.chunks = [bytes];
+    }
+    return this.chunks[0];
+  }
+
+  // every message of a buffer holding complete length delimited messages.
+  static readAll<T>(type: DelimitedMessageType<T>, bytes: Uint8Array): T[] {
+    const reader: DelimitedReader<T> = new DelimitedReader<T>(type);
+    const messages: T[] = reader.push(bytes);
+    reader.end();
+    return messages;
+  }
+}
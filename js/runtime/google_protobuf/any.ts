@@ -1,3 +1,23 @@
+export interface RegisteredMessage {
+  toJson(): unknown;
+  toBinary(): Uint8Array;
+}
+
+export interface MessageType {
+  type: string;
+  fromJson(json: unknown): RegisteredMessage;
+  fromBinary(bytes: Uint8Array): RegisteredMessage;
+}
+
+// the generated class a packed message is unpacked into.
+export interface MessageClass<T extends RegisteredMessage> {
+  type: string;
+  fromBinary(bytes: Uint8Array): T;
+}
+
+// populated by the register function of every generated file when it is loaded.
+export const type_registry: Map<string, MessageType> = new Map<string, MessageType>();
+
 class Any extends $wkt_google_protobuf_Any {
   static fromJson(json: object) {
     if (json === null || Array.isArray(json) || typeof json != "object") {
@@ -10,7 +30,7 @@ class Any extends $wkt_google_protobuf_Any {
       );
     }
     const name = type_url.substring(type_url.lastIndexOf("/") + 1);
-    const message_type = $type_registry$.get(name);
+    const message_type = type_registry.get(name);
     if (!message_type) {
       throw new Error(`unknown message type ${name}}`);
     }
@@ -18,7 +38,9 @@ class Any extends $wkt_google_protobuf_Any {
     if (name.startsWith("google.protobuf.") && "value" in json) {
       message = message_type.fromJson(json["value"]);
     } else {
-      message = message_type.fromJson(json);
+      // the packed message does not know the @type key.
+      const { "@type": _type_url, ...fields } = json as Record<string, unknown>;
+      message = message_type.fromJson(fields);
     }
     const m = new $wkt_google_protobuf_Any();
     m.type_url = `type.googleapis.com/${message_type.type}`;
@@ -30,7 +52,7 @@ class Any extends $wkt_google_protobuf_Any {
       return {};
     }
     const name = this.type_url.substring(this.type_url.lastIndexOf("/") + 1);
-    const message_type = $type_registry$.get(name);
+    const message_type = type_registry.get(name);
     if (!message_type) {
       throw new Error(`unknown message type ${this.type_url}}`);
     }
@@ -52,4 +74,29 @@ class Any extends $wkt_google_protobuf_Any {
 
     return json;
   }
+  static pack(
+    message: RegisteredMessage,
+    type_url_prefix: string = "type.googleapis.com"
+  ) {
+    const type_name = (message.constructor as MessageType).type;
+    const m = new $wkt_google_protobuf_Any();
+    m.type_url = type_url_prefix.endsWith("/")
+      ? `${type_url_prefix}${type_name}`
+      : `${type_url_prefix}/${type_name}`;
+    m.value = message.toBinary();
+    return m;
+  }
+  typeName(): string {
+    const type_url = this.type_url ?? "";
+    return type_url.substring(type_url.lastIndexOf("/") + 1);
+  }
+  is<T extends RegisteredMessage>(message_class: MessageClass<T>): boolean {
+    return this.typeName() === message_class.type;
+  }
+  unpack<T extends RegisteredMessage>(message_class: MessageClass<T>): T | undefined {
+    if (!this.is(message_class)) {
+      return undefined;
+    }
+    return message_class.fromBinary(this.value ?? new Uint8Array());
+  }
 }
@@ -0,0 +1,121 @@
+// resolves the descriptors embedded by with_descriptor=true by their full name. Every generated
+// file registers its descriptor when it is loaded, the descriptor is decoded on the first lookup.
+export class DescriptorRegistry {
+  private loaders: Map<string, () => $wkt_google_protobuf_FileDescriptorProto> = new Map<
+    string,
+    () => $wkt_google_protobuf_FileDescriptorProto
+  >();
+  private files: Map<string, $wkt_google_protobuf_FileDescriptorProto> = new Map<
+    string,
+    $wkt_google_protobuf_FileDescriptorProto
+  >();
+  private messages: Map<string, $wkt_google_protobuf_DescriptorProto> = new Map<
+    string,
+    $wkt_google_protobuf_DescriptorProto
+  >();
+  private enums: Map<string, $wkt_google_protobuf_EnumDescriptorProto> = new Map<
+    string,
+    $wkt_google_protobuf_EnumDescriptorProto
+  >();
+
+  register(name: string, load: () => $wkt_google_protobuf_FileDescriptorProto): void {
+    if (!this.files.has(name)) {
+      this.loaders.set(name, load);
+    }
+  }
+
+  // the descriptor of a proto file by its path, like "foo/bar.proto".
+  findFile(name: string): $wkt_google_protobuf_FileDescriptorProto | undefined {
+    this.indexPending();
+    return this.files.get(name);
+  }
+
+  // a message by its full name, like "foo.Bar.Baz", a leading dot is accepted.
+  findMessage(type_name: string): $wkt_google_protobuf_DescriptorProto | undefined {
+    this.indexPending();
+    return this.messages.get(DescriptorRegistry.trimDot(type_name));
+  }
+
+  // an enum by its full name, like "foo.Bar.Kind", a leading dot is accepted.
+  findEnum(type_name: string): $wkt_google_protobuf_EnumDescriptorProto | undefined {
+    this.indexPending();
+    return this.enums.get(DescriptorRegistry.trimDot(type_name));
+  }
+
+  private static trimDot(type_name: string): string {
+    return type_name.startsWith(".") ? type_name.substring(1) : type_name;
+  }
+
+  private indexPending(): void {
+    if (this.loaders.size === 0) {
+      return;
+    }
+    const pending: Array<[string, () => $wkt_google_protobuf_FileDescriptorProto]> = [];
+    this.loaders.forEach((load: () => $wkt_google_protobuf_FileDescriptorProto, name: string) => {
+      pending.push([name, load]);
+    });
+    this.loaders.clear();
+    for (const entry of pending) {
+      const file: $wkt_google_protobuf_FileDescriptorProto = entry[1]();
+      this.files.set(entry[0], file);
+      const prefix: string = file.package === undefined || file.package === "" ? "" : file.package + ".";
+      for (const e of file.enum_type) {
+        this.enums.set(prefix + (e.name ?? ""), e);
+      }
+      for (const message of file.message_type) {
+        this.indexMessage(prefix, message);
+      }
+    }
+  }
+
+  private indexMessage(prefix: string, message: $wkt_google_protobuf_DescriptorProto): void {
+    const type_name: string = prefix + (message.name ?? "");
+    this.messages.set(type_name, message);
+    for (const e of message.enum_type) {
+      this.enums.set(type_name + "." + (e.name ?? ""), e);
+    }
+    for (const nested of message.nested_type) {
+      this.indexMessage(type_name + ".", nested);
+    }
+  }
+}
+
+export const descriptor_registry: DescriptorRegistry = new DescriptorRegistry();
+
+// a field of a message by its proto name or its json name.
+export function findField(
+  message: $wkt_google_protobuf_DescriptorProto,
+  name: string
+): $wkt_google_protobuf_FieldDescriptorProto | undefined {
+  for (const field of message.field) {
+    if (field.name === name || field.json_name === name) {
+      return field;
+    }
+  }
+  return undefined;
+}
+
+export function findFieldByNumber(
+  message: $wkt_google_protobuf_DescriptorProto,
+  number: number
+): $wkt_google_protobuf_FieldDescriptorProto | undefined {
+  for (const field of message.field) {
+    if (field.number === number) {
+      return field;
+    }
+  }
+  return undefined;
+}
+
+// a value of an enum by its name or its number, the first one for aliased numbers.
+export function findEnumValue(
+  e: $wkt_google_protobuf_EnumDescriptorProto,
+  value: string | number
+): $wkt_google_protobuf_EnumValueDescriptorProto | undefined {
+  for (const v of e.value) {
+    if (v.name === value || v.number === value) {
+      return v;
+    }
+  }
+  return undefined;
+}
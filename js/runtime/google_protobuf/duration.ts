@@ -0,0 +1,69 @@
+class Duration extends $wkt_google_protobuf_Duration {
+  static fromJson(json: string) {
+    if (typeof json !== "string") {
+      throw new Error("cannot decode google.protobuf.Duration from JSON");
+    }
+    const matches = json.match(/^(-)?([0-9]+)(?:\.([0-9]{1,9}))?s$/);
+    if (!matches) {
+      throw new Error(
+        "cannot decode google.protobuf.Duration from JSON: invalid duration string"
+      );
+    }
+    const sign = matches[1] === "-" ? -1 : 1;
+    const m = new $wkt_google_protobuf_Duration();
+    m.seconds = BigInt(matches[2]) * BigInt(sign);
+    m.nanos = matches[3]
+      ? sign * parseInt((matches[3] + "00000000").substring(0, 9))
+      : 0;
+    m.checkRange();
+    return m;
+  }
+  toJson() {
+    this.checkRange();
+    const seconds = this.seconds ?? BigInt(0);
+    const nanos = this.nanos ?? 0;
+    let text = seconds.toString();
+    if (nanos !== 0) {
+      const digits = (Math.abs(nanos) + 1000000000).toString().substring(1);
+      if (digits.substring(3) === "000000") {
+        text += "." + digits.substring(0, 3);
+      } else if (digits.substring(6) === "000") {
+        text += "." + digits.substring(0, 6);
+      } else {
+        text += "." + digits;
+      }
+      // the sign of sub-second durations is only carried by nanos.
+      if (nanos < 0 && seconds === BigInt(0)) {
+        text = "-" + text;
+      }
+    }
+    return text + "s";
+  }
+  static fromMillis(millis: bigint | number) {
+    const ms = typeof millis === "bigint" ? millis : BigInt(Math.trunc(millis));
+    const m = new $wkt_google_protobuf_Duration();
+    // bigint division truncates, seconds and nanos keep the same sign.
+    m.seconds = ms / BigInt(1000);
+    m.nanos = Number(ms % BigInt(1000)) * 1000000;
+    m.checkRange();
+    return m;
+  }
+  toMillis(): number {
+    this.checkRange();
+    return Number(this.seconds ?? BigInt(0)) * 1000 + Math.trunc((this.nanos ?? 0) / 1000000);
+  }
+  private checkRange() {
+    // about +-10,000 years
+    const seconds = this.seconds ?? BigInt(0);
+    const nanos = this.nanos ?? 0;
+    if (seconds < BigInt(-315576000000) || seconds > BigInt(315576000000)) {
+      throw new Error("google.protobuf.Duration seconds out of range");
+    }
+    if (!Number.isInteger(nanos) || nanos < -999999999 || nanos > 999999999) {
+      throw new Error("google.protobuf.Duration nanos out of range");
+    }
+    if ((seconds > BigInt(0) && nanos < 0) || (seconds < BigInt(0) && nanos > 0)) {
+      throw new Error("google.protobuf.Duration seconds and nanos must have the same sign");
+    }
+  }
+}
@@ -1,4 +1,12 @@
 class FieldMask extends $wkt_google_protobuf_FieldMask {
+  static fromPaths(paths: string[]) {
+    const m = new $wkt_google_protobuf_FieldMask();
+    for (const path of paths) {
+      m.paths.push(path);
+    }
+    return m;
+  }
+
   static fromJson(json: string) {
     if (typeof json !== "string") {
       throw new Error("cannot decode google.protobuf.FieldMask");
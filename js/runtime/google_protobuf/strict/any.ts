@@ -0,0 +1,114 @@
+// the JSON of an Any is the JSON of the packed message with an additional @type key, ArkTS has
+// no index signatures so it is a record.
+export type AnyJson = Record<string, AnyJsonValue>;
+export type AnyJsonValue = null | number | string | boolean | AnyJson | AnyJsonValue[];
+
+export interface RegisteredMessage {
+  toJsonString(): string;
+  toBinary(): Uint8Array;
+}
+
+export interface MessageType {
+  type: string;
+  fromJsonString(text: string): RegisteredMessage;
+  fromBinary(bytes: Uint8Array): RegisteredMessage;
+}
+
+// the generated class a packed message is unpacked into.
+export interface MessageClass<T extends RegisteredMessage> {
+  type: string;
+  fromBinary(bytes: Uint8Array): T;
+}
+
+// populated by the register function of every generated file when it is loaded.
+export const type_registry: Map<string, MessageType> = new Map<string, MessageType>();
+
+class Any extends $wkt_google_protobuf_Any {
+  static fromJson(json: AnyJson): $wkt_google_protobuf_Any {
+    if (json === null || Array.isArray(json) || typeof json !== "object") {
+      throw new Error("cannot decode message google.protobuf.Any");
+    }
+    const type_key: string = "@type";
+    const value_key: string = "value";
+    const type_url: AnyJsonValue = json[type_key];
+    if (typeof type_url !== "string" || type_url === "") {
+      throw new Error("cannot decode message google.protobuf.Any since @type is empty");
+    }
+    const name: string = type_url.substring(type_url.lastIndexOf("/") + 1);
+    const message_type: MessageType | undefined = type_registry.get(name);
+    if (message_type === undefined) {
+      throw new Error("unknown message type " + name);
+    }
+    let message: RegisteredMessage;
+    if (name.startsWith("google.protobuf.") && json[value_key] !== undefined) {
+      message = message_type.fromJsonString(JSON.stringify(json[value_key]));
+    } else {
+      // the packed message does not know the @type key.
+      const fields: AnyJson = {};
+      for (const key of Object.keys(json)) {
+        if (key !== type_key) {
+          fields[key] = json[key];
+        }
+      }
+      message = message_type.fromJsonString(JSON.stringify(fields));
+    }
+    const m: $wkt_google_protobuf_Any = new $wkt_google_protobuf_Any();
+    m.type_url = "type.googleapis.com/" + message_type.type;
+    m.value = message.toBinary();
+    return m;
+  }
+  toJson(): AnyJson {
+    const type_key: string = "@type";
+    const value_key: string = "value";
+    const json: AnyJson = {};
+    if (!this.type_url) {
+      return json;
+    }
+    const name: string = this.type_url.substring(this.type_url.lastIndexOf("/") + 1);
+    const message_type: MessageType | undefined = type_registry.get(name);
+    if (message_type === undefined) {
+      throw new Error("unknown message type " + this.type_url);
+    }
+    const message: RegisteredMessage = message_type.fromBinary(this.value);
+    const value: AnyJsonValue = JSON.parse(message.toJsonString()) as AnyJsonValue;
+    if (
+      name.startsWith("google.protobuf.") ||
+      value === null ||
+      Array.isArray(value) ||
+      typeof value !== "object"
+    ) {
+      json[value_key] = value;
+    } else {
+      for (const key of Object.keys(value)) {
+        json[key] = value[key];
+      }
+    }
+    json[type_key] = this.type_url;
+    return json;
+  }
+  static pack(
+    message: RegisteredMessage,
+    type_url_prefix: string = "type.googleapis.com"
+  ): $wkt_google_protobuf_Any {
+    const type_name: string = (message.constructor as MessageType).type;
+    const m: $wkt_google_protobuf_Any = new $wkt_google_protobuf_Any();
+    m.type_url = type_url_prefix.endsWith("/")
+      ? type_url_prefix + type_name
+      : type_url_prefix + "/" + type_name;
+    m.value = message.toBinary();
+    return m;
+  }
+  typeName(): string {
+    const type_url: string = this.type_url ?? "";
+    return type_url.substring(type_url.lastIndexOf("/") + 1);
+  }
+  is<T extends RegisteredMessage>(message_class: MessageClass<T>): boolean {
+    return this.typeName() === message_class.type;
+  }
+  unpack<T extends RegisteredMessage>(message_class: MessageClass<T>): T | undefined {
+    if (!this.is(message_class)) {
+      return undefined;
+    }
+    return message_class.fromBinary(this.value ?? new Uint8Array());
+  }
+}
@@ -0,0 +1,71 @@
+class FieldMask extends $wkt_google_protobuf_FieldMask {
+  static fromPaths(paths: string[]): $wkt_google_protobuf_FieldMask {
+    const m: $wkt_google_protobuf_FieldMask = new $wkt_google_protobuf_FieldMask();
+    for (const path of paths) {
+      m.paths.push(path);
+    }
+    return m;
+  }
+
+  static fromJson(json: string): $wkt_google_protobuf_FieldMask {
+    if (typeof json !== "string") {
+      throw new Error("cannot decode google.protobuf.FieldMask");
+    }
+    const m: $wkt_google_protobuf_FieldMask = new $wkt_google_protobuf_FieldMask();
+    if (json === "") {
+      return m;
+    }
+    for (const path of json.split(",")) {
+      m.paths.push($wkt_google_protobuf_FieldMask.camelToSnake(path));
+    }
+    return m;
+  }
+
+  toJson(): string {
+    const paths: string[] = [];
+    for (const path of this.paths) {
+      if (path.match(/_[0-9]?_/g) || path.match(/[A-Z]/g)) {
+        throw new Error(
+          'cannot encode google.protobuf.FieldMask to JSON: lowerCamelCase of path name "' +
+            path +
+            '" is irreversible'
+        );
+      }
+      paths.push($wkt_google_protobuf_FieldMask.protoCamelCase(path));
+    }
+    return paths.join(",");
+  }
+
+  private static camelToSnake(path: string): string {
+    if (path.includes("_")) {
+      throw new Error(
+        "cannot decode google.protobuf.FieldMask from JSON: path names must be lowerCamelCase"
+      );
+    }
+    let snake: string = "";
+    for (let i = 0; i < path.length; i++) {
+      const c: string = path.charAt(i);
+      const lower: string = c.toLowerCase();
+      snake += c !== lower && i > 0 ? "_" + lower : lower;
+    }
+    return snake;
+  }
+
+  private static protoCamelCase(path: string): string {
+    let capNext: boolean = false;
+    let camel: string = "";
+    for (let i = 0; i < path.length; i++) {
+      const c: string = path.charAt(i);
+      if (c === "_") {
+        capNext = true;
+      } else if (c >= "0" && c <= "9") {
+        camel += c;
+        capNext = false;
+      } else {
+        camel += capNext ? c.toUpperCase() : c;
+        capNext = false;
+      }
+    }
+    return camel;
+  }
+}
@@ -0,0 +1,80 @@
+// plain representation of Struct, Value and ListValue used with the plain_struct option, ArkTS
+// has no index signatures so objects are records.
+export type JsonObject = Record<string, JsonValue>;
+export type JsonValue = null | number | string | boolean | JsonObject | JsonValue[];
+
+class Struct extends $wkt_google_protobuf_Struct {
+  static fromJson(json: JsonObject): $wkt_google_protobuf_Struct {
+    const m: $wkt_google_protobuf_Struct = new $wkt_google_protobuf_Struct();
+    if (typeof json !== "object" || json === null || Array.isArray(json)) {
+      throw new Error("cannot decode google.protobuf.Struct from JSON");
+    }
+    for (const key of Object.keys(json)) {
+      m.fields.set(key, $wkt_google_protobuf_Value.fromJson(json[key]));
+    }
+    return m;
+  }
+  toJson(): JsonObject {
+    const json: JsonObject = {};
+    this.fields.forEach((value: $wkt_google_protobuf_Value, key: string) => {
+      json[key] = value.toJson();
+    });
+    return json;
+  }
+}
+
+class ListValue extends $wkt_google_protobuf_ListValue {
+  static fromJson(json: JsonValue[]): $wkt_google_protobuf_ListValue {
+    const m: $wkt_google_protobuf_ListValue = new $wkt_google_protobuf_ListValue();
+    if (!Array.isArray(json)) {
+      throw new Error("cannot decode google.protobuf.ListValue from JSON");
+    }
+    for (const value of json) {
+      m.values.push($wkt_google_protobuf_Value.fromJson(value));
+    }
+    return m;
+  }
+  toJson(): JsonValue[] {
+    return this.values.map((value: $wkt_google_protobuf_Value): JsonValue => value.toJson());
+  }
+}
+
+class Value extends $wkt_google_protobuf_Value {
+  static fromJson(json: JsonValue): $wkt_google_protobuf_Value {
+    const m: $wkt_google_protobuf_Value = new $wkt_google_protobuf_Value();
+    if (json === null) {
+      m.null_value = $wkt_google_protobuf_NullValue.NULL_VALUE;
+    } else if (typeof json === "number") {
+      m.number_value = json;
+    } else if (typeof json === "string") {
+      m.string_value = json;
+    } else if (typeof json === "boolean") {
+      m.bool_value = json;
+    } else if (Array.isArray(json)) {
+      m.list_value = $wkt_google_protobuf_ListValue.fromJson(json);
+    } else if (typeof json === "object") {
+      m.struct_value = $wkt_google_protobuf_Struct.fromJson(json);
+    } else {
+      throw new Error("cannot decode google.protobuf.Value from JSON");
+    }
+    return m;
+  }
+  // a Value without a kind is written as null.
+  toJson(): JsonValue {
+    if (this.number_value !== undefined) {
+      if (!Number.isFinite(this.number_value)) {
+        throw new Error("number value cannot be NaN or Infinity");
+      }
+      return this.number_value;
+    } else if (this.string_value !== undefined) {
+      return this.string_value;
+    } else if (this.bool_value !== undefined) {
+      return this.bool_value;
+    } else if (this.struct_value !== undefined) {
+      return this.struct_value.toJson();
+    } else if (this.list_value !== undefined) {
+      return this.list_value.toJson();
+    }
+    return null;
+  }
+}
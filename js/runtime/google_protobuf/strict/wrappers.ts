@@ -0,0 +1,98 @@
+class DoubleValue extends $wkt_google_protobuf_DoubleValue {
+  static fromJson(json: number): $wkt_google_protobuf_DoubleValue {
+    const m: $wkt_google_protobuf_DoubleValue = new $wkt_google_protobuf_DoubleValue();
+    m.value = Number(json);
+    return m;
+  }
+  toJson(): number {
+    return this.value;
+  }
+}
+
+class FloatValue extends $wkt_google_protobuf_FloatValue {
+  static fromJson(json: number): $wkt_google_protobuf_FloatValue {
+    const m: $wkt_google_protobuf_FloatValue = new $wkt_google_protobuf_FloatValue();
+    m.value = Number(json);
+    return m;
+  }
+  toJson(): number {
+    return this.value;
+  }
+}
+
+class Int64Value extends $wkt_google_protobuf_Int64Value {
+  static fromJson(json: string): $wkt_google_protobuf_Int64Value {
+    const m: $wkt_google_protobuf_Int64Value = new $wkt_google_protobuf_Int64Value();
+    m.value = BigInt(json);
+    return m;
+  }
+  toJson(): string {
+    return this.value.toString();
+  }
+}
+
+class UInt64Value extends $wkt_google_protobuf_UInt64Value {
+  static fromJson(json: string): $wkt_google_protobuf_UInt64Value {
+    const m: $wkt_google_protobuf_UInt64Value = new $wkt_google_protobuf_UInt64Value();
+    m.value = BigInt(json);
+    return m;
+  }
+  toJson(): string {
+    return this.value.toString();
+  }
+}
+
+class Int32Value extends $wkt_google_protobuf_Int32Value {
+  static fromJson(json: number): $wkt_google_protobuf_Int32Value {
+    const m: $wkt_google_protobuf_Int32Value = new $wkt_google_protobuf_Int32Value();
+    m.value = json;
+    return m;
+  }
+  toJson(): number {
+    return this.value;
+  }
+}
+
+class UInt32Value extends $wkt_google_protobuf_UInt32Value {
+  static fromJson(json: number): $wkt_google_protobuf_UInt32Value {
+    const m: $wkt_google_protobuf_UInt32Value = new $wkt_google_protobuf_UInt32Value();
+    m.value = json;
+    return m;
+  }
+  toJson(): number {
+    return this.value;
+  }
+}
+
+class BoolValue extends $wkt_google_protobuf_BoolValue {
+  static fromJson(json: boolean): $wkt_google_protobuf_BoolValue {
+    const m: $wkt_google_protobuf_BoolValue = new $wkt_google_protobuf_BoolValue();
+    m.value = json;
+    return m;
+  }
+  toJson(): boolean {
+    return this.value;
+  }
+}
+
+class StringValue extends $wkt_google_protobuf_StringValue {
+  static fromJson(json: string): $wkt_google_protobuf_StringValue {
+    const m: $wkt_google_protobuf_StringValue = new $wkt_google_protobuf_StringValue();
+    m.value = json;
+    return m;
+  }
+  toJson(): string {
+    return this.value;
+  }
+}
+
+class BytesValue extends $wkt_google_protobuf_BytesValue {
+  static fromJson(json: string): $wkt_google_protobuf_BytesValue {
+    const m: $wkt_google_protobuf_BytesValue = new $wkt_google_protobuf_BytesValue();
+    m.value = $base64$.decode(json);
+    return m;
+  }
+  toJson(): string {
+    return $base64$.encode(this.value);
+  }
+}
@@ -1,3 +1,9 @@
+// plain representation of Struct, Value and ListValue used with the plain_struct option.
+export interface JsonObject {
+  [key: string]: JsonValue;
+}
+export type JsonValue = null | number | string | boolean | JsonObject | JsonValue[];
+
 class Struct extends $wkt_google_protobuf_Struct {
   static fromJson(json: unknown) {
     const m = new $wkt_google_protobuf_Struct();
@@ -0,0 +1,88 @@
+class Timestamp extends $wkt_google_protobuf_Timestamp {
+  static fromJson(json: string) {
+    if (typeof json !== "string") {
+      throw new Error("cannot decode google.protobuf.Timestamp from JSON");
+    }
+    const matches = json.match(
+      /^([0-9]{4})-([0-9]{2})-([0-9]{2})T([0-9]{2}):([0-9]{2}):([0-9]{2})(?:\.([0-9]{1,9}))?(?:Z|([+-][0-9]{2}):([0-9]{2}))$/
+    );
+    if (!matches) {
+      throw new Error(
+        "cannot decode google.protobuf.Timestamp from JSON: invalid RFC 3339 string"
+      );
+    }
+    const offset = matches[8] ? `${matches[8]}:${matches[9]}` : "Z";
+    const ms = Date.parse(
+      `${matches[1]}-${matches[2]}-${matches[3]}T${matches[4]}:${matches[5]}:${matches[6]}${offset}`
+    );
+    if (Number.isNaN(ms)) {
+      throw new Error(
+        "cannot decode google.protobuf.Timestamp from JSON: invalid RFC 3339 string"
+      );
+    }
+    const m = new $wkt_google_protobuf_Timestamp();
+    m.seconds = BigInt(Math.floor(ms / 1000));
+    m.nanos = matches[7] ? parseInt((matches[7] + "00000000").substring(0, 9)) : 0;
+    m.checkRange();
+    return m;
+  }
+  toJson() {
+    this.checkRange();
+    const ms = Number(this.seconds ?? BigInt(0)) * 1000;
+    const nanos = this.nanos ?? 0;
+    let fraction = "";
+    if (nanos > 0) {
+      const digits = (nanos + 1000000000).toString().substring(1);
+      if (digits.substring(3) === "000000") {
+        fraction = "." + digits.substring(0, 3);
+      } else if (digits.substring(6) === "000") {
+        fraction = "." + digits.substring(0, 6);
+      } else {
+        fraction = "." + digits;
+      }
+    }
+    return new Date(ms).toISOString().replace(".000Z", fraction + "Z");
+  }
+  static now() {
+    return $wkt_google_protobuf_Timestamp.fromMillis(Date.now());
+  }
+  static fromDate(date: Date) {
+    const ms = date.getTime();
+    if (Number.isNaN(ms)) {
+      throw new Error("cannot convert an invalid Date to google.protobuf.Timestamp");
+    }
+    return $wkt_google_protobuf_Timestamp.fromMillis(ms);
+  }
+  static fromMillis(millis: bigint | number) {
+    const ms = typeof millis === "bigint" ? millis : BigInt(Math.floor(millis));
+    // nanos are never negative, instants before the epoch borrow from seconds.
+    let seconds = ms / BigInt(1000);
+    let remainder = ms % BigInt(1000);
+    if (remainder < BigInt(0)) {
+      remainder += BigInt(1000);
+      seconds -= BigInt(1);
+    }
+    const m = new $wkt_google_protobuf_Timestamp();
+    m.seconds = seconds;
+    m.nanos = Number(remainder) * 1000000;
+    m.checkRange();
+    return m;
+  }
+  toDate(): Date {
+    this.checkRange();
+    return new Date(
+      Number(this.seconds ?? BigInt(0)) * 1000 + Math.floor((this.nanos ?? 0) / 1000000)
+    );
+  }
+  private checkRange() {
+    // 0001-01-01T00:00:00Z to 9999-12-31T23:59:59.999999999Z
+    const seconds = this.seconds ?? BigInt(0);
+    const nanos = this.nanos ?? 0;
+    if (seconds < BigInt(-62135596800) || seconds > BigInt(253402300799)) {
+      throw new Error("google.protobuf.Timestamp seconds out of range");
+    }
+    if (!Number.isInteger(nanos) || nanos < 0 || nanos > 999999999) {
+      throw new Error("google.protobuf.Timestamp nanos out of range");
+    }
+  }
+}
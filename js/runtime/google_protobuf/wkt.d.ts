@@ -3,10 +3,6 @@ declare const $base64$: {
   decode(v: string): Uint8Array;
 };
 
-declare const $type_registry$: {
-    get(qualified_name: string): typeof Message;
-}
-
 
 // message
 declare abstract class Message<JsonRepr> {
@@ -93,4 +89,36 @@ declare class $wkt_google_protobuf_Int32Value extends $wkt_Value<number, number,
 declare class $wkt_google_protobuf_UInt32Value extends $wkt_Value<number, number, number>() {}
 declare class $wkt_google_protobuf_BoolValue extends $wkt_Value<boolean, boolean, boolean>() {}
 declare class $wkt_google_protobuf_StringValue extends $wkt_Value<string, string, string>() {}
-declare class $wkt_google_protobuf_BytesValue extends $wkt_Value<Uint8Array, string, string>() {}
\ No newline at end of file
+declare class $wkt_google_protobuf_BytesValue extends $wkt_Value<Uint8Array, string, string>() {}
+
+
+// descriptor.ts
+declare class $wkt_google_protobuf_FileDescriptorProto extends $wkt_Message<object>() {
+    name?: string;
+    package?: string;
+    message_type: $wkt_google_protobuf_DescriptorProto[];
+    enum_type: $wkt_google_protobuf_EnumDescriptorProto[];
+}
+
+declare class $wkt_google_protobuf_DescriptorProto extends $wkt_Message<object>() {
+    name?: string;
+    field: $wkt_google_protobuf_FieldDescriptorProto[];
+    nested_type: $wkt_google_protobuf_DescriptorProto[];
+    enum_type: $wkt_google_protobuf_EnumDescriptorProto[];
+}
+
+declare class $wkt_google_protobuf_FieldDescriptorProto extends $wkt_Message<object>() {
+    name?: string;
+    number?: number;
+    json_name?: string;
+}
+
+declare class $wkt_google_protobuf_EnumDescriptorProto extends $wkt_Message<object>() {
+    name?: string;
+    value: $wkt_google_protobuf_EnumValueDescriptorProto[];
+}
+
+declare class $wkt_google_protobuf_EnumValueDescriptorProto extends $wkt_Message<object>() {
+    name?: string;
+    number?: number;
+}
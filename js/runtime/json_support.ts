@@ -0,0 +1,114 @@
+// options of the generated toJson methods.
+export interface JsonWriteOptions {
+  // write fields without presence even when they hold their default value.
+  emitDefaultValues?: boolean;
+  // write enum values by number instead of by name.
+  enumAsInteger?: boolean;
+  // use the proto field names instead of the lowerCamelCase json names.
+  useProtoFieldName?: boolean;
+  // write the keys of map fields in the order of the map keys instead of in insertion order.
+  deterministic?: boolean;
+}
+
+// options of the generated fromJson methods.
+export interface JsonReadOptions {
+  // skip unknown enum value names, and unknown fields under strict, instead of throwing.
+  ignoreUnknownFields?: boolean;
+  // reject unknown fields, check the type and range of every value and reject duplicate fields and oneof members.
+  strict?: boolean;
+}
+
+// thrown by the generated fromJson methods, the path locates the offending value like $.items[3].price.
+export class ProtoJsonError extends Error {
+  path: string;
+
+  constructor(path: string, message: string) {
+    super(path + ": " + message);
+    this.name = "ProtoJsonError";
+    this.path = path;
+  }
+}
+
+// options of the generated toJsonString methods.
+export interface JsonStringifyOptions extends JsonWriteOptions {
+  // the number of spaces to indent nested values with, the output is compact without it.
+  indent?: number;
+}
+
+function isInteger(token: string): boolean {
+  const start: number = token.charAt(0) === "-" ? 1 : 0;
+  if (token.length === start) {
+    return false;
+  }
+  for (let i = start; i < token.length; i++) {
+    const c: string = token.charAt(i);
+    if (c < "0" || c > "9") {
+      return false;
+    }
+  }
+  return true;
+}
+
+// JSON.parse reads every number as a double, so integers beyond 2^53 lose precision before
+// fromJson sees them. Such integers are quoted first where isInt64Path tells a 64-bit integer field
+// is read, the 64-bit integer parsers accept strings. Numbers of other fields, Value included, stay
+// numbers. The path holds the keys of the objects enclosing the number, array indices are left out.
+function quoteUnsafeIntegers(text: string, isInt64Path: (path: string[]) => boolean): string {
+  let out: string = "";
+  let start: number = 0;
+  let i: number = 0;
+  const path: string[] = [];
+  // true for an object and false for an array, for each container enclosing the position.
+  const objects: boolean[] = [];
+  while (i < text.length) {
+    const c: string = text.charAt(i);
+    if (c === '"') {
+      // skips the string, escaped quotes included.
+      const begin: number = i;
+      i++;
+      while (i < text.length && text.charAt(i) !== '"') {
+        i += text.charAt(i) === "\\" ? 2 : 1;
+      }
+      i++;
+      let next: number = i;
+      while (next < text.length && " \t\n\r".indexOf(text.charAt(next)) !== -1) {
+        next++;
+      }
+      if (objects.length > 0 && objects[objects.length - 1] && text.charAt(next) === ":") {
+        path[path.length - 1] = JSON.parse(text.substring(begin, i)) as string;
+      }
+    } else if (c === "{") {
+      objects.push(true);
+      path.push("");
+      i++;
+    } else if (c === "[") {
+      objects.push(false);
+      i++;
+    } else if (c === "}" || c === "]") {
+      if (objects.pop() === true) {
+        path.pop();
+      }
+      i++;
+    } else if (c === "-" || (c >= "0" && c <= "9")) {
+      let end: number = i + 1;
+      while (end < text.length && "0123456789.eE+-".indexOf(text.charAt(end)) !== -1) {
+        end++;
+      }
+      const token: string = text.substring(i, end);
+      if (isInteger(token) && !Number.isSafeInteger(Number(token)) && isInt64Path(path)) {
+        out += text.substring(start, i) + '"' + token + '"';
+        start = end;
+      }
+      i = end;
+    } else {
+      i++;
+    }
+  }
+  return out + text.substring(start);
+}
+
+// parses the text of a generated fromJsonString without losing the precision of 64-bit integer
+// fields, isInt64Path is the isInt64Path of the message read from the text.
+export function parseJson(text: string, isInt64Path: (path: string[]) => boolean): Object {
+  return JSON.parse(quoteUnsafeIntegers(text, isInt64Path)) as Object;
+}
@@ -0,0 +1,281 @@
+// sizes and writer of the generated byteSize, writeTo and toBinaryInto methods.
+
+const TWO_32: bigint = BigInt(4294967296);
+const SHIFT_32: bigint = BigInt(32);
+const SHIFT_63: bigint = BigInt(63);
+const ONE: bigint = BigInt(1);
+
+// the encoded size of an unsigned 32-bit varint.
+export function uint32Size(value: number): number {
+  value = value >>> 0;
+  if (value < 0x80) {
+    return 1;
+  }
+  if (value < 0x4000) {
+    return 2;
+  }
+  if (value < 0x200000) {
+    return 3;
+  }
+  if (value < 0x10000000) {
+    return 4;
+  }
+  return 5;
+}
+
+// negative int32 values are sign extended to 64 bits.
+export function int32Size(value: number): number {
+  return value < 0 ? 10 : uint32Size(value);
+}
+
+export function sint32Size(value: number): number {
+  return uint32Size((value << 1) ^ (value >> 31));
+}
+
+export function uint64Size(value: bigint): number {
+  const unsigned: bigint = BigInt.asUintN(64, value);
+  const hi: number = Number(unsigned >> SHIFT_32);
+  if (hi === 0) {
+    return uint32Size(Number(unsigned));
+  }
+  // every byte holds 7 bits, the low 32 bits take the first 4 bytes and 4 bits of the 5th.
+  if (hi < 0x8) {
+    return 5;
+  }
+  let size: number = 6;
+  for (let rest: number = hi >>> 10; rest > 0; rest = rest >>> 7) {
+    size++;
+  }
+  return size;
+}
+
+export function int64Size(value: bigint): number {
+  return uint64Size(value);
+}
+
+export function sint64Size(value: bigint): number {
+  return uint64Size((value << ONE) ^ (value >> SHIFT_63));
+}
+
+// the size of a length delimited value, its length prefix included.
+export function delimitedSize(length: number): number {
+  return uint32Size(length) + length;
+}
+
+// the utf-8 encoded length of a string, lone surrogates count as U+FFFD like TextEncoder.
+function utf8Length(value: string): number {
+  let length: number = 0;
+  for (let i: number = 0; i < value.length; i++) {
+    const c: number = value.charCodeAt(i);
+    if (c < 0x80) {
+      length += 1;
+    } else if (c < 0x800) {
+      length += 2;
+    } else if (c >= 0xd800 && c <= 0xdbff && i + 1 < value.length && isLowSurrogate(value.charCodeAt(i + 1))) {
+      length += 4;
+      i++;
+    } else {
+      length += 3;
+    }
+  }
+  return length;
+}
+
+function isLowSurrogate(c: number): boolean {
+  return c >= 0xdc00 && c <= 0xdfff;
+}
+
+export function stringSize(value: string): number {
+  return delimitedSize(utf8Length(value));
+}
+
+// the sizes byteSize computed for the nested messages and the messages it converted from plain
+// values, keyed by the value, so that writeTo does not compute them again. The sizes go stale
+// once a message changes, so a cache serves a single byteSize and writeTo pair and must not be
+// kept across mutations; toBinaryInto creates a fresh one on every call.
+export class SizeCache {
+  private sizes: Map<Object, number> = new Map<Object, number>();
+  private messages: Map<Object | null, Object> = new Map<Object | null, Object>();
+
+  setSize(message: Object, size: number): void {
+    this.sizes.set(message, size);
+  }
+
+  getSize(message: Object): number | undefined {
+    return this.sizes.get(message);
+  }
+
+  setMessage(plain: Object | null, message: Object): void {
+    this.messages.set(plain, message);
+  }
+
+  getMessage(plain: Object | null): Object | undefined {
+    return this.messages.get(plain);
+  }
+}
+
+// the size byteSize computed for a nested message, recorded in the cache when there is one.
+export function recordSize(cache: SizeCache | undefined, message: Object, size: number): number {
+  if (cache !== undefined) {
+    cache.setSize(message, size);
+  }
+  return size;
+}
+
+// writes the binary format into a buffer allocated for the exact size of a message, see byteSize.
+export class SizedWriter {
+  private buffer: Uint8Array;
+  private view: DataView;
+  private start: number;
+  private end: number;
+  private pos: number;
+  private cache?: SizeCache;
+
+  // writes size bytes at offset of buffer, a buffer of that size is allocated without one. The
+  // cache holds the sizes computed along with size, the nested sizes are computed again without it.
+  constructor(size: number, buffer?: Uint8Array, offset?: number, cache?: SizeCache) {
+    this.cache = cache;
+    this.buffer = buffer ?? new Uint8Array(size);
+    this.start = offset ?? 0;
+    this.end = this.start + size;
+    this.pos = this.start;
+    if (this.start < 0 || this.end > this.buffer.length) {
+      throw new RangeError(
+        "message of " + size + " bytes does not fit at offset " + this.start + " of a buffer of " + this.buffer.length + " bytes"
+      );
+    }
+    this.view = new DataView(this.buffer.buffer, this.buffer.byteOffset, this.buffer.byteLength);
+  }
+
+  // the bytes written, checking that they match the size the writer was created with.
+  finish(): Uint8Array {
+    if (this.pos !== this.end) {
+      throw new Error("wrote " + (this.pos - this.start) + " bytes instead of " + (this.end - this.start));
+    }
+    return this.buffer.subarray(this.start, this.end);
+  }
+
+  // the size byteSize recorded for a nested message, undefined when it did not.
+  cachedSize(message: Object): number | undefined {
+    return this.cache?.getSize(message);
+  }
+
+  // the message byteSize converted from a plain value, undefined when it did not.
+  convertedMessage(plain: Object | null): Object | undefined {
+    return this.cache?.getMessage(plain);
+  }
+
+  writeTag(field: number, wireType: number): void {
+    this.writeUint32(((field << 3) | wireType) >>> 0);
+  }
+
+  writeUint32(value: number): void {
+    value = value >>> 0;
+    while (value >= 0x80) {
+      this.buffer[this.pos++] = (value & 0x7f) | 0x80;
+      value = value >>> 7;
+    }
+    this.buffer[this.pos++] = value;
+  }
+
+  writeInt32(value: number): void {
+    if (value < 0) {
+      this.writeSplitVarint(value >>> 0, 0xffffffff);
+    } else {
+      this.writeUint32(value);
+    }
+  }
+
+  writeSint32(value: number): void {
+    this.writeUint32((value << 1) ^ (value >> 31));
+  }
+
+  writeUint64(value: bigint): void {
+    const unsigned: bigint = BigInt.asUintN(64, value);
+    this.writeSplitVarint(Number(unsigned % TWO_32), Number(unsigned >> SHIFT_32));
+  }
+
+  writeInt64(value: bigint): void {
+    this.writeUint64(value);
+  }
+
+  writeSint64(value: bigint): void {
+    this.writeUint64((value << ONE) ^ (value >> SHIFT_63));
+  }
+
+  private writeSplitVarint(lo: number, hi: number): void {
+    while (hi > 0 || lo >= 0x80) {
+      this.buffer[this.pos++] = (lo & 0x7f) | 0x80;
+      lo = ((lo >>> 7) | (hi << 25)) >>> 0;
+      hi = hi >>> 7;
+    }
+    this.buffer[this.pos++] = lo;
+  }
+
+  writeBool(value: boolean): void {
+    this.buffer[this.pos++] = value ? 1 : 0;
+  }
+
+  writeFixed32(value: number): void {
+    this.view.setUint32(this.pos, value >>> 0, true);
+    this.pos += 4;
+  }
+
+  writeSfixed32(value: number): void {
+    this.view.setInt32(this.pos, value, true);
+    this.pos += 4;
+  }
+
+  writeFixed64(value: bigint): void {
+    this.view.setBigUint64(this.pos, BigInt.asUintN(64, value), true);
+    this.pos += 8;
+  }
+
+  writeSfixed64(value: bigint): void {
+    this.view.setBigInt64(this.pos, BigInt.asIntN(64, value), true);
+    this.pos += 8;
+  }
+
+  writeFloat(value: number): void {
+    this.view.setFloat32(this.pos, value, true);
+    this.pos += 4;
+  }
+
+  writeDouble(value: number): void {
+    this.view.setFloat64(this.pos, value, true);
+    this.pos += 8;
+  }
+
+  writeString(value: string): void {
+    this.writeUint32(utf8Length(value));
+    for (let i: number = 0; i < value.length; i++) {
+      let c: number = value.charCodeAt(i);
+      if (c >= 0xd800 && c <= 0xdbff && i + 1 < value.length && isLowSurrogate(value.charCodeAt(i + 1))) {
+        c = 0x10000 + ((c - 0xd800) << 10) + (value.charCodeAt(++i) - 0xdc00);
+      } else if (c >= 0xd800 && c <= 0xdfff) {
+        c = 0xfffd;
+      }
+      if (c < 0x80) {
+        this.buffer[this.pos++] = c;
+      } else if (c < 0x800) {
+        this.buffer[this.pos++] = 0xc0 | (c >> 6);
+        this.buffer[this.pos++] = 0x80 | (c & 0x3f);
+      } else if (c < 0x10000) {
+        this.buffer[this.pos++] = 0xe0 | (c >> 12);
+        this.buffer[this.pos++] = 0x80 | ((c >> 6) & 0x3f);
+        this.buffer[this.pos++] = 0x80 | (c & 0x3f);
+      } else {
+        this.buffer[this.pos++] = 0xf0 | (c >> 18);
+        this.buffer[this.pos++] = 0x80 | ((c >> 12) & 0x3f);
+        this.buffer[this.pos++] = 0x80 | ((c >> 6) & 0x3f);
+        this.buffer[this.pos++] = 0x80 | (c & 0x3f);
+      }
+    }
+  }
+
+  writeBytes(value: Uint8Array): void {
+    this.writeUint32(value.length);
+    this.buffer.set(value, this.pos);
+    this.pos += value.length;
+  }
+}
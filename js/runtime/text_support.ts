@@ -0,0 +1,657 @@
+// thrown by the generated fromText methods, line and column locate the offending token.
+export class ProtoTextError extends Error {
+  line: number;
+  column: number;
+
+  constructor(line: number, column: number, message: string) {
+    super(line + ":" + column + ": " + message);
+    this.name = "ProtoTextError";
+    this.line = line;
+    this.column = column;
+  }
+}
+
+// implemented by every generated message when the text format is enabled.
+export interface TextMessage {
+  writeText(w: TextWriter): void;
+  mergeText(r: TextReader): void;
+  toBinary(): Uint8Array;
+}
+
+export interface TextMessageType {
+  type: string;
+  fromBinary(bytes: Uint8Array): TextMessage;
+}
+
+// populated by the register function of every generated file, expands google.protobuf.Any.
+export const text_registry: Map<string, TextMessageType> = new Map<string, TextMessageType>();
+
+// the fields of google.protobuf.Any.
+export interface AnyMessage {
+  type_url: string;
+  value: Uint8Array;
+}
+
+function encodeUtf8(text: string): number[] {
+  const bytes: number[] = [];
+  for (let i = 0; i < text.length; i++) {
+    let c: number = text.charCodeAt(i);
+    if (c >= 0xd800 && c < 0xdc00 && i + 1 < text.length) {
+      const low: number = text.charCodeAt(i + 1);
+      if (low >= 0xdc00 && low < 0xe000) {
+        c = 0x10000 + ((c - 0xd800) << 10) + (low - 0xdc00);
+        i++;
+      }
+    }
+    if (c < 0x80) {
+      bytes.push(c);
+    } else if (c < 0x800) {
+      bytes.push(0xc0 | (c >> 6), 0x80 | (c & 0x3f));
+    } else if (c < 0x10000) {
+      bytes.push(0xe0 | (c >> 12), 0x80 | ((c >> 6) & 0x3f), 0x80 | (c & 0x3f));
+    } else {
+      bytes.push(0xf0 | (c >> 18), 0x80 | ((c >> 12) & 0x3f), 0x80 | ((c >> 6) & 0x3f), 0x80 | (c & 0x3f));
+    }
+  }
+  return bytes;
+}
+
+function decodeUtf8(bytes: number[]): string {
+  let out: string = "";
+  let i: number = 0;
+  while (i < bytes.length) {
+    const b: number = bytes[i++];
+    let c: number = b;
+    let continuation: number = 0;
+    if (b >= 0xf0) {
+      c = b & 0x07;
+      continuation = 3;
+    } else if (b >= 0xe0) {
+      c = b & 0x0f;
+      continuation = 2;
+    } else if (b >= 0xc0) {
+      c = b & 0x1f;
+      continuation = 1;
+    }
+    for (let k = 0; k < continuation && i < bytes.length; k++) {
+      c = (c << 6) | (bytes[i++] & 0x3f);
+    }
+    out += String.fromCodePoint(c);
+  }
+  return out;
+}
+
+function octal(c: number): string {
+  return "\\" + ("00" + c.toString(8)).slice(-3);
+}
+
+function escapeChar(c: number): string | undefined {
+  switch (c) {
+    case 0x0a:
+      return "\\n";
+    case 0x0d:
+      return "\\r";
+    case 0x09:
+      return "\\t";
+    case 0x22:
+      return '\\"';
+    case 0x27:
+      return "\\'";
+    case 0x5c:
+      return "\\\\";
+  }
+  return undefined;
+}
+
+// non-ascii characters are kept as they are, control characters are escaped.
+function quoteString(value: string): string {
+  let out: string = '"';
+  for (let i = 0; i < value.length; i++) {
+    const c: number = value.charCodeAt(i);
+    const escaped: string | undefined = escapeChar(c);
+    if (escaped !== undefined) {
+      out += escaped;
+    } else if (c < 0x20 || c === 0x7f) {
+      out += octal(c);
+    } else {
+      out += value.charAt(i);
+    }
+  }
+  return out + '"';
+}
+
+function quoteBytes(value: Uint8Array): string {
+  let out: string = '"';
+  for (let i = 0; i < value.length; i++) {
+    const c: number = value[i];
+    const escaped: string | undefined = escapeChar(c);
+    if (escaped !== undefined) {
+      out += escaped;
+    } else if (c < 0x20 || c >= 0x7f) {
+      out += octal(c);
+    } else {
+      out += String.fromCharCode(c);
+    }
+  }
+  return out + '"';
+}
+
+function formatNumber(value: number): string {
+  if (Number.isNaN(value)) {
+    return "nan";
+  }
+  if (value === Infinity) {
+    return "inf";
+  }
+  if (value === -Infinity) {
+    return "-inf";
+  }
+  return String(value);
+}
+
+// writes the text format of the generated toText methods, one field per line.
+export class TextWriter {
+  private out: string = "";
+  private indent: string = "";
+
+  private line(text: string): void {
+    this.out += this.indent + text + "\n";
+  }
+
+  writeString(name: string, value: string): void {
+    this.line(name + ": " + quoteString(value));
+  }
+
+  writeBytes(name: string, value: Uint8Array): void {
+    this.line(name + ": " + quoteBytes(value));
+  }
+
+  writeBool(name: string, value: boolean): void {
+    this.line(name + ": " + (value ? "true" : "false"));
+  }
+
+  writeNumber(name: string, value: number): void {
+    this.line(name + ": " + formatNumber(value));
+  }
+
+  writeBigInt(name: string, value: bigint): void {
+    this.line(name + ": " + value.toString());
+  }
+
+  // enum values are written by name, unknown values by number.
+  writeEnum(name: string, value: string | number): void {
+    this.line(name + ": " + value.toString());
+  }
+
+  writeMessage(name: string, message: TextMessage): void {
+    this.beginMessage(name);
+    message.writeText(this);
+    this.endMessage();
+  }
+
+  beginMessage(name: string): void {
+    this.line(name + " {");
+    this.indent += "  ";
+  }
+
+  endMessage(): void {
+    this.indent = this.indent.substring(2);
+    this.line("}");
+  }
+
+  toString(): string {
+    return this.out;
+  }
+}
+
+function isIdentifierStart(c: string): boolean {
+  return (c >= "a" && c <= "z") || (c >= "A" && c <= "Z") || c === "_";
+}
+
+function isIdentifierChar(c: string): boolean {
+  return isIdentifierStart(c) || (c >= "0" && c <= "9");
+}
+
+function isIdentifier(token: string): boolean {
+  if (token.length === 0 || !isIdentifierStart(token.charAt(0))) {
+    return false;
+  }
+  for (let i = 1; i < token.length; i++) {
+    if (!isIdentifierChar(token.charAt(i))) {
+      return false;
+    }
+  }
+  return true;
+}
+
+function isQuoted(token: string): boolean {
+  return token.startsWith('"') || token.startsWith("'");
+}
+
+const INT32_MIN: bigint = BigInt("-2147483648");
+const INT32_MAX: bigint = BigInt("2147483647");
+const UINT32_MAX: bigint = BigInt("4294967295");
+const INT64_MIN: bigint = BigInt("-9223372036854775808");
+const INT64_MAX: bigint = BigInt("9223372036854775807");
+const UINT64_MAX: bigint = BigInt("18446744073709551615");
+
+// reads the text format for the generated fromText methods.
+export class TextReader {
+  private text: string;
+  private pos: number = 0;
+  private line: number = 1;
+  private lineStart: number = 0;
+  // where the last peeked token starts, errors are reported there.
+  private tokenLine: number = 1;
+  private tokenColumn: number = 1;
+  // the closing delimiters of the messages being read.
+  private closers: string[] = [];
+
+  constructor(text: string) {
+    this.text = text;
+  }
+
+  error(message: string): ProtoTextError {
+    return new ProtoTextError(this.tokenLine, this.tokenColumn, message);
+  }
+
+  private skipWhitespace(): void {
+    while (this.pos < this.text.length) {
+      const c: string = this.text.charAt(this.pos);
+      if (c === "#") {
+        while (this.pos < this.text.length && this.text.charAt(this.pos) !== "\n") {
+          this.pos++;
+        }
+      } else if (c === "\n") {
+        this.pos++;
+        this.line++;
+        this.lineStart = this.pos;
+      } else if (c === " " || c === "\t" || c === "\r" || c === "\f" || c === "\v") {
+        this.pos++;
+      } else {
+        return;
+      }
+    }
+  }
+
+  // the next token without consuming it, empty at the end of the text.
+  peek(): string {
+    this.skipWhitespace();
+    this.tokenLine = this.line;
+    this.tokenColumn = this.pos - this.lineStart + 1;
+    if (this.pos >= this.text.length) {
+      return "";
+    }
+    const c: string = this.text.charAt(this.pos);
+    let end: number = this.pos + 1;
+    if (isQuoted(c)) {
+      while (end < this.text.length && this.text.charAt(end) !== c && this.text.charAt(end) !== "\n") {
+        end += this.text.charAt(end) === "\\" ? 2 : 1;
+      }
+      if (end >= this.text.length || this.text.charAt(end) !== c) {
+        throw this.error("unterminated string");
+      }
+      return this.text.substring(this.pos, end + 1);
+    }
+    if (isIdentifierChar(c) || c === ".") {
+      const numeric: boolean = !isIdentifierStart(c);
+      while (end < this.text.length) {
+        const next: string = this.text.charAt(end);
+        const exponent: string = this.text.charAt(end - 1);
+        if (isIdentifierChar(next) || next === ".") {
+          end++;
+        } else if (numeric && (next === "+" || next === "-") && (exponent === "e" || exponent === "E")) {
+          end++;
+        } else {
+          break;
+        }
+      }
+      return this.text.substring(this.pos, end);
+    }
+    return c;
+  }
+
+  next(): string {
+    const token: string = this.peek();
+    this.pos += token.length;
+    return token;
+  }
+
+  tryConsume(token: string): boolean {
+    if (this.peek() !== token) {
+      return false;
+    }
+    this.pos += token.length;
+    return true;
+  }
+
+  consume(token: string): void {
+    if (!this.tryConsume(token)) {
+      throw this.error('expected "' + token + '" but found "' + this.peek() + '"');
+    }
+  }
+
+  // the name of the next field, `[type.googleapis.com/pkg.Msg]` for an expanded google.protobuf.Any.
+  readFieldName(): string {
+    if (this.tryConsume("[")) {
+      let name: string = "";
+      while (!this.tryConsume("]")) {
+        const token: string = this.next();
+        if (token === "") {
+          throw this.error("unterminated type url");
+        }
+        name += token;
+      }
+      return "[" + name + "]";
+    }
+    const name: string = this.peek();
+    if (!isIdentifier(name)) {
+      throw this.error('expected a field name but found "' + name + '"');
+    }
+    this.pos += name.length;
+    return name;
+  }
+
+  beginMessage(): void {
+    if (this.tryConsume("{")) {
+      this.closers.push("}");
+    } else if (this.tryConsume("<")) {
+      this.closers.push(">");
+    } else {
+      throw this.error('expected "{" but found "' + this.peek() + '"');
+    }
+  }
+
+  // skips the separator after a field, true once the current message or the text ends.
+  endMessage(): boolean {
+    if (!this.tryConsume(",")) {
+      this.tryConsume(";");
+    }
+    if (this.closers.length === 0) {
+      return this.peek() === "";
+    }
+    if (this.tryConsume(this.closers[this.closers.length - 1])) {
+      this.closers.pop();
+      return true;
+    }
+    if (this.peek() === "") {
+      throw this.error("unexpected end of text");
+    }
+    return false;
+  }
+
+  // a single value or a `[a, b]` list of values of a repeated field.
+  readList<T>(read: () => T): T[] {
+    const values: T[] = [];
+    if (!this.tryConsume("[")) {
+      values.push(read());
+      return values;
+    }
+    if (this.tryConsume("]")) {
+      return values;
+    }
+    do {
+      values.push(read());
+    } while (this.tryConsume(","));
+    this.consume("]");
+    return values;
+  }
+
+  // the `{ value: V }` message of an unboxed wrapper type.
+  readWrapper<T>(value: T, read: () => T): T {
+    this.beginMessage();
+    let result: T = value;
+    while (!this.endMessage()) {
+      const name: string = this.readFieldName();
+      if (name !== "value") {
+        throw this.error("unknown field " + name + " of a wrapper type");
+      }
+      this.consume(":");
+      result = read();
+    }
+    return result;
+  }
+
+  // one or a list of `{ key: K value: V }` entries, missing keys and values read as the given defaults.
+  readMapEntries<K, V>(key: K, value: V, readKey: () => K, readValue: () => V, add: (key: K, value: V) => void): void {
+    this.readList<boolean>((): boolean => {
+      this.beginMessage();
+      let entryKey: K = key;
+      let entryValue: V = value;
+      while (!this.endMessage()) {
+        const name: string = this.readFieldName();
+        if (name === "key") {
+          this.consume(":");
+          entryKey = readKey();
+        } else if (name === "value") {
+          this.tryConsume(":");
+          entryValue = readValue();
+        } else {
+          throw this.error("unknown field " + name + " of a map entry");
+        }
+      }
+      add(entryKey, entryValue);
+      return true;
+    });
+  }
+
+  private unescape(token: string, bytes: number[]): void {
+    let i: number = 1;
+    while (i < token.length - 1) {
+      const c: string = token.charAt(i++);
+      if (c !== "\\") {
+        const code: number = token.codePointAt(i - 1) ?? 0;
+        if (code > 0xffff) {
+          i++;
+        }
+        encodeUtf8(String.fromCodePoint(code)).forEach((b: number) => bytes.push(b));
+        continue;
+      }
+      const e: string = token.charAt(i++);
+      switch (e) {
+        case "n":
+          bytes.push(0x0a);
+          break;
+        case "r":
+          bytes.push(0x0d);
+          break;
+        case "t":
+          bytes.push(0x09);
+          break;
+        case "a":
+          bytes.push(0x07);
+          break;
+        case "b":
+          bytes.push(0x08);
+          break;
+        case "f":
+          bytes.push(0x0c);
+          break;
+        case "v":
+          bytes.push(0x0b);
+          break;
+        case "x": {
+          let end: number = i;
+          while (end < token.length - 1 && end < i + 2 && "0123456789abcdefABCDEF".indexOf(token.charAt(end)) !== -1) {
+            end++;
+          }
+          if (end === i) {
+            throw this.error("invalid escape \\x");
+          }
+          bytes.push(parseInt(token.substring(i, end), 16));
+          i = end;
+          break;
+        }
+        case "u":
+        case "U": {
+          const length: number = e === "u" ? 4 : 8;
+          const code: number = parseInt(token.substring(i, i + length), 16);
+          if (Number.isNaN(code) || code > 0x10ffff) {
+            throw this.error("invalid escape \\" + e);
+          }
+          encodeUtf8(String.fromCodePoint(code)).forEach((b: number) => bytes.push(b));
+          i += length;
+          break;
+        }
+        default:
+          if (e >= "0" && e <= "7") {
+            let end: number = i;
+            while (end < token.length - 1 && end < i + 2 && token.charAt(end) >= "0" && token.charAt(end) <= "7") {
+              end++;
+            }
+            bytes.push(parseInt(token.substring(i - 1, end), 8) & 0xff);
+            i = end;
+          } else if (e === '"' || e === "'" || e === "\\" || e === "?") {
+            bytes.push(e.charCodeAt(0));
+          } else {
+            throw this.error("invalid escape \\" + e);
+          }
+      }
+    }
+  }
+
+  // adjacent string literals are concatenated.
+  private readQuoted(): number[] {
+    let token: string = this.peek();
+    if (!isQuoted(token)) {
+      throw this.error('expected a string but found "' + token + '"');
+    }
+    const bytes: number[] = [];
+    while (isQuoted(token)) {
+      this.pos += token.length;
+      this.unescape(token, bytes);
+      token = this.peek();
+    }
+    return bytes;
+  }
+
+  readString(): string {
+    return decodeUtf8(this.readQuoted());
+  }
+
+  readBytes(): Uint8Array {
+    return new Uint8Array(this.readQuoted());
+  }
+
+  readBool(): boolean {
+    const token: string = this.next();
+    if (token === "true" || token === "True" || token === "t" || token === "1") {
+      return true;
+    }
+    if (token === "false" || token === "False" || token === "f" || token === "0") {
+      return false;
+    }
+    throw this.error('expected a bool but found "' + token + '"');
+  }
+
+  readFloat(): number {
+    const negative: boolean = this.tryConsume("-");
+    const token: string = this.next();
+    const lower: string = token.toLowerCase();
+    let value: number;
+    if (lower === "inf" || lower === "infinity") {
+      value = Infinity;
+    } else if (lower === "nan") {
+      value = NaN;
+    } else {
+      const digits: string = lower.endsWith("f") && !lower.startsWith("0x") ? token.substring(0, token.length - 1) : token;
+      value = Number(digits);
+      if (digits === "" || Number.isNaN(value)) {
+        throw this.error('expected a number but found "' + token + '"');
+      }
+    }
+    return negative ? -value : value;
+  }
+
+  private readInteger(type: string, min: bigint, max: bigint): bigint {
+    const negative: boolean = this.tryConsume("-");
+    const token: string = this.next();
+    let radix: number = 10;
+    let digits: string = token;
+    if (token.startsWith("0x") || token.startsWith("0X")) {
+      radix = 16;
+      digits = token.substring(2);
+    } else if (token.length > 1 && token.startsWith("0")) {
+      radix = 8;
+      digits = token.substring(1);
+    }
+    let value: bigint = BigInt(0);
+    for (let i = 0; i < digits.length; i++) {
+      const digit: number = parseInt(digits.charAt(i), radix);
+      if (Number.isNaN(digit)) {
+        digits = "";
+        break;
+      }
+      value = value * BigInt(radix) + BigInt(digit);
+    }
+    if (digits === "") {
+      throw this.error("expected an " + type + ' value but found "' + token + '"');
+    }
+    if (negative) {
+      value = -value;
+    }
+    if (value < min || value > max) {
+      throw this.error(type + " value out of range: " + value.toString());
+    }
+    return value;
+  }
+
+  readInt32(): number {
+    return Number(this.readInteger("int32", INT32_MIN, INT32_MAX));
+  }
+
+  readUint32(): number {
+    return Number(this.readInteger("uint32", BigInt(0), UINT32_MAX));
+  }
+
+  readInt64(): bigint {
+    return this.readInteger("int64", INT64_MIN, INT64_MAX);
+  }
+
+  readUint64(): bigint {
+    return this.readInteger("uint64", BigInt(0), UINT64_MAX);
+  }
+
+  // the name of the value, or its number for values unknown to the generated code.
+  readEnum(): string | number {
+    const token: string = this.peek();
+    if (isIdentifier(token)) {
+      this.pos += token.length;
+      return token;
+    }
+    return this.readInt32();
+  }
+}
+
+export function readMessage<T extends TextMessage>(r: TextReader, message: T): T {
+  r.beginMessage();
+  message.mergeText(r);
+  return message;
+}
+
+function typeName(type_url: string): string {
+  return type_url.substring(type_url.lastIndexOf("/") + 1);
+}
+
+// writes google.protobuf.Any as `[type_url] { ... }` when the packed type is registered.
+export function writeAny(w: TextWriter, any: AnyMessage): boolean {
+  const type: TextMessageType | undefined = text_registry.get(typeName(any.type_url));
+  if (!any.type_url || type === undefined) {
+    return false;
+  }
+  w.writeMessage("[" + any.type_url + "]", type.fromBinary(any.value));
+  return true;
+}
+
+// reads the expanded `[type_url] { ... }` form of google.protobuf.Any.
+export function readAny(r: TextReader, name: string, any: AnyMessage): void {
+  const type_url: string = name.substring(1, name.length - 1);
+  const type: TextMessageType | undefined = text_registry.get(typeName(type_url));
+  if (type === undefined) {
+    throw r.error("unknown message type " + type_url);
+  }
+  r.tryConsume(":");
+  const message: TextMessage = readMessage(r, type.fromBinary(new Uint8Array(0)));
+  any.type_url = type_url;
+  any.value = message.toBinary();
+}
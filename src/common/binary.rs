@@ -0,0 +1,53 @@
+use crate::context::Context;
+use crate::descriptor::field_descriptor_proto::Type;
+use crate::descriptor::FieldDescriptorProto;
+use swc_ecma_ast::{Expr, Param};
+use swc_ecma_utils::quote_ident;
+
+use super::json::{json_option_set, json_param};
+
+// the options of toBinary and the ordering of map keys shared by every generated file, emitted at the root of the output.
+pub const BINARY_SUPPORT_MODULE: &str = "binary_support";
+pub const BINARY_SUPPORT_SOURCE: &str = include_str!("../../js/runtime/binary_support.ts");
+
+// options?: BinaryWriteOptions
+pub fn binary_options_param(ctx: &Context) -> Param {
+    json_param(
+        "options?",
+        crate::type_ref!(crate::entity_name_ident!(
+            ctx.lazy_support_ref(BINARY_SUPPORT_MODULE, "BinaryWriteOptions")
+        )),
+    )
+}
+
+impl FieldDescriptorProto {
+    // the comparator of binary_support ordering map keys of this type like protobuf does.
+    fn map_key_compare_name(&self) -> &'static str {
+        match self.type_() {
+            Type::TYPE_STRING => "compareStrings",
+            Type::TYPE_BOOL => "compareBooleans",
+            Type::TYPE_UINT32 | Type::TYPE_FIXED32 => "compareUnsignedNumbers",
+            Type::TYPE_UINT64 | Type::TYPE_FIXED64 => "compareUnsignedBigints",
+            _ if self.is_bigint() => "compareBigints",
+            _ => "compareNumbers",
+        }
+    }
+
+    // the entries of a map field, ordered by key with options?.deterministic.
+    // binary_support.orderedEntries(this.m.entries(), options?.deterministic === true, binary_support.compareStrings)
+    pub fn ordered_entries_expr(&self, ctx: &mut Context, map: Expr) -> Expr {
+        let descriptor = ctx
+            .get_map_type(self.type_name())
+            .unwrap_or_else(|| panic!("can not find the map type {}", self.type_name()));
+        crate::call_expr!(
+            ctx.lazy_support_ref(BINARY_SUPPORT_MODULE, "orderedEntries").into(),
+            vec![
+                crate::expr_or_spread!(crate::call_expr!(crate::member_expr_bare!(map, "entries"))),
+                crate::expr_or_spread!(json_option_set("deterministic")),
+                crate::expr_or_spread!(ctx
+                    .lazy_support_ref(BINARY_SUPPORT_MODULE, descriptor.field[0].map_key_compare_name())
+                    .into()),
+            ]
+        )
+    }
+}
@@ -0,0 +1,86 @@
+use crate::context::Context;
+use crate::descriptor::DescriptorProto;
+use swc_common::DUMMY_SP;
+use swc_ecma_ast::{BlockStmt, ClassMember, ClassMethod, Expr, Function, MethodKind, PropName, TsType};
+use swc_ecma_utils::quote_ident;
+
+use super::binary::binary_options_param;
+use super::json::json_param;
+
+// the framing of length delimited messages shared by every generated file, emitted at the root of the output.
+pub const DELIMITED_SUPPORT_MODULE: &str = "delimited_support";
+pub const DELIMITED_SUPPORT_SOURCE: &str = include_str!("../../js/runtime/delimited_support.ts");
+
+impl DescriptorProto {
+    fn delimited_method(
+        &self,
+        name: &str,
+        is_static: bool,
+        params: Vec<swc_ecma_ast::Param>,
+        value: Expr,
+        return_type: TsType,
+    ) -> ClassMember {
+        ClassMember::Method(ClassMethod {
+            span: DUMMY_SP,
+            accessibility: None,
+            key: PropName::Ident(quote_ident!(name)),
+            is_abstract: false,
+            is_optional: false,
+            is_override: false,
+            is_static,
+            function: Box::new(Function {
+                body: Some(BlockStmt {
+                    span: DUMMY_SP,
+                    stmts: vec![crate::return_stmt!(value)],
+                }),
+                decorators: vec![],
+                is_async: false,
+                is_generator: false,
+                params,
+                return_type: Some(Box::new(crate::type_annotation!(return_type))),
+                span: DUMMY_SP,
+                type_params: None,
+            }),
+            kind: MethodKind::Method,
+        })
+    }
+
+    // toBinaryDelimited(options?: BinaryWriteOptions): Uint8Array and static fromBinaryDelimited(bytes: Uint8Array): Msg
+    pub(super) fn print_delimited_members(&self, ctx: &mut Context) -> Vec<ClassMember> {
+        let class_name = ctx.normalize_name(self.name());
+
+        // delimited_support.writeDelimited(this.toBinary(options))
+        let to_binary_delimited = crate::call_expr!(
+            ctx.lazy_support_ref(DELIMITED_SUPPORT_MODULE, "writeDelimited").into(),
+            vec![crate::expr_or_spread!(crate::call_expr!(
+                crate::member_expr!("this", "toBinary"),
+                vec![crate::expr_or_spread!(quote_ident!("options").into())]
+            ))]
+        );
+        // Msg.fromBinary(delimited_support.readDelimited(bytes))
+        let from_binary_delimited = crate::call_expr!(
+            crate::member_expr!(quote_ident!(class_name.clone()), "fromBinary"),
+            vec![crate::expr_or_spread!(crate::call_expr!(
+                ctx.lazy_support_ref(DELIMITED_SUPPORT_MODULE, "readDelimited").into(),
+                vec![crate::expr_or_spread!(quote_ident!("bytes").into())]
+            ))]
+        );
+
+        vec![
+            self.delimited_method(
+                "toBinaryDelimited",
+                false,
+                vec![binary_options_param(ctx)],
+                to_binary_delimited,
+                crate::type_ref!("Uint8Array"),
+            ),
+            self.delimited_method(
+                "fromBinaryDelimited",
+                true,
+                vec![json_param("bytes", crate::type_ref!("Uint8Array"))],
+                from_binary_delimited,
+                crate::type_ref!(crate::entity_name_ident!(quote_ident!(class_name))),
+            ),
+        ]
+    }
+}
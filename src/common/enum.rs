@@ -1,9 +1,207 @@
 use crate::{context::Context, descriptor::EnumDescriptorProto, print::Print, runtime::Runtime};
 use swc_common::DUMMY_SP;
 use swc_ecma_ast::{
-    Decl, ModuleItem, TsEnumDecl, TsEnumMember, TsEnumMemberId, ModuleDecl, ExportDecl,
+    BinaryOp, BlockStmt, Decl, ExportDecl, Expr, FnDecl, Function, ModuleDecl, ModuleItem,
+    Param, Stmt, SwitchCase, SwitchStmt, TsAsExpr, TsEnumDecl, TsEnumMember, TsEnumMemberId,
+    TsKeywordTypeKind, TsLit, TsLitType, TsType, TsTypeAliasDecl, TsUnionOrIntersectionType,
+    TsUnionType, UnaryOp,
 };
-use swc_ecma_utils::quote_ident;
+use swc_ecma_utils::{quote_ident, quote_str};
+
+use super::json::{json_option_set, json_options_param, json_param, json_path_param, throw_json_error};
+
+impl EnumDescriptorProto {
+    // proto3 json accepts the value name as well as the number.
+    // export type EnumJson = "A" | "B" | number;
+    fn print_json_type(&self, ctx: &mut Context) -> ModuleItem {
+        let mut types: Vec<Box<TsType>> = self
+            .value
+            .iter()
+            .map(|member| {
+                Box::new(TsType::TsLitType(TsLitType {
+                    span: DUMMY_SP,
+                    lit: TsLit::Str(quote_str!(member.name())),
+                }))
+            })
+            .collect();
+        types.push(Box::new(crate::keyword_type!(TsKeywordTypeKind::TsNumberKeyword)));
+
+        ModuleItem::ModuleDecl(ModuleDecl::ExportDecl(ExportDecl {
+            span: DUMMY_SP,
+            decl: Decl::TsTypeAlias(Box::new(TsTypeAliasDecl {
+                span: DUMMY_SP,
+                declare: false,
+                id: quote_ident!(format!("{}Json", ctx.normalize_name(self.name()))),
+                type_params: None,
+                type_ann: Box::new(TsType::TsUnionOrIntersectionType(
+                    TsUnionOrIntersectionType::TsUnionType(TsUnionType {
+                        span: DUMMY_SP,
+                        types,
+                    }),
+                )),
+            })),
+        }))
+    }
+
+    // values are written by name unless enumAsInteger is set, unknown values keep their number.
+    // export function Enum_toJson(value: Enum, options?: JsonWriteOptions): EnumJson
+    fn print_to_json(&self, ctx: &mut Context) -> ModuleItem {
+        let enum_name = ctx.normalize_name(self.name());
+
+        let mut cases = vec![];
+        let mut numbers = vec![];
+        for member in &self.value {
+            // aliases share a number, the first name is the canonical one.
+            if numbers.contains(&member.number()) {
+                continue;
+            }
+            numbers.push(member.number());
+            cases.push(SwitchCase {
+                span: DUMMY_SP,
+                test: Some(Box::new(crate::member_expr!(
+                    quote_ident!(enum_name.clone()),
+                    member.name()
+                ))),
+                cons: vec![crate::return_stmt!(quote_str!(member.name()).into())],
+            });
+        }
+
+        let as_number = crate::return_stmt!(Expr::TsAs(TsAsExpr {
+            span: DUMMY_SP,
+            expr: Box::new(quote_ident!("value").into()),
+            type_ann: Box::new(crate::keyword_type!(TsKeywordTypeKind::TsNumberKeyword)),
+        }));
+        let stmts = vec![
+            crate::if_stmt!(
+                json_option_set("enumAsInteger"),
+                crate::block_stmt!(vec![as_number.clone()])
+            ),
+            Stmt::Switch(SwitchStmt {
+                span: DUMMY_SP,
+                discriminant: Box::new(quote_ident!("value").into()),
+                cases,
+            }),
+            as_number,
+        ];
+
+        self.print_json_function(
+            format!("{}_toJson", enum_name),
+            vec![
+                json_param(
+                    "value",
+                    crate::type_ref!(crate::entity_name_ident!(quote_ident!(enum_name.clone()))),
+                ),
+                json_options_param(ctx, "JsonWriteOptions"),
+            ],
+            stmts,
+            crate::type_ref!(crate::entity_name_ident!(quote_ident!(format!("{}Json", enum_name)))),
+        )
+    }
+
+    // unknown names read as undefined when ignoreUnknownFields is set, the caller drops them.
+    // export function Enum_fromJson(json: EnumJson, options?: JsonReadOptions, path: string = "$"): Enum | undefined
+    fn print_from_json(&self, ctx: &mut Context) -> ModuleItem {
+        let enum_name = ctx.normalize_name(self.name());
+        let enum_type = crate::type_ref!(crate::entity_name_ident!(quote_ident!(enum_name.clone())));
+
+        let mut cases = vec![];
+        for member in &self.value {
+            cases.push(SwitchCase {
+                span: DUMMY_SP,
+                test: Some(Box::new(quote_str!(member.name()).into())),
+                cons: vec![crate::return_stmt!(crate::member_expr!(
+                    quote_ident!(enum_name.clone()),
+                    member.name()
+                ))],
+            });
+        }
+
+        let stmts = vec![
+            crate::if_stmt!(
+                crate::typeof_unary_expr!(quote_ident!("json").into(), "number"),
+                crate::block_stmt!(vec![crate::return_stmt!(Expr::TsAs(TsAsExpr {
+                    span: DUMMY_SP,
+                    expr: Box::new(quote_ident!("json").into()),
+                    type_ann: Box::new(enum_type.clone()),
+                }))])
+            ),
+            Stmt::Switch(SwitchStmt {
+                span: DUMMY_SP,
+                discriminant: Box::new(quote_ident!("json").into()),
+                cases,
+            }),
+            crate::if_stmt!(
+                json_option_set("ignoreUnknownFields"),
+                crate::block_stmt!(vec![crate::return_stmt!(quote_ident!("undefined").into())])
+            ),
+            throw_json_error(
+                ctx,
+                quote_ident!("path").into(),
+                crate::bin_expr!(
+                    quote_str!(format!(
+                        "unknown value for enum {}: ",
+                        ctx.calculate_type_name(self.name()).trim_start_matches(".")
+                    ))
+                    .into(),
+                    quote_ident!("json").into(),
+                    BinaryOp::Add
+                )
+            ),
+        ];
+
+        self.print_json_function(
+            format!("{}_fromJson", enum_name),
+            vec![
+                json_param(
+                    "json",
+                    crate::type_ref!(crate::entity_name_ident!(quote_ident!(format!(
+                        "{}Json",
+                        enum_name
+                    )))),
+                ),
+                json_options_param(ctx, "JsonReadOptions"),
+                json_path_param(),
+            ],
+            stmts,
+            TsType::TsUnionOrIntersectionType(TsUnionOrIntersectionType::TsUnionType(TsUnionType {
+                span: DUMMY_SP,
+                types: vec![
+                    Box::new(enum_type),
+                    Box::new(crate::keyword_type!(TsKeywordTypeKind::TsUndefinedKeyword)),
+                ],
+            })),
+        )
+    }
+
+    fn print_json_function(
+        &self,
+        name: String,
+        params: Vec<Param>,
+        stmts: Vec<Stmt>,
+        return_type: TsType,
+    ) -> ModuleItem {
+        ModuleItem::ModuleDecl(ModuleDecl::ExportDecl(ExportDecl {
+            span: DUMMY_SP,
+            decl: Decl::Fn(FnDecl {
+                ident: quote_ident!(name),
+                declare: false,
+                function: Box::new(Function {
+                    params,
+                    decorators: vec![],
+                    span: DUMMY_SP,
+                    body: Some(BlockStmt {
+                        span: DUMMY_SP,
+                        stmts,
+                    }),
+                    is_generator: false,
+                    is_async: false,
+                    type_params: None,
+                    return_type: Some(Box::new(crate::type_annotation!(return_type))),
+                }),
+            }),
+        }))
+    }
+}
 
 impl<T> Print<T> for EnumDescriptorProto
 where
@@ -30,6 +228,15 @@ where
             span: DUMMY_SP,
         }));
 
-        vec![module]
+        let mut items = vec![
+            module,
+            self.print_json_type(ctx),
+            self.print_to_json(ctx),
+            self.print_from_json(ctx),
+        ];
+        if ctx.options.with_descriptor {
+            items.push(self.print_descriptor_function(ctx));
+        }
+        items
     }
 }
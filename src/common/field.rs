@@ -25,7 +25,7 @@ pub fn map_to_string_normalizer(expr: &Expr) -> Expr {
         crate::member_expr_bare!(expr.clone(), "map"),
         vec![crate::expr_or_spread!(crate::arrow_func_short!(
             crate::call_expr!(crate::member_expr!("v", "toString")),
-            vec![crate::pat_ident!(quote_ident!("v"))]
+            vec![crate::pat_ident!(quote_ident!("v: bigint"))]
         ))]
     )
 }
@@ -40,8 +40,21 @@ pub fn this_field_member(field: &FieldDescriptorProto) -> Expr {
     crate::member_expr!("this", field.name())
 }
 
+// the parameters and locals of the generated methods which fields must not shadow.
+const GENERATED_LOCALS: &[&str] = &["json", "jsonMessage", "options", "path"];
+
+// the local variable holding the value of a field, fields named after reserved words like
+// `package` or after the generated locals like `options` get a trailing underscore.
+pub fn local_name(field: &FieldDescriptorProto) -> String {
+    if crate::context::RESERVED_WORDS.contains(&field.name()) || GENERATED_LOCALS.contains(&field.name()) {
+        format!("{}_", field.name())
+    } else {
+        field.name().to_string()
+    }
+}
+
 pub fn bare_field_member(field: &FieldDescriptorProto) -> Expr {
-    Expr::Ident(quote_ident!(field.name()))
+    Expr::Ident(quote_ident!(local_name(field)))
 }
 
 pub fn static_field_member(_field: &FieldDescriptorProto) -> Expr {
@@ -62,7 +75,7 @@ impl FieldDescriptorProto {
 
 impl FieldDescriptorProto {
     pub fn prop_name(&self) -> String {
-        if self.has_oneof_index() {
+        if self.has_oneof_index() && !self.proto3_optional() {
             format!("_{}", self.name())
         } else {
             self.name().to_string()
@@ -91,7 +104,9 @@ impl FieldDescriptorProto {
                     BinaryOp::NotEqEq
                 )
             )
-        } else if (self.is_bytes() && ctx.syntax == &Syntax::Proto3) || self.is_repeated() {
+        } else if (self.is_bytes() && ctx.syntax == &Syntax::Proto3 && !self.proto3_optional())
+            || self.is_repeated()
+        {
             crate::bin_expr!(
                 neq_undefined_check,
                 crate::bin_expr!(
@@ -123,6 +138,10 @@ impl FieldDescriptorProto {
         if self.is_repeated() || self.has_oneof_index() {
             return None;
         }
+        // an unset wrapper is undefined, a set one is written even if it wraps the default.
+        if self.unboxed_wrapper(ctx).is_some() {
+            return None;
+        }
         if self.is_string() {
             Some(crate::lit_str!("").into())
         } else if self.is_bigint() {
@@ -291,10 +310,21 @@ impl FieldDescriptorProto {
     }
 
     pub fn print_prop<T: Runtime>(&self, ctx: &mut Context, _runtime: &T) -> ClassMember {
+        let unboxed = self.unboxed_wrapper(ctx);
         let mut value: Option<Box<Expr>> = None;
-        if ctx.syntax == &Syntax::Proto3 || self.is_repeated() || self.is_map(&ctx) || !self.is_optional() {
+        if unboxed.is_some() || self.is_plain_struct(ctx) {
+            // unboxed wrappers and plain structs are undefined when unset.
+            value = Some(Box::new(quote_ident!("undefined").into()))
+        } else if ctx.syntax == &Syntax::Proto3 || self.is_repeated() || self.is_map(&ctx) || !self.is_optional() {
             value = Some(Box::new(self.default_value_expr(ctx, false)))
         }
+        let type_ann = match &unboxed {
+            Some(unboxed) => unboxed.type_annotation(ctx),
+            None if self.is_plain_struct(ctx) => Some(Box::new(crate::type_annotation!(
+                self.plain_struct_type(ctx)
+            ))),
+            None => self.type_annotation(ctx),
+        };
         let mut key_ident = quote_ident!(self.prop_name());
         if self.is_optional() {
             key_ident = crate::quote_ident_optional!(self.prop_name())
@@ -304,7 +334,7 @@ impl FieldDescriptorProto {
             span: DUMMY_SP,
             key: PropName::Ident(key_ident),
             value,
-            type_ann: self.type_annotation(ctx),
+            type_ann,
             declare: false,
             is_static: false,
             decorators: vec![],
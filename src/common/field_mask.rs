@@ -0,0 +1,484 @@
+use crate::context::{Context, Syntax};
+use crate::descriptor::{DescriptorProto, FieldDescriptorProto};
+use swc_common::DUMMY_SP;
+use swc_ecma_ast::{
+    ArrayLit, AssignOp, BinaryOp, BindingIdent, BlockStmt, BreakStmt, ClassMember, ClassMethod,
+    Expr, ForHead, ForOfStmt, Function, MethodKind, Param, Pat, PatOrExpr, PropName, Stmt,
+    SwitchCase, SwitchStmt, TsKeywordTypeKind,
+};
+use swc_ecma_utils::{quote_ident, quote_str};
+
+pub const FIELD_MASK_PROTO: &str = "google/protobuf/field_mask.proto";
+
+impl FieldDescriptorProto {
+    // a path may continue past this field only if it is a message with generated mask support.
+    fn is_mask_traversable(&self, ctx: &Context) -> bool {
+        self.is_message()
+            && !self.is_repeated()
+            && !self.is_well_known_message()
+            && self.unboxed_wrapper(ctx).is_none()
+            && !self.is_plain_struct(ctx)
+    }
+
+    // mirrors the initial value of the generated property.
+    fn mask_reset_value(&self, ctx: &mut Context) -> Expr {
+        if self.unboxed_wrapper(ctx).is_some() || self.is_plain_struct(ctx) {
+            quote_ident!("undefined").into()
+        } else if ctx.syntax == &Syntax::Proto3 || self.is_repeated() || !self.is_optional() {
+            self.default_value_expr(ctx, false)
+        } else {
+            quote_ident!("undefined").into()
+        }
+    }
+
+    fn mask_merge_stmts(&self, ctx: &mut Context) -> Vec<Stmt> {
+        let this_field = crate::member_expr!("this", self.name());
+        let src_field = crate::member_expr!("src", self.name());
+
+        if self.is_map(ctx) {
+            // for (let entry of src.m.entries()) { this.m.set(entry[0], entry[1]); }
+            return vec![Stmt::ForOf(ForOfStmt {
+                is_await: false,
+                left: ForHead::Pat(Box::new(crate::pat_ident!(quote_ident!("let entry")))),
+                right: Box::new(crate::member_expr_bare!(src_field, "entries()")),
+                body: Box::new(crate::block_stmt!(vec![crate::expr_stmt!(crate::call_expr!(
+                    crate::member_expr_bare!(this_field, "set"),
+                    vec![
+                        crate::expr_or_spread!(quote_ident!("entry[0]").into()),
+                        crate::expr_or_spread!(quote_ident!("entry[1]").into()),
+                    ]
+                ))])),
+                span: DUMMY_SP,
+            })];
+        }
+
+        if self.is_repeated() {
+            // repeated fields are appended.
+            return vec![Stmt::ForOf(ForOfStmt {
+                is_await: false,
+                left: ForHead::VarDecl(Box::new(crate::const_decl_uinit!("value"))),
+                right: Box::new(src_field),
+                body: Box::new(crate::block_stmt!(vec![crate::expr_stmt!(crate::call_expr!(
+                    crate::member_expr_bare!(this_field, "push"),
+                    vec![crate::expr_or_spread!(quote_ident!("value").into())]
+                ))])),
+                span: DUMMY_SP,
+            })];
+        }
+
+        if self.is_message() && self.unboxed_wrapper(ctx).is_none() && !self.is_plain_struct(ctx) {
+            // messages in the last position of a path are merged.
+            let type_ref = ctx.lazy_type_ref(self.type_name());
+            let merge_stmt = crate::if_stmt!(
+                crate::bin_expr!(
+                    src_field.clone(),
+                    quote_ident!("undefined").into(),
+                    BinaryOp::NotEqEq
+                ),
+                crate::block_stmt!(vec![
+                    crate::expr_stmt!(crate::assign_expr!(
+                        PatOrExpr::Expr(Box::new(this_field.clone())),
+                        crate::new_expr!(type_ref.clone().into()),
+                        AssignOp::NullishAssign
+                    )),
+                    crate::expr_stmt!(crate::call_expr!(
+                        crate::member_expr_bare!(this_field.clone(), "mergeFrom"),
+                        vec![crate::expr_or_spread!(crate::call_expr!(
+                            crate::member_expr_bare!(src_field.clone(), "toBinary")
+                        ))]
+                    )),
+                ])
+            );
+            if !self.is_mask_traversable(ctx) {
+                return vec![merge_stmt];
+            }
+
+            let field_mask = ctx.lazy_type_ref(".google.protobuf.FieldMask");
+            return vec![crate::if_stmt!(
+                crate::bin_expr!(
+                    quote_ident!("dot").into(),
+                    crate::lit_num!(-1).into(),
+                    BinaryOp::EqEqEq
+                ),
+                crate::block_stmt!(vec![merge_stmt]),
+                crate::block_stmt!(vec![
+                    crate::expr_stmt!(crate::assign_expr!(
+                        PatOrExpr::Expr(Box::new(this_field.clone())),
+                        crate::new_expr!(type_ref.clone().into()),
+                        AssignOp::NullishAssign
+                    )),
+                    crate::expr_stmt!(crate::call_expr!(
+                        crate::member_expr_bare!(this_field, "mergeFromWithMask"),
+                        vec![
+                            crate::expr_or_spread!(crate::bin_expr!(
+                                src_field,
+                                crate::new_expr!(type_ref.into()),
+                                BinaryOp::NullishCoalescing
+                            )),
+                            crate::expr_or_spread!(crate::call_expr!(
+                                crate::member_expr!(field_mask, "fromPaths"),
+                                vec![crate::expr_or_spread!(Expr::Array(ArrayLit {
+                                    span: DUMMY_SP,
+                                    elems: vec![Some(crate::expr_or_spread!(
+                                        quote_ident!("rest").into()
+                                    ))],
+                                }))]
+                            )),
+                        ]
+                    )),
+                ])
+            )];
+        }
+
+        if self.has_oneof_index() && !self.proto3_optional() {
+            // only clear this member when the source does not set it, the setter resets the others.
+            return vec![crate::if_stmt!(
+                crate::bin_expr!(
+                    src_field.clone(),
+                    quote_ident!("undefined").into(),
+                    BinaryOp::NotEqEq
+                ),
+                crate::block_stmt!(vec![crate::expr_stmt!(crate::assign_expr!(
+                    PatOrExpr::Expr(Box::new(this_field)),
+                    src_field
+                ))]),
+                crate::block_stmt!(vec![crate::expr_stmt!(crate::assign_expr!(
+                    PatOrExpr::Expr(Box::new(crate::member_expr!("this", self.prop_name()))),
+                    quote_ident!("undefined").into()
+                ))])
+            )];
+        }
+
+        vec![crate::expr_stmt!(crate::assign_expr!(
+            PatOrExpr::Expr(Box::new(this_field)),
+            src_field
+        ))]
+    }
+
+    // if (!mask.paths.includes("f")) {
+    //     const sub = mask.paths.filter((p) => p.startsWith("f.")).map((p) => p.substring(2));
+    //     if (sub.length === 0) {
+    //         this.f = undefined;
+    //     } else {
+    //         this.f?.pruneWithMask(FieldMask.fromPaths(sub));
+    //     }
+    // }
+    fn mask_prune_stmt(&self, ctx: &mut Context) -> Stmt {
+        let reset_stmt = crate::expr_stmt!(crate::assign_expr!(
+            PatOrExpr::Expr(Box::new(crate::member_expr!("this", self.prop_name()))),
+            self.mask_reset_value(ctx)
+        ));
+
+        let prune_stmt = if self.is_mask_traversable(ctx) {
+            let field_mask = ctx.lazy_type_ref(".google.protobuf.FieldMask");
+            let prefix = format!("{}.", self.name());
+            crate::block_stmt!(vec![
+                Stmt::Decl(crate::const_decl!(
+                    "sub: string[]",
+                    crate::call_expr!(
+                        crate::member_expr_bare!(
+                            crate::call_expr!(
+                                crate::member_expr_bare!(crate::member_expr!("mask", "paths"), "filter"),
+                                vec![crate::expr_or_spread!(crate::arrow_func_short!(
+                                    crate::call_expr!(
+                                        crate::member_expr!("p", "startsWith"),
+                                        vec![crate::expr_or_spread!(quote_str!(prefix.clone()).into())]
+                                    ),
+                                    vec![crate::pat_ident!(quote_ident!("p: string"))]
+                                ))]
+                            ),
+                            "map"
+                        ),
+                        vec![crate::expr_or_spread!(crate::arrow_func_short!(
+                            crate::call_expr!(
+                                crate::member_expr!("p", "substring"),
+                                vec![crate::expr_or_spread!(crate::lit_num!(prefix.len()).into())]
+                            ),
+                            vec![crate::pat_ident!(quote_ident!("p: string"))]
+                        ))]
+                    )
+                )),
+                crate::if_stmt!(
+                    crate::bin_expr!(
+                        crate::member_expr!("sub", "length"),
+                        crate::lit_num!(0).into(),
+                        BinaryOp::EqEqEq
+                    ),
+                    crate::block_stmt!(vec![reset_stmt]),
+                    crate::block_stmt!(vec![crate::expr_stmt!(crate::call_expr!(
+                        crate::member_expr!("this", format!("{}?.pruneWithMask", self.name())),
+                        vec![crate::expr_or_spread!(crate::call_expr!(
+                            crate::member_expr!(field_mask, "fromPaths"),
+                            vec![crate::expr_or_spread!(quote_ident!("sub").into())]
+                        ))]
+                    ))])
+                ),
+            ])
+        } else {
+            crate::block_stmt!(vec![reset_stmt])
+        };
+
+        crate::if_stmt!(
+            crate::unary_expr!(crate::call_expr!(
+                crate::member_expr_bare!(crate::member_expr!("mask", "paths"), "includes"),
+                vec![crate::expr_or_spread!(quote_str!(self.name()).into())]
+            )),
+            prune_stmt
+        )
+    }
+}
+
+fn mask_param(name: &str, type_ann: swc_ecma_ast::TsTypeAnn) -> Param {
+    Param {
+        span: DUMMY_SP,
+        decorators: vec![],
+        pat: Pat::Ident(BindingIdent {
+            id: quote_ident!(name),
+            type_ann: Some(Box::new(type_ann)),
+        }),
+    }
+}
+
+fn mask_method(name: &str, is_static: bool, params: Vec<Param>, stmts: Vec<Stmt>, return_type: Option<swc_ecma_ast::TsTypeAnn>) -> ClassMember {
+    ClassMember::Method(ClassMethod {
+        span: DUMMY_SP,
+        accessibility: None,
+        key: PropName::Ident(quote_ident!(name)),
+        is_abstract: false,
+        is_optional: false,
+        is_override: false,
+        is_static,
+        function: Box::new(Function {
+            body: Some(BlockStmt {
+                span: DUMMY_SP,
+                stmts,
+            }),
+            decorators: vec![],
+            is_async: false,
+            is_generator: false,
+            params,
+            return_type: return_type.map(Box::new),
+            span: DUMMY_SP,
+            type_params: None,
+        }),
+        kind: MethodKind::Method,
+    })
+}
+
+// const dot: number = path.indexOf(".");
+// const name: string = dot === -1 ? path : path.substring(0, dot);
+// const rest: string = path.substring(dot + 1);
+fn split_path_stmts() -> Vec<Stmt> {
+    vec![
+        Stmt::Decl(crate::const_decl!(
+            "dot: number",
+            crate::call_expr!(
+                crate::member_expr!("path", "indexOf"),
+                vec![crate::expr_or_spread!(quote_str!(".").into())]
+            )
+        )),
+        Stmt::Decl(crate::const_decl!(
+            "name: string",
+            crate::cond_expr!(
+                crate::bin_expr!(
+                    quote_ident!("dot").into(),
+                    crate::lit_num!(-1).into(),
+                    BinaryOp::EqEqEq
+                ),
+                quote_ident!("path").into(),
+                crate::call_expr!(
+                    crate::member_expr!("path", "substring"),
+                    vec![
+                        crate::expr_or_spread!(crate::lit_num!(0).into()),
+                        crate::expr_or_spread!(quote_ident!("dot").into()),
+                    ]
+                )
+            )
+        )),
+        Stmt::Decl(crate::const_decl!(
+            "rest: string",
+            crate::call_expr!(
+                crate::member_expr!("path", "substring"),
+                vec![crate::expr_or_spread!(crate::bin_expr!(
+                    quote_ident!("dot").into(),
+                    crate::lit_num!(1).into(),
+                    BinaryOp::Add
+                ))]
+            )
+        )),
+    ]
+}
+
+impl DescriptorProto {
+    // for (const path of mask.paths) {
+    //     if (!Msg.validateMaskPath(path)) {
+    //         throw new Error("invalid field mask path " + path + " for pkg.Msg");
+    //     }
+    // }
+    fn mask_validation_stmt(&self, ctx: &mut Context) -> Stmt {
+        Stmt::ForOf(ForOfStmt {
+            is_await: false,
+            left: ForHead::VarDecl(Box::new(crate::const_decl_uinit!("path"))),
+            right: Box::new(crate::member_expr!("mask", "paths")),
+            body: Box::new(crate::block_stmt!(vec![crate::if_stmt!(
+                crate::unary_expr!(crate::call_expr!(
+                    crate::member_expr!(quote_ident!(ctx.normalize_name(self.name())), "validateMaskPath"),
+                    vec![crate::expr_or_spread!(quote_ident!("path").into())]
+                )),
+                crate::block_stmt!(vec![crate::throw_stmt!(crate::new_expr!(
+                    quote_ident!("Error").into(),
+                    vec![crate::expr_or_spread!(crate::bin_expr!(
+                        crate::bin_expr!(
+                            quote_str!("invalid field mask path ").into(),
+                            quote_ident!("path").into(),
+                            BinaryOp::Add
+                        ),
+                        quote_str!(format!(
+                            " for {}",
+                            ctx.calculate_type_name(self.name()).trim_start_matches('.')
+                        ))
+                        .into(),
+                        BinaryOp::Add
+                    ))]
+                ))])
+            )])),
+            span: DUMMY_SP,
+        })
+    }
+
+    // static validateMaskPath(path: string): boolean
+    fn print_validate_mask_path(&self, ctx: &mut Context) -> ClassMember {
+        let mut cases = vec![];
+        for field in &self.field {
+            let terminal = crate::bin_expr!(
+                quote_ident!("dot").into(),
+                crate::lit_num!(-1).into(),
+                BinaryOp::EqEqEq
+            );
+            let test = if field.is_mask_traversable(ctx) {
+                crate::bin_expr!(
+                    terminal,
+                    crate::call_expr!(
+                        crate::member_expr!(ctx.lazy_type_ref(field.type_name()), "validateMaskPath"),
+                        vec![crate::expr_or_spread!(quote_ident!("rest").into())]
+                    ),
+                    BinaryOp::LogicalOr
+                )
+            } else {
+                terminal
+            };
+            cases.push(SwitchCase {
+                span: DUMMY_SP,
+                test: Some(Box::new(quote_str!(field.name()).into())),
+                cons: vec![crate::return_stmt!(test)],
+            });
+        }
+        cases.push(SwitchCase {
+            span: DUMMY_SP,
+            test: None,
+            cons: vec![crate::return_stmt!(crate::lit_bool!(false).into())],
+        });
+
+        let mut stmts = split_path_stmts();
+        stmts.push(Stmt::Switch(SwitchStmt {
+            span: DUMMY_SP,
+            discriminant: Box::new(quote_ident!("name").into()),
+            cases,
+        }));
+
+        mask_method(
+            "validateMaskPath",
+            true,
+            vec![mask_param("path", crate::type_annotation!("string"))],
+            stmts,
+            Some(crate::type_annotation!(crate::keyword_type!(
+                TsKeywordTypeKind::TsBooleanKeyword
+            ))),
+        )
+    }
+
+    // mergeFromWithMask(src: Msg, mask: FieldMask)
+    fn print_merge_from_with_mask(&self, ctx: &mut Context) -> ClassMember {
+        let mut cases = vec![];
+        for field in &self.field {
+            let mut cons = field.mask_merge_stmts(ctx);
+            cons.push(Stmt::Break(BreakStmt {
+                label: None,
+                span: DUMMY_SP,
+            }));
+            cases.push(SwitchCase {
+                span: DUMMY_SP,
+                test: Some(Box::new(quote_str!(field.name()).into())),
+                cons,
+            });
+        }
+
+        let mut body = split_path_stmts();
+        body.push(Stmt::Switch(SwitchStmt {
+            span: DUMMY_SP,
+            discriminant: Box::new(quote_ident!("name").into()),
+            cases,
+        }));
+
+        let stmts = vec![
+            self.mask_validation_stmt(ctx),
+            Stmt::ForOf(ForOfStmt {
+                is_await: false,
+                left: ForHead::VarDecl(Box::new(crate::const_decl_uinit!("path"))),
+                right: Box::new(crate::member_expr!("mask", "paths")),
+                body: Box::new(crate::block_stmt!(body)),
+                span: DUMMY_SP,
+            }),
+            crate::return_stmt!(quote_ident!("this").into()),
+        ];
+
+        let field_mask = ctx.lazy_type_ref(".google.protobuf.FieldMask");
+        mask_method(
+            "mergeFromWithMask",
+            false,
+            vec![
+                mask_param(
+                    "src",
+                    crate::type_annotation!(crate::type_ref!(crate::entity_name_ident!(
+                        quote_ident!(ctx.normalize_name(self.name()))
+                    ))),
+                ),
+                mask_param(
+                    "mask",
+                    crate::type_annotation!(crate::type_ref!(crate::entity_name_ident!(field_mask))),
+                ),
+            ],
+            stmts,
+            None,
+        )
+    }
+
+    // pruneWithMask(mask: FieldMask)
+    fn print_prune_with_mask(&self, ctx: &mut Context) -> ClassMember {
+        let mut stmts = vec![self.mask_validation_stmt(ctx)];
+        for field in &self.field {
+            stmts.push(field.mask_prune_stmt(ctx));
+        }
+        stmts.push(crate::return_stmt!(quote_ident!("this").into()));
+
+        let field_mask = ctx.lazy_type_ref(".google.protobuf.FieldMask");
+        mask_method(
+            "pruneWithMask",
+            false,
+            vec![mask_param(
+                "mask",
+                crate::type_annotation!(crate::type_ref!(crate::entity_name_ident!(field_mask))),
+            )],
+            stmts,
+            None,
+        )
+    }
+
+    pub(super) fn print_field_mask_members(&self, ctx: &mut Context) -> Vec<ClassMember> {
+        vec![
+            self.print_validate_mask_path(ctx),
+            self.print_merge_from_with_mask(ctx),
+            self.print_prune_with_mask(ctx),
+        ]
+    }
+}
@@ -1,5 +1,5 @@
 use swc_common::DUMMY_SP;
-use swc_ecma_ast::{TsEntityName, TsKeywordTypeKind, TsTypeRef};
+use swc_ecma_ast::{TsArrayType, TsEntityName, TsKeywordTypeKind, TsType, TsTypeRef};
 use swc_ecma_utils::quote_ident;
 
 use crate::{
@@ -111,6 +111,10 @@ impl FieldDescriptorProto {
             && self.type_() != Type::TYPE_FLOAT
     }
 
+    pub fn is_float(&self) -> bool {
+        self.type_() == Type::TYPE_DOUBLE || self.type_() == Type::TYPE_FLOAT
+    }
+
     pub fn is_bigint(&self) -> bool {
         self.type_() == Type::TYPE_INT64
             || self.type_() == Type::TYPE_UINT64
@@ -147,4 +151,86 @@ impl FieldDescriptorProto {
     pub fn is_well_known_message(&self) -> bool {
         self.is_message() && self.type_name().contains("google.protobuf")
     }
+
+    /// With `plain_struct`, a singular Struct, Value or ListValue field is exposed
+    /// as a plain json value and converted at the binary and json boundaries.
+    pub fn is_plain_struct(&self, ctx: &Context) -> bool {
+        ctx.options.plain_struct
+            && self.is_message()
+            && !self.is_repeated()
+            && (!self.has_oneof_index() || self.proto3_optional())
+            && matches!(
+                self.type_name(),
+                ".google.protobuf.Struct" | ".google.protobuf.Value" | ".google.protobuf.ListValue"
+            )
+    }
+
+    /// With `lazy_fields`, a singular message field marked `[lazy = true]` keeps
+    /// its encoded bytes when read and decodes them on first access.
+    pub fn is_lazy(&self, ctx: &Context) -> bool {
+        ctx.options.lazy_fields
+            && !ctx.options.with_sendable
+            && (self.options.lazy() || self.options.unverified_lazy())
+            && self.is_message()
+            && !self.is_repeated()
+            && (!self.has_oneof_index() || self.proto3_optional())
+            && self.unboxed_wrapper(ctx).is_none()
+            && !self.is_plain_struct(ctx)
+    }
+
+    pub fn plain_struct_type(&self, ctx: &mut Context) -> TsType {
+        let provided_by = ctx
+            .find_type_provider(&self.type_name().to_string())
+            .expect("expected to find a proto file for the type");
+        let name = if self.type_name() == ".google.protobuf.Struct" {
+            "JsonObject"
+        } else {
+            "JsonValue"
+        };
+        let type_ref = TsType::TsTypeRef(TsTypeRef {
+            span: DUMMY_SP,
+            type_name: TsEntityName::Ident(ctx.lazy_symbol_ref(&provided_by, name)),
+            type_params: None,
+        });
+        if self.type_name() == ".google.protobuf.ListValue" {
+            TsType::TsArrayType(TsArrayType {
+                span: DUMMY_SP,
+                elem_type: Box::new(type_ref),
+            })
+        } else {
+            type_ref
+        }
+    }
+
+    /// With `unbox_wrappers`, a singular wrapper typed field is exposed as its
+    /// wrapped primitive. Returns that primitive field, optional so that an
+    /// unset wrapper stays distinguishable from the default value.
+    pub fn unboxed_wrapper(&self, ctx: &Context) -> Option<FieldDescriptorProto> {
+        if !ctx.options.unbox_wrappers
+            || !self.is_message()
+            || self.is_repeated()
+            || (self.has_oneof_index() && !self.proto3_optional())
+        {
+            return None;
+        }
+        let r#type = match self.type_name() {
+            ".google.protobuf.DoubleValue" => Type::TYPE_DOUBLE,
+            ".google.protobuf.FloatValue" => Type::TYPE_FLOAT,
+            ".google.protobuf.Int64Value" => Type::TYPE_INT64,
+            ".google.protobuf.UInt64Value" => Type::TYPE_UINT64,
+            ".google.protobuf.Int32Value" => Type::TYPE_INT32,
+            ".google.protobuf.UInt32Value" => Type::TYPE_UINT32,
+            ".google.protobuf.BoolValue" => Type::TYPE_BOOL,
+            ".google.protobuf.StringValue" => Type::TYPE_STRING,
+            ".google.protobuf.BytesValue" => Type::TYPE_BYTES,
+            _ => return None,
+        };
+        let mut unboxed = self.clone();
+        unboxed.set_type(r#type);
+        unboxed.clear_type_name();
+        unboxed.clear_oneof_index();
+        unboxed.set_label(Label::LABEL_OPTIONAL);
+        unboxed.set_proto3_optional(true);
+        Some(unboxed)
+    }
 }
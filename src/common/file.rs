@@ -13,7 +13,7 @@ impl FileDescriptorProto {
     ) -> Vec<ModuleItem> {
         let mut ctx = context::descend_if_necessary!(ctx, self);
 
-        let mut modules: Vec<ModuleItem> = Vec::new();
+        let mut modules: Vec<ModuleItem> = runtime.print_prelude(&mut ctx, self);
 
         for r#enum in &self.enum_type {
             modules.append(&mut r#enum.print(&mut ctx, runtime))
@@ -29,6 +29,12 @@ impl FileDescriptorProto {
 
         let mut modules = ctx.wrap_if_needed(modules);
 
+        if ctx.options.with_descriptor {
+            modules.append(&mut self.print_file_descriptor(&mut ctx));
+        }
+
+        modules.append(&mut self.print_type_registration(&mut ctx));
+
         let imports = ctx.drain_imports();
         // prepend imports
         modules.splice(0..0, imports);
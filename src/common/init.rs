@@ -0,0 +1,241 @@
+use crate::context::Context;
+use crate::descriptor::{field_descriptor_proto::Label, DescriptorProto, FieldDescriptorProto};
+use swc_common::DUMMY_SP;
+use swc_ecma_ast::{
+    BinaryOp, BindingIdent, BlockStmt, ClassMember, ClassMethod, Decl, ExportDecl, Expr, ForHead,
+    ForOfStmt, Function, MethodKind, ModuleDecl, ModuleItem, Param, Pat, PatOrExpr, PropName,
+    Stmt, TsEntityName, TsInterfaceBody, TsInterfaceDecl, TsPropertySignature, TsType,
+    TsTypeElement, TsTypeParamInstantiation, TsTypeRef,
+};
+use swc_ecma_utils::quote_ident;
+
+fn generic_type(name: &str, params: Vec<TsType>) -> TsType {
+    TsType::TsTypeRef(TsTypeRef {
+        span: DUMMY_SP,
+        type_name: TsEntityName::Ident(quote_ident!(name)),
+        type_params: Some(Box::new(TsTypeParamInstantiation {
+            span: DUMMY_SP,
+            params: params.into_iter().map(Box::new).collect(),
+        })),
+    })
+}
+
+impl FieldDescriptorProto {
+    // messages are initialized either from an instance or from their init object.
+    fn is_init_message(&self, ctx: &Context) -> bool {
+        self.is_message() && self.unboxed_wrapper(ctx).is_none() && !self.is_plain_struct(ctx)
+    }
+
+    // the type accepted for a single value of this field.
+    fn init_value_type(&self, ctx: &mut Context) -> TsType {
+        if let Some(unboxed) = self.unboxed_wrapper(ctx) {
+            return *unboxed
+                .type_annotation(ctx)
+                .expect("expected wrapper fields to have a type")
+                .type_ann;
+        }
+        if self.is_plain_struct(ctx) {
+            return self.plain_struct_type(ctx);
+        }
+        if self.is_init_message(ctx) {
+            return *crate::type_union!(
+                crate::type_ref!(crate::entity_name_ident!(ctx.lazy_type_ref(self.type_name()))),
+                crate::type_ref!(crate::entity_name_ident!(
+                    ctx.lazy_companion_type_ref(self.type_name(), "Init")
+                ))
+            )
+            .type_ann;
+        }
+        let mut singular = self.clone();
+        singular.set_label(Label::LABEL_OPTIONAL);
+        *singular
+            .type_annotation(ctx)
+            .expect("expected fields to have a type")
+            .type_ann
+    }
+
+    // repeated fields and maps accept plain containers, they are copied into the message.
+    fn init_type(&self, ctx: &mut Context) -> TsType {
+        if self.is_map(ctx) {
+            let descriptor = ctx
+                .get_map_type(self.type_name())
+                .unwrap_or_else(|| panic!("can not find the map type {}", self.type_name()));
+            let key_type = descriptor.field[0].init_value_type(ctx);
+            let value_type = descriptor.field[1].init_value_type(ctx);
+            generic_type("Map", vec![key_type, value_type])
+        } else if self.is_repeated() {
+            generic_type("Array", vec![self.init_value_type(ctx)])
+        } else {
+            self.init_value_type(ctx)
+        }
+    }
+
+    // init.f instanceof F ? init.f : F.create(init.f)
+    fn init_value_expr(&self, ctx: &mut Context, value: Expr) -> Expr {
+        if !self.is_init_message(ctx) {
+            return value;
+        }
+        let type_ref = ctx.lazy_type_ref(self.type_name());
+        crate::cond_expr!(
+            crate::bin_expr!(value.clone(), type_ref.clone().into(), BinaryOp::InstanceOf),
+            value.clone(),
+            crate::call_expr!(
+                crate::member_expr!(type_ref, "create"),
+                vec![crate::expr_or_spread!(value)]
+            )
+        )
+    }
+
+    fn print_init_prop(&self, ctx: &mut Context) -> TsTypeElement {
+        TsTypeElement::TsPropertySignature(TsPropertySignature {
+            span: DUMMY_SP,
+            readonly: false,
+            key: Box::new(quote_ident!(self.name()).into()),
+            computed: false,
+            optional: true,
+            init: None,
+            params: vec![],
+            type_ann: Some(Box::new(crate::type_annotation!(self.init_type(ctx)))),
+            type_params: None,
+        })
+    }
+
+    // if (init.f !== undefined) {
+    //     ...
+    // }
+    fn print_init_stmt(&self, ctx: &mut Context) -> Stmt {
+        let init_field = crate::member_expr!("init", self.name());
+        let message_field = crate::member_expr!("m", self.name());
+
+        let stmt = if self.is_map(ctx) {
+            let descriptor = ctx
+                .get_map_type(self.type_name())
+                .unwrap_or_else(|| panic!("can not find the map type {}", self.type_name()));
+            let value = descriptor.field[1].init_value_expr(ctx, quote_ident!("value").into());
+            Stmt::ForOf(ForOfStmt {
+                is_await: false,
+                left: ForHead::Pat(Box::new(crate::pat_ident!(quote_ident!("let entry")))),
+                right: Box::new(crate::member_expr_bare!(init_field.clone(), "entries()")),
+                body: Box::new(crate::block_stmt!(vec![
+                    crate::expr_stmt!(Expr::Ident(quote_ident!("let key = entry[0]"))),
+                    crate::expr_stmt!(Expr::Ident(quote_ident!("let value = entry[1]"))),
+                    crate::expr_stmt!(crate::call_expr!(
+                        crate::member_expr_bare!(message_field, "set"),
+                        vec![
+                            crate::expr_or_spread!(quote_ident!("key").into()),
+                            crate::expr_or_spread!(value),
+                        ]
+                    )),
+                ])),
+                span: DUMMY_SP,
+            })
+        } else if self.is_repeated() {
+            let value = self.init_value_expr(ctx, quote_ident!("value").into());
+            Stmt::ForOf(ForOfStmt {
+                is_await: false,
+                left: ForHead::VarDecl(Box::new(crate::const_decl_uinit!("value"))),
+                right: Box::new(init_field.clone()),
+                body: Box::new(crate::block_stmt!(vec![crate::expr_stmt!(crate::call_expr!(
+                    crate::member_expr_bare!(message_field, "push"),
+                    vec![crate::expr_or_spread!(value)]
+                ))])),
+                span: DUMMY_SP,
+            })
+        } else {
+            // oneof members go through their setter so the other cases are cleared.
+            crate::expr_stmt!(crate::assign_expr!(
+                PatOrExpr::Expr(Box::new(message_field)),
+                self.init_value_expr(ctx, init_field.clone())
+            ))
+        };
+
+        crate::if_stmt!(
+            crate::bin_expr!(init_field, quote_ident!("undefined").into(), BinaryOp::NotEqEq),
+            crate::block_stmt!(vec![stmt])
+        )
+    }
+}
+
+impl DescriptorProto {
+    fn init_interface_name(&self, ctx: &Context) -> String {
+        format!("{}Init", ctx.normalize_name(self.name()))
+    }
+
+    // export interface MsgInit { f?: F | FInit; r?: Array<number>; ... }
+    pub(super) fn print_init_interface(&self, ctx: &mut Context) -> ModuleItem {
+        let mut body = vec![];
+        for field in &self.field {
+            body.push(field.print_init_prop(ctx));
+        }
+
+        ModuleItem::ModuleDecl(ModuleDecl::ExportDecl(ExportDecl {
+            span: DUMMY_SP,
+            decl: Decl::TsInterface(Box::new(TsInterfaceDecl {
+                span: DUMMY_SP,
+                id: quote_ident!(self.init_interface_name(ctx)),
+                declare: false,
+                type_params: None,
+                extends: vec![],
+                body: TsInterfaceBody { span: DUMMY_SP, body },
+            })),
+        }))
+    }
+
+    // static create(init?: MsgInit): Msg
+    pub(super) fn print_create(&self, ctx: &mut Context) -> ClassMember {
+        let class_name = ctx.normalize_name(self.name());
+        let mut stmts = vec![
+            Stmt::Decl(crate::const_decl!(
+                format!("m: {}", class_name),
+                crate::new_expr!(quote_ident!(class_name.clone()).into())
+            )),
+            crate::if_stmt!(
+                crate::bin_expr!(
+                    quote_ident!("init").into(),
+                    quote_ident!("undefined").into(),
+                    BinaryOp::EqEqEq
+                ),
+                crate::block_stmt!(vec![crate::return_stmt!(quote_ident!("m").into())])
+            ),
+        ];
+        for field in &self.field {
+            stmts.push(field.print_init_stmt(ctx));
+        }
+        stmts.push(crate::return_stmt!(quote_ident!("m").into()));
+
+        ClassMember::Method(ClassMethod {
+            span: DUMMY_SP,
+            accessibility: None,
+            key: PropName::Ident(quote_ident!("create")),
+            is_abstract: false,
+            is_optional: false,
+            is_override: false,
+            is_static: true,
+            function: Box::new(Function {
+                body: Some(BlockStmt {
+                    span: DUMMY_SP,
+                    stmts,
+                }),
+                decorators: vec![],
+                is_async: false,
+                is_generator: false,
+                params: vec![Param {
+                    span: DUMMY_SP,
+                    decorators: vec![],
+                    pat: Pat::Ident(BindingIdent {
+                        id: quote_ident!("init?"),
+                        type_ann: Some(Box::new(crate::type_annotation!(crate::type_ref!(
+                            crate::entity_name_ident!(quote_ident!(self.init_interface_name(ctx)))
+                        )))),
+                    }),
+                }],
+                return_type: Some(Box::new(crate::type_annotation!(crate::type_ref!(
+                    crate::entity_name_ident!(quote_ident!(class_name))
+                )))),
+                span: DUMMY_SP,
+                type_params: None,
+            }),
+            kind: MethodKind::Method,
+        })
+    }
+}
@@ -19,18 +19,124 @@ use convert_case::{Case, Casing};
 use protobuf::well_known_types::struct_::value;
 use swc_common::DUMMY_SP;
 use swc_ecma_ast::{
-    ArrayLit, ArrayPat, BinaryOp, BlockStmt, ClassMember, ClassMethod, Expr, Function, MethodKind, ObjectLit, Param, Pat, PatOrExpr, PropName, Stmt, TsType, UnaryOp, TsNonNullExpr
+    ArrayLit, ArrayPat, AssignPat, BinaryOp, BindingIdent, BlockStmt, ClassMember, ClassMethod, Decl, ExportDecl, Expr, ForHead, ForOfStmt, Function, MethodKind, ModuleDecl, ModuleItem, ObjectLit, Param, Pat, PatOrExpr, PropName, Stmt, SwitchCase, SwitchStmt, TsAsExpr, TsEntityName, TsInterfaceBody, TsInterfaceDecl, TsKeywordTypeKind, TsPropertySignature, TsType, TsTypeElement, TsTypeParamInstantiation, TsTypeRef, UnaryOp
 };
 use swc_ecma_utils::{quote_ident, quote_str};
 
-pub(crate) fn json_key_name_field_member(field: &FieldDescriptorProto) -> Expr {
-    crate::member_expr!("json", field.json_key_name())
-}
+// the json options and helpers shared by every generated file, emitted at the root of the output.
+pub const JSON_SUPPORT_MODULE: &str = "json_support";
+pub const JSON_SUPPORT_SOURCE: &str = include_str!("../../js/runtime/json_support.ts");
 
 pub(crate) fn name_field_member(field: &FieldDescriptorProto) -> Expr {
     crate::member_expr!("json", field.name())
 }
 
+pub(super) fn json_param(name: &str, type_ann: TsType) -> Param {
+    Param {
+        span: DUMMY_SP,
+        decorators: vec![],
+        pat: Pat::Ident(BindingIdent {
+            id: quote_ident!(name),
+            type_ann: Some(Box::new(crate::type_annotation!(type_ann))),
+        }),
+    }
+}
+
+// options?: JsonWriteOptions
+pub(super) fn json_options_param(ctx: &Context, options_type: &str) -> Param {
+    json_param(
+        "options?",
+        crate::type_ref!(crate::entity_name_ident!(
+            ctx.lazy_support_ref(JSON_SUPPORT_MODULE, options_type)
+        )),
+    )
+}
+
+// an enum value read by Enum_fromJson, which is undefined for an unknown name under
+// ignoreUnknownFields and then left out.
+// const enum_value = Enum_fromJson(...); if (enum_value !== undefined) store(enum_value);
+pub(super) fn known_enum_stmts(value: Expr, store: impl FnOnce(Expr) -> Stmt) -> Vec<Stmt> {
+    vec![
+        Stmt::Decl(crate::const_decl!("enum_value", value)),
+        crate::if_stmt!(
+            crate::bin_expr!(quote_ident!("enum_value").into(), quote_ident!("undefined").into(), BinaryOp::NotEqEq),
+            crate::block_stmt!(vec![store(quote_ident!("enum_value").into())])
+        ),
+    ]
+}
+
+// options?.emitDefaultValues === true
+pub(super) fn json_option_set(name: &str) -> Expr {
+    crate::bin_expr!(
+        crate::member_expr!("options?", name),
+        quote_ident!("true").into(),
+        BinaryOp::EqEqEq
+    )
+}
+
+// path: string[], the object keys leading to a value seen by parseJson.
+fn json_path_list_param() -> Param {
+    json_param(
+        "path",
+        TsType::TsArrayType(swc_ecma_ast::TsArrayType {
+            span: DUMMY_SP,
+            elem_type: Box::new(crate::keyword_type!(TsKeywordTypeKind::TsStringKeyword)),
+        }),
+    )
+}
+
+// path: string = "$", the json path of the value being parsed for the errors.
+pub(super) fn json_path_param() -> Param {
+    Param {
+        span: DUMMY_SP,
+        decorators: vec![],
+        pat: Pat::Assign(AssignPat {
+            span: DUMMY_SP,
+            left: Box::new(Pat::Ident(BindingIdent {
+                id: quote_ident!("path"),
+                type_ann: Some(Box::new(crate::type_annotation!(crate::keyword_type!(
+                    TsKeywordTypeKind::TsStringKeyword
+                )))),
+            })),
+            right: Box::new(quote_str!("$").into()),
+            type_ann: None,
+        }),
+    }
+}
+
+// path + ".items" + "[" + i + "]"
+pub(super) fn json_path(segments: Vec<Expr>) -> Expr {
+    segments
+        .into_iter()
+        .fold(quote_ident!("path").into(), |path, segment| {
+            crate::bin_expr!(path, segment, BinaryOp::Add)
+        })
+}
+
+// throw new ProtoJsonError(path, message);
+pub(super) fn throw_json_error(ctx: &Context, path: Expr, message: Expr) -> Stmt {
+    crate::throw_stmt!(crate::new_expr!(
+        ctx.lazy_support_ref(JSON_SUPPORT_MODULE, "ProtoJsonError").into(),
+        vec![crate::expr_or_spread!(path), crate::expr_or_spread!(message)]
+    ))
+}
+
+// if (options?.strict === true) { ... }
+pub(super) fn strict_checks_stmt(stmts: Vec<Stmt>) -> Stmt {
+    crate::if_stmt!(json_option_set("strict"), crate::block_stmt!(stmts))
+}
+
+fn json_generic_type(name: &str, params: Vec<TsType>) -> TsType {
+    TsType::TsTypeRef(TsTypeRef {
+        span: DUMMY_SP,
+        type_name: TsEntityName::Ident(quote_ident!(name)),
+        type_params: Some(Box::new(TsTypeParamInstantiation {
+            span: DUMMY_SP,
+            params: params.into_iter().map(Box::new).collect(),
+        })),
+    })
+}
+
 impl FieldDescriptorProto {
     fn json_repr_for_well_known_type(&self) -> &str {
         match self.type_name().trim_start_matches(".") {
@@ -52,96 +158,130 @@ impl FieldDescriptorProto {
             _ => "object",
         }
     }
-    fn typeof_expr_for_well_known_type(&self, accessor: FieldAccessorFn) -> Expr {
-        self.typeof_expr_for_type(accessor, self.json_repr_for_well_known_type())
+    // the key of a 64-bit integer as seen by parseJson, relative to the key of this field: the
+    // value itself, the values of a map, or a key of the nested message.
+    // path.length === depth + 1 / Msg.isInt64Path(path, depth + 1)
+    fn int64_path_check(&self, ctx: &mut Context) -> Option<Expr> {
+        let (value, offset) = if self.is_map(ctx) {
+            let descriptor = ctx
+                .get_map_type(self.type_name())
+                .unwrap_or_else(|| panic!("can not find the map type {}", self.type_name()));
+            (descriptor.field[1].clone(), 2)
+        } else {
+            (self.clone(), 1)
+        };
+        let depth = crate::bin_expr!(
+            quote_ident!("depth").into(),
+            crate::lit_num!(offset).into(),
+            BinaryOp::Add
+        );
+        let at_depth = crate::bin_expr!(
+            crate::member_expr!("path", "length"),
+            depth.clone(),
+            BinaryOp::EqEqEq
+        );
+        if value.is_bigint() {
+            return Some(at_depth);
+        }
+        if !value.is_message() && !value.is_group() {
+            return None;
+        }
+        match value.type_name() {
+            ".google.protobuf.Int64Value" | ".google.protobuf.UInt64Value" => Some(at_depth),
+            // read by hand written codecs, numbers below them are doubles.
+            ".google.protobuf.Any"
+            | ".google.protobuf.Struct"
+            | ".google.protobuf.Value"
+            | ".google.protobuf.ListValue"
+            | ".google.protobuf.Timestamp"
+            | ".google.protobuf.Duration"
+            | ".google.protobuf.FieldMask"
+            | ".google.protobuf.DoubleValue"
+            | ".google.protobuf.FloatValue"
+            | ".google.protobuf.Int32Value"
+            | ".google.protobuf.UInt32Value"
+            | ".google.protobuf.BoolValue"
+            | ".google.protobuf.StringValue"
+            | ".google.protobuf.BytesValue" => None,
+            _ => Some(crate::call_expr!(
+                crate::member_expr!(ctx.lazy_type_ref(value.type_name()), "isInt64Path"),
+                vec![
+                    crate::expr_or_spread!(quote_ident!("path").into()),
+                    crate::expr_or_spread!(depth),
+                ]
+            )),
+        }
+    }
+
+    fn typeof_expr_for_well_known_type(&self, value: &Expr) -> Expr {
+        self.typeof_expr_for_type(value, self.json_repr_for_well_known_type())
     }
 
-    fn typeof_expr_for_type(&self, accessor: FieldAccessorFn, typ: &str) -> Expr {
+    fn typeof_expr_for_type(&self, value: &Expr, typ: &str) -> Expr {
         match typ {
             "unknown" => crate::paren_expr!(crate::chain_bin_exprs_or!(
-                crate::typeof_unary_expr!(accessor(self).into(), "number"),
-                crate::typeof_unary_expr!(accessor(self).into(), "string"),
-                crate::typeof_unary_expr!(accessor(self).into(), "boolean"),
-                crate::typeof_unary_expr!(accessor(self).into(), "object"),
-                crate::bin_expr!(
-                    accessor(self).into(),
-                    quote_ident!("null").into(),
-                    BinaryOp::EqEqEq
-                )
+                crate::typeof_unary_expr!(value.clone(), "number"),
+                crate::typeof_unary_expr!(value.clone(), "string"),
+                crate::typeof_unary_expr!(value.clone(), "boolean"),
+                crate::typeof_unary_expr!(value.clone(), "object"),
+                crate::bin_expr!(value.clone(), quote_ident!("null").into(), BinaryOp::EqEqEq)
             )),
             "number|string" => crate::paren_expr!(crate::chain_bin_exprs_or!(
-                crate::typeof_unary_expr!(accessor(self).into(), "number"),
-                crate::typeof_unary_expr!(accessor(self).into(), "string")
+                crate::typeof_unary_expr!(value.clone(), "number"),
+                crate::typeof_unary_expr!(value.clone(), "string")
             )),
             "array" => crate::call_expr!(
                 crate::member_expr!("Array", "isArray"),
-                vec![crate::expr_or_spread!(accessor(self).into())]
-            ),
-            "null" => crate::bin_expr!(
-                accessor(self).into(),
-                quote_ident!("null").into(),
-                BinaryOp::EqEqEq
+                vec![crate::expr_or_spread!(value.clone())]
             ),
-            typ => crate::typeof_unary_expr!(accessor(self).into(), typ),
+            "null" => crate::bin_expr!(value.clone(), quote_ident!("null").into(), BinaryOp::EqEqEq),
+            typ => crate::typeof_unary_expr!(value.clone(), typ),
         }
     }
 
-    pub(self) fn infinity_and_nan_check(&self, accessor: FieldAccessorFn) -> Expr {
+    pub(self) fn infinity_and_nan_check(&self, value: &Expr) -> Expr {
         crate::chain_bin_exprs_or!(
-            crate::bin_expr!(
-                accessor(self),
-                crate::lit_str!("NaN").into(),
-                BinaryOp::EqEqEq
-            ),
-            crate::bin_expr!(
-                accessor(self),
-                crate::lit_str!("Infinity").into(),
-                BinaryOp::EqEqEq
-            ),
-            crate::bin_expr!(
-                accessor(self),
-                crate::lit_str!("-Infinity").into(),
-                BinaryOp::EqEqEq
-            )
+            crate::bin_expr!(value.clone(), crate::lit_str!("NaN").into(), BinaryOp::EqEqEq),
+            crate::bin_expr!(value.clone(), crate::lit_str!("Infinity").into(), BinaryOp::EqEqEq),
+            crate::bin_expr!(value.clone(), crate::lit_str!("-Infinity").into(), BinaryOp::EqEqEq)
         )
     }
 
-    pub(self) fn min_max_check<T>(&self, accessor: FieldAccessorFn, min: T, max: T) -> Expr
+    pub(self) fn min_max_check<T>(&self, number: Expr, min: T, max: T) -> Expr
     where
         T: Display + LowerExp,
     {
         crate::paren_expr!(crate::chain_bin_exprs_and!(
             crate::bin_expr!(
-                accessor(self),
+                number.clone(),
                 quote_ident!(format!("{:+e}", min)).into(),
                 BinaryOp::GtEq
             ),
             crate::bin_expr!(
-                accessor(self),
+                number,
                 quote_ident!(format!("{:+e}", max)).into(),
                 BinaryOp::LtEq
             )
         ))
     }
 
-    pub(self) fn min_max_check_bigint<T>(&self, accessor: FieldAccessorFn, min: T, max: T) -> Expr
+    pub(self) fn min_max_check_bigint<T>(&self, number: Expr, min: T, max: T) -> Expr
     where
         T: Into<num_bigint::BigInt>,
     {
         crate::paren_expr!(crate::chain_bin_exprs_and!(
             crate::bin_expr!(
-                accessor(self),
+                number.clone(),
                 crate::lit_bigint!(min.into()).into(),
                 BinaryOp::GtEq
             ),
             crate::bin_expr!(
-                accessor(self),
+                number,
                 crate::lit_bigint!(max.into()).into(),
                 BinaryOp::LtEq
             )
         ))
     }
-
     pub(self) fn default_value_bin_expr_for_json(
         &self,
         ctx: &mut Context,
@@ -165,19 +305,12 @@ impl FieldDescriptorProto {
             crate::chain_bin_exprs_and!(neq_null_check, neq_undefined_check)
         };
 
-        let presence_check = if self.has_oneof_index() {
+        let presence_check = if self.has_oneof_index() || self.is_map(ctx) {
             // for oneof field we have to serialize the value unconditionally even if the value is the default.
             neq_null_or_undefined_check
-        } else if self.is_map(ctx) {
-            crate::bin_expr!(
-                neq_null_or_undefined_check,
-                crate::bin_expr!(
-                    crate::member_expr_bare!(accessor(self), "size"),
-                    Expr::Lit(crate::lit_num!(0)),
-                    BinaryOp::NotEqEq
-                )
-            )
-        } else if (self.is_bytes() && ctx.syntax == &Syntax::Proto3) || self.is_repeated() {
+        } else if (self.is_bytes() && ctx.syntax == &Syntax::Proto3 && !self.proto3_optional())
+            || self.is_repeated()
+        {
             crate::bin_expr!(
                 neq_null_or_undefined_check,
                 crate::bin_expr!(
@@ -192,7 +325,8 @@ impl FieldDescriptorProto {
 
         let default_expr = self.proto3_default(ctx);
 
-        if default_expr.is_some() && ctx.syntax == &Syntax::Proto3 && !self.has_oneof_index() {
+        // int64 values are strings in json, the default check does not apply.
+        if default_expr.is_some() && ctx.syntax == &Syntax::Proto3 && !self.has_oneof_index() && !self.is_bigint() {
             crate::bin_expr!(
                 presence_check,
                 crate::bin_expr!(accessor(self), default_expr.unwrap(), BinaryOp::NotEqEq)
@@ -202,106 +336,119 @@ impl FieldDescriptorProto {
         }
     }
 
-    pub(self) fn value_check_stmt(&self, ctx: &Context, accessor: FieldAccessorFn) -> Stmt {
-        let min_max_check: Option<Expr> = match self.type_() {
-            Type::TYPE_FLOAT => Some(self.min_max_check(accessor, f32::MIN, f32::MAX)),
-            Type::TYPE_DOUBLE => Some(self.min_max_check(accessor, f64::MIN, f64::MAX)),
+    // integers are numbers or strings without whitespace, String() keeps the check well typed
+    // when the json type of the field is only one of them.
+    fn integer_text_check(&self, value: &Expr) -> Expr {
+        let text = crate::call_expr!(
+            quote_ident!("String").into(),
+            vec![crate::expr_or_spread!(value.clone())]
+        );
+        crate::chain_bin_exprs_and!(
+            crate::paren_expr!(crate::chain_bin_exprs_or!(
+                self.typeof_expr_for_type(value, "number"),
+                crate::chain_bin_exprs_and!(
+                    self.typeof_expr_for_type(value, "string"),
+                    crate::bin_expr!(
+                        crate::call_expr!(
+                            crate::member_expr_bare!(text.clone(), "indexOf"),
+                            vec![crate::expr_or_spread!(crate::lit_str!(" ").into())]
+                        ),
+                        crate::lit_num!(-1).into(),
+                        BinaryOp::EqEqEq
+                    ),
+                    crate::bin_expr!(text, crate::lit_str!("").into(), BinaryOp::NotEqEq)
+                )
+            )),
+            crate::call_expr!(
+                crate::member_expr!("Number", "isInteger"),
+                vec![crate::expr_or_spread!(crate::call_expr!(
+                    quote_ident!("Number").into(),
+                    vec![crate::expr_or_spread!(value.clone())]
+                ))]
+            )
+        )
+    }
+
+    // int64, pkg.Message, the type named by the errors, map values have no field name of their own.
+    fn json_type_label(&self) -> String {
+        if self.is_message() || self.is_enum() {
+            self.type_name().trim_start_matches('.').to_string()
+        } else {
+            format!("{:?}", self.type_())
+                .trim_start_matches("TYPE_")
+                .to_lowercase()
+        }
+    }
+
+    // if (!(check)) throw new ProtoJsonError(path, "illegal int64 value");
+    pub(super) fn value_check_stmt(&self, ctx: &Context, value: Expr, path: Expr) -> Stmt {
+        let number = crate::call_expr!(
+            quote_ident!("Number").into(),
+            vec![crate::expr_or_spread!(value.clone())]
+        );
+        let bigint = crate::call_expr!(
+            quote_ident!("BigInt").into(),
+            vec![crate::expr_or_spread!(value.clone())]
+        );
+        let range_check: Option<Expr> = match self.type_() {
+            Type::TYPE_FLOAT => Some(self.min_max_check(number, f32::MIN, f32::MAX)),
+            Type::TYPE_DOUBLE => Some(self.min_max_check(number, f64::MIN, f64::MAX)),
             Type::TYPE_UINT32 | Type::TYPE_FIXED32 => {
-                Some(self.min_max_check(accessor, u32::MIN, u32::MAX))
+                Some(self.min_max_check(number, u32::MIN, u32::MAX))
             }
             Type::TYPE_UINT64 | Type::TYPE_FIXED64 => {
-                Some(self.min_max_check_bigint(accessor, u64::MIN, u64::MAX))
+                Some(self.min_max_check_bigint(bigint, u64::MIN, u64::MAX))
             }
             Type::TYPE_INT32 | Type::TYPE_SFIXED32 | Type::TYPE_SINT32 => {
-                Some(self.min_max_check(accessor, i32::MIN, i32::MAX))
+                Some(self.min_max_check(number, i32::MIN, i32::MAX))
             }
             Type::TYPE_INT64 | Type::TYPE_SFIXED64 | Type::TYPE_SINT64 => {
-                Some(self.min_max_check_bigint(accessor, i64::MIN, i64::MAX))
+                Some(self.min_max_check_bigint(bigint, i64::MIN, i64::MAX))
             }
             _ => None,
         };
 
-        let num_check = if min_max_check.is_some() {
-            Some(crate::chain_bin_exprs_or!(
-                self.infinity_and_nan_check(accessor),
-                min_max_check.unwrap()
-            ))
-        } else {
-            None
-        };
-
-        let typeof_check = if self.is_well_known_message() {
-            self.typeof_expr_for_well_known_type(accessor)
+        let check = if self.is_well_known_message() {
+            self.typeof_expr_for_well_known_type(&value)
         } else if self.is_string() || self.is_bytes() {
-            self.typeof_expr_for_type(accessor, "string")
+            self.typeof_expr_for_type(&value, "string")
         } else if self.is_booelan() {
-            self.typeof_expr_for_type(accessor, "boolean")
+            self.typeof_expr_for_type(&value, "boolean")
         } else if self.is_message() {
-            /* also map */
-            self.typeof_expr_for_type(accessor, "object")
-        } else if self.is_integer() {
-            // integer (non-bigint-float-double) needs special check
+            self.typeof_expr_for_type(&value, "object")
+        } else if self.is_null_value() {
+            self.typeof_expr_for_type(&value, "null")
+        } else if self.is_enum() {
+            // the names are checked by the enum parser.
+            self.typeof_expr_for_type(&value, "number|string")
+        } else if self.is_float() {
+            // NaN and the infinities are the only non-numeric strings.
             crate::chain_bin_exprs_and!(
+                self.typeof_expr_for_type(&value, "number|string"),
                 crate::paren_expr!(crate::chain_bin_exprs_or!(
-                    self.typeof_expr_for_type(accessor, "number"),
-                    crate::chain_bin_exprs_and!(
-                        self.typeof_expr_for_type(accessor, "string"),
-                        crate::bin_expr!(
-                            crate::call_expr!(
-                                crate::member_expr_bare!(accessor(self), "indexOf"),
-                                vec![crate::expr_or_spread!(crate::lit_str!(" ").into())]
-                            ),
-                            crate::lit_num!(-1).into(),
-                            BinaryOp::EqEqEq
-                        )
-                    )
-                )),
-                crate::call_expr!(
-                    crate::member_expr!("Number", "isInteger"),
-                    vec![crate::expr_or_spread!(crate::unary_expr!(
-                        accessor(self).into(),
-                        UnaryOp::Plus
-                    ))]
-                )
+                    self.infinity_and_nan_check(&value),
+                    range_check.expect("floats have a range")
+                ))
             )
         } else if self.is_number() {
-            self.typeof_expr_for_type(accessor, "number|string")
-        } else if self.is_enum() {
-            crate::chain_bin_exprs_or!(
-                self.typeof_expr_for_type(accessor, "number"),
-                crate::chain_bin_exprs_and!(
-                    self.typeof_expr_for_type(accessor, "string"),
-                    crate::bin_expr!(
-                        accessor(self).into(),
-                        ctx.lazy_type_ref(self.type_name()).into(),
-                        BinaryOp::In
-                    )
-                )
+            crate::chain_bin_exprs_and!(
+                self.integer_text_check(&value),
+                range_check.expect("integers have a range")
             )
         } else {
-            self.typeof_expr_for_type(accessor, "never!")
-        };
-
-        let check = if num_check.is_some() {
-            crate::chain_bin_exprs_and!(typeof_check, crate::paren_expr!(num_check.unwrap()))
-        } else {
-            typeof_check
+            self.typeof_expr_for_type(&value, "never!")
         };
 
         crate::if_stmt!(
             crate::unary_expr!(crate::paren_expr!(check)),
-            crate::throw_stmt!(crate::new_expr!(
-                quote_ident!("Error").into(),
-                vec![crate::expr_or_spread!(crate::lit_str!(format!(
-                    "illegal value for {}",
-                    self.json_key_name()
-                ))
-                .into())]
-            ))
+            throw_json_error(
+                ctx,
+                path,
+                crate::lit_str!(format!("illegal {} value", self.json_type_label())).into()
+            )
         )
     }
-
-    pub(self) fn json_key_name(&self) -> String {
+    pub(super) fn json_key_name(&self) -> String {
         if self.has_json_name() {
             self.json_name().to_string()
         } else {
@@ -326,14 +473,16 @@ impl FieldDescriptorProto {
         accessor_fn: super::field::FieldAccessorFn,
     ) -> Expr {
         let accessor = accessor_fn(self);
-        if self.is_enum() {
-            crate::call_expr!(crate::member_expr_bare!(accessor_fn(self), "valueOf"))
-
-            // crate::bin_expr!(
-            //     crate::member_expr_computed!(ctx.lazy_type_ref(self.type_name()).into(), accessor),
-            //     accessor_fn(self).into(),
-            //     BinaryOp::NullishCoalescing
-            // )
+        if self.is_null_value() {
+            quote_ident!("null").into()
+        } else if self.is_enum() {
+            crate::call_expr!(
+                ctx.lazy_companion_type_ref(self.type_name(), "_toJson").into(),
+                vec![
+                    crate::expr_or_spread!(accessor),
+                    crate::expr_or_spread!(quote_ident!("options").into())
+                ]
+            )
         } else if self.is_bytes() {
             ctx.get_base64_import(ctx.options.base64_package.as_str());
             let mut params = vec![crate::expr_or_spread!(accessor)];
@@ -346,8 +495,7 @@ impl FieldDescriptorProto {
                 params
             )
         } else if self.is_bigint() {
-            // crate::call_expr!(crate::member_expr_bare!(accessor, "toString"))
-            accessor
+            crate::call_expr!(crate::member_expr_bare!(accessor, "toString"))
         } else if self.is_number() {
             crate::cond_expr!(
                 crate::call_expr!(
@@ -358,7 +506,18 @@ impl FieldDescriptorProto {
                 super::field::to_string_normalizer(&accessor)
             )
         } else if self.is_message() && !self.is_map(ctx) {
-            crate::call_expr!(crate::member_expr_bare!(accessor, "toJson"))
+            // the hand written well-known type codecs are not typed and take no options.
+            match self.well_known_json_type(ctx) {
+                Some(type_ann) => Expr::TsAs(TsAsExpr {
+                    span: DUMMY_SP,
+                    expr: Box::new(crate::call_expr!(crate::member_expr_bare!(accessor, "toJson"))),
+                    type_ann: Box::new(type_ann),
+                }),
+                None => crate::call_expr!(
+                    crate::member_expr_bare!(accessor, "toJson"),
+                    vec![crate::expr_or_spread!(quote_ident!("options").into())]
+                ),
+            }
         } else {
             accessor
         }
@@ -368,12 +527,13 @@ impl FieldDescriptorProto {
         &self,
         ctx: &mut Context,
         accessor_fn: super::field::FieldAccessorFn,
+        path: Expr,
     ) -> Expr {
         let accessor = accessor_fn(self);
         if self.is_booelan() {
             crate::bin_expr!(accessor, quote_str!("true").into(), BinaryOp::EqEqEq)
         } else {
-            self.into_from_json_expr(ctx, accessor_fn)
+            self.into_from_json_expr(ctx, accessor_fn, path)
         }
     }
 
@@ -381,13 +541,19 @@ impl FieldDescriptorProto {
         &self,
         ctx: &mut Context,
         accessor_fn: super::field::FieldAccessorFn,
+        path: Expr,
     ) -> Expr {
         let accessor = accessor_fn(self);
-        if self.is_enum() {
-            crate::cond_expr!(
-                crate::typeof_unary_expr!(accessor_fn(self).into(), "number"),
-                accessor_fn(self).into(),
-                crate::member_expr_computed!(ctx.lazy_type_ref(self.type_name()).into(), accessor)
+        if self.is_null_value() {
+            crate::member_expr!(ctx.lazy_type_ref(self.type_name()), "NULL_VALUE")
+        } else if self.is_enum() {
+            crate::call_expr!(
+                ctx.lazy_companion_type_ref(self.type_name(), "_fromJson").into(),
+                vec![
+                    crate::expr_or_spread!(accessor),
+                    crate::expr_or_spread!(quote_ident!("options").into()),
+                    crate::expr_or_spread!(path)
+                ]
             )
         } else if self.is_bytes() {
             let mut method = Expr::Ident(quote_ident!("toUint8Array"));
@@ -417,108 +583,457 @@ impl FieldDescriptorProto {
                 vec![crate::expr_or_spread!(accessor)]
             )
         } else if self.is_message() && !self.is_map(ctx) {
+            let mut args = vec![crate::expr_or_spread!(accessor)];
+            if self.well_known_json_type(ctx).is_none() {
+                args.push(crate::expr_or_spread!(quote_ident!("options").into()));
+                args.push(crate::expr_or_spread!(path));
+            }
             crate::call_expr!(
                 crate::member_expr_bare!(ctx.lazy_type_ref(self.type_name()).into(), "fromJson"),
-                vec![crate::expr_or_spread!(accessor)]
+                args
             )
         } else {
             accessor
         }
     }
+
+    // emitDefaultValues only applies to the fields which do not track their presence.
+    fn has_json_presence(&self, ctx: &mut Context) -> bool {
+        (self.is_message() && !self.is_map(ctx))
+            || self.has_oneof_index()
+            || self.proto3_optional()
+            || (ctx.syntax == &Syntax::Proto2 && !self.is_repeated())
+    }
+
+    // whether toJson writes the field, called on the field as declared by the message.
+    pub(super) fn json_write_check(&self, ctx: &mut Context) -> Expr {
+        let default_check = self.default_value_bin_expr(ctx, super::field::this_field_member);
+        if self.has_json_presence(ctx) {
+            return default_check;
+        }
+        crate::chain_bin_exprs_or!(
+            crate::paren_expr!(crate::chain_bin_exprs_and!(
+                json_option_set("emitDefaultValues"),
+                crate::bin_expr!(
+                    super::field::this_field_member(self),
+                    quote_ident!("undefined").into(),
+                    BinaryOp::NotEqEq
+                )
+            )),
+            default_check
+        )
+    }
+
+    // json[options?.useProtoFieldName === true ? "field_name" : "fieldName"]
+    fn json_output_member(&self) -> Expr {
+        let json_key = quote_str!(self.json_key_name()).into();
+        let key = if self.name() == self.json_key_name() {
+            json_key
+        } else {
+            crate::cond_expr!(
+                json_option_set("useProtoFieldName"),
+                quote_str!(self.name()).into(),
+                json_key
+            )
+        };
+        crate::member_expr_computed!(quote_ident!("json").into(), key)
+    }
+
+    // path + ".key"
+    pub(super) fn json_field_path(&self) -> Expr {
+        json_path(vec![quote_str!(format!(".{}", self.json_key_name())).into()])
+    }
+
+    // path + ".key[" + i + "]"
+    pub(super) fn json_element_path(&self, index: Expr) -> Expr {
+        json_path(vec![
+            quote_str!(format!(".{}[", self.json_key_name())).into(),
+            index,
+            quote_str!("]").into(),
+        ])
+    }
+
+    // path + ".key[\"" + k + "\"]"
+    pub(super) fn json_map_value_path(&self, key: Expr) -> Expr {
+        json_path(vec![
+            quote_str!(format!(".{}[\"", self.json_key_name())).into(),
+            key,
+            quote_str!("\"]").into(),
+        ])
+    }
+
+    // repeated fields are arrays and maps are objects.
+    pub(super) fn container_check_stmt(&self, ctx: &Context, value: Expr) -> Stmt {
+        let is_array = crate::call_expr!(
+            crate::member_expr!("Array", "isArray"),
+            vec![crate::expr_or_spread!(value.clone())]
+        );
+        let (check, expected) = if self.is_map(ctx) {
+            (
+                crate::chain_bin_exprs_and!(
+                    crate::typeof_unary_expr!(value, "object"),
+                    crate::unary_expr!(is_array)
+                ),
+                "an object",
+            )
+        } else {
+            (is_array, "an array")
+        };
+        crate::if_stmt!(
+            crate::unary_expr!(crate::paren_expr!(check)),
+            throw_json_error(
+                ctx,
+                self.json_field_path(),
+                crate::lit_str!(format!("expected {} for {}", expected, self.json_key_name())).into()
+            )
+        )
+    }
+
+    // google.protobuf.NullValue is always null in json.
+    pub(super) fn is_null_value(&self) -> bool {
+        self.is_enum() && self.type_name() == ".google.protobuf.NullValue"
+    }
+
+    // well-known types with a hand written json codec and the type of their json representation.
+    pub(super) fn well_known_json_type(&self, ctx: &mut Context) -> Option<TsType> {
+        let keyword = match self.type_name() {
+            ".google.protobuf.Timestamp"
+            | ".google.protobuf.Duration"
+            | ".google.protobuf.FieldMask"
+            | ".google.protobuf.StringValue"
+            | ".google.protobuf.BytesValue"
+            | ".google.protobuf.Int64Value"
+            | ".google.protobuf.UInt64Value" => TsKeywordTypeKind::TsStringKeyword,
+            ".google.protobuf.DoubleValue"
+            | ".google.protobuf.FloatValue"
+            | ".google.protobuf.Int32Value"
+            | ".google.protobuf.UInt32Value" => TsKeywordTypeKind::TsNumberKeyword,
+            ".google.protobuf.BoolValue" => TsKeywordTypeKind::TsBooleanKeyword,
+            ".google.protobuf.Any" if ctx.options.arkts_strict => {
+                let provided_by = ctx
+                    .find_type_provider(&self.type_name().to_string())
+                    .expect("expected to find a proto file for the type");
+                return Some(crate::type_ref!(crate::entity_name_ident!(
+                    ctx.lazy_symbol_ref(&provided_by, "AnyJson")
+                )));
+            }
+            ".google.protobuf.Any" => TsKeywordTypeKind::TsObjectKeyword,
+            ".google.protobuf.Struct" | ".google.protobuf.Value" | ".google.protobuf.ListValue" => {
+                return Some(self.plain_struct_type(ctx))
+            }
+            _ => return None,
+        };
+        Some(crate::keyword_type!(keyword))
+    }
+
+    // the proto3 json type of a single value of this field.
+    pub(super) fn json_value_type(&self, ctx: &mut Context) -> TsType {
+        if self.is_plain_struct(ctx) {
+            return self.plain_struct_type(ctx);
+        }
+        if self.is_message() {
+            if let Some(json_type) = self.well_known_json_type(ctx) {
+                return json_type;
+            }
+            return crate::type_ref!(crate::entity_name_ident!(
+                ctx.lazy_companion_type_ref(self.type_name(), "Json")
+            ));
+        }
+        if self.is_null_value() {
+            return crate::keyword_type!(TsKeywordTypeKind::TsNullKeyword);
+        }
+        if self.is_enum() {
+            return crate::type_ref!(crate::entity_name_ident!(
+                ctx.lazy_companion_type_ref(self.type_name(), "Json")
+            ));
+        }
+        if self.is_float() {
+            // NaN and the infinities are written as strings.
+            return *crate::type_union!(
+                crate::keyword_type!(TsKeywordTypeKind::TsNumberKeyword),
+                crate::keyword_type!(TsKeywordTypeKind::TsStringKeyword)
+            )
+            .type_ann;
+        }
+        let keyword = if self.is_string() || self.is_bytes() || self.is_bigint() {
+            TsKeywordTypeKind::TsStringKeyword
+        } else if self.is_booelan() {
+            TsKeywordTypeKind::TsBooleanKeyword
+        } else {
+            TsKeywordTypeKind::TsNumberKeyword
+        };
+        crate::keyword_type!(keyword)
+    }
+
+    // maps are objects keyed by the stringified map key.
+    pub(super) fn json_type(&self, ctx: &mut Context) -> TsType {
+        if self.is_map(ctx) {
+            let descriptor = ctx
+                .get_map_type(self.type_name())
+                .unwrap_or_else(|| panic!("can not find the map type {}", self.type_name()));
+            json_generic_type(
+                "Record",
+                vec![
+                    crate::keyword_type!(TsKeywordTypeKind::TsStringKeyword),
+                    descriptor.field[1].json_value_type(ctx),
+                ],
+            )
+        } else if self.is_repeated() {
+            json_generic_type("Array", vec![self.json_value_type(ctx)])
+        } else {
+            self.json_value_type(ctx)
+        }
+    }
+
+    // parsers accept the proto field name as well as the json name.
+    fn print_json_props(&self, ctx: &mut Context) -> Vec<TsTypeElement> {
+        let unboxed = self.unboxed_wrapper(ctx);
+        let field = unboxed.as_ref().unwrap_or(self);
+        let mut keys = vec![self.json_key_name()];
+        if self.name() != self.json_key_name() {
+            keys.push(self.name().to_string());
+        }
+        let mut props = vec![];
+        for key in keys {
+            props.push(TsTypeElement::TsPropertySignature(TsPropertySignature {
+                span: DUMMY_SP,
+                readonly: false,
+                key: Box::new(quote_ident!(key).into()),
+                computed: false,
+                optional: true,
+                init: None,
+                params: vec![],
+                type_ann: Some(Box::new(crate::type_annotation!(field.json_type(ctx)))),
+                type_params: None,
+            }));
+        }
+        props
+    }
 }
 
 impl DescriptorProto {
-    fn get_map_field_descriptor_str(&self, field: &FieldDescriptorProto) -> &str {
-        if field.is_string() {
-            "string"
-        } else if field.is_bigint() {
-            "bigint"
-        } else if field.is_number() {
-            "number"
-        } else if field.is_booelan() {
-            "boolean"
-        } else if field.is_bytes() {
-            "Uint8Array"
-        } else {
-            "object"
-        }
-    }
-
-    fn get_field_descriptor_str(&self, ctx: &mut Context, field: &FieldDescriptorProto) -> String {
-            let base = if field.is_string() {
-                ": string".to_string()
-            } else if field.is_bigint() {
-                ": bigint".to_string()
-            } else if field.is_number() || field.is_enum() {
-                ": number".to_string()
-            } else if field.is_booelan() {
-                ": boolean".to_string()
-            } else if field.is_bytes() {
-                ": string".to_string()
-            } else if field.is_map(ctx) {
-                let descriptor = ctx
-                    .get_map_type(field.type_name())
-                    .expect(format!("can not find the map type {}", field.type_name()).as_str());
-                let key_type = self.get_map_field_descriptor_str(&descriptor.field[0]);
-                let value_type = self.get_map_field_descriptor_str(&descriptor.field[1]);
-                format!(": Map<{},{}>", key_type, value_type)
-            } else {
-                ": object".to_string()
-            };
-            
-            if field.is_repeated() && !field.is_map(ctx)  {
-                format!("{}[]", base)
-            } else {
-                base
+    pub(super) fn json_interface_type(&self, ctx: &Context) -> TsType {
+        crate::type_ref!(crate::entity_name_ident!(quote_ident!(format!(
+            "{}Json",
+            ctx.normalize_name(self.name())
+        ))))
+    }
+
+    // unknown keys are skipped unless the reader is strict, ignoreUnknownFields skips them even then.
+    // if (options?.strict === true && options?.ignoreUnknownFields !== true) { for (const key of Object.keys(json)) { switch (key) { ... } } }
+    pub(super) fn print_unknown_fields_check(&self, ctx: &Context) -> Stmt {
+        let mut cases = vec![];
+        for field in &self.field {
+            cases.push(quote_str!(field.json_key_name()));
+            if field.name() != field.json_key_name() {
+                cases.push(quote_str!(field.name()));
             }
+        }
+        let mut cases: Vec<SwitchCase> = cases
+            .into_iter()
+            .map(|key| SwitchCase {
+                span: DUMMY_SP,
+                test: Some(Box::new(key.into())),
+                cons: vec![],
+            })
+            .collect();
+        if let Some(last) = cases.last_mut() {
+            last.cons.push(Stmt::Break(swc_ecma_ast::BreakStmt {
+                span: DUMMY_SP,
+                label: None,
+            }));
+        }
+        cases.push(SwitchCase {
+            span: DUMMY_SP,
+            test: None,
+            cons: vec![throw_json_error(
+                ctx,
+                json_path(vec![quote_str!(".").into(), quote_ident!("key").into()]),
+                quote_str!(format!(
+                    "unknown field of {}",
+                    ctx.calculate_type_name(self.name()).trim_start_matches(".")
+                ))
+                .into(),
+            )],
+        });
+
+        crate::if_stmt!(
+            crate::bin_expr!(
+                json_option_set("strict"),
+                crate::bin_expr!(
+                    crate::member_expr!("options?", "ignoreUnknownFields"),
+                    quote_ident!("true").into(),
+                    BinaryOp::NotEqEq
+                )
+            ),
+            crate::block_stmt!(vec![Stmt::ForOf(ForOfStmt {
+                is_await: false,
+                left: ForHead::VarDecl(Box::new(crate::const_decl_uinit!("key"))),
+                right: Box::new(crate::call_expr!(
+                    crate::member_expr!("Object", "keys"),
+                    vec![crate::expr_or_spread!(quote_ident!("json").into())]
+                )),
+                body: Box::new(crate::block_stmt!(vec![Stmt::Switch(SwitchStmt {
+                    span: DUMMY_SP,
+                    discriminant: Box::new(quote_ident!("key").into()),
+                    cases,
+                })])),
+                span: DUMMY_SP,
+            })])
+        )
     }
-     
 
-    fn print_to_json_inner(&self, ctx: &mut Context, index: i32, fields: &Vec<&FieldDescriptorProto>) -> ClassMember {
+    // a oneof takes a single member and a field is keyed by either of its names.
+    // if (options?.strict === true) { if ((json.a !== undefined && json.a !== null ? 1 : 0) + ... > 1) throw ...; }
+    pub(super) fn print_duplicate_fields_check(&self, ctx: &Context) -> Option<Stmt> {
+        let present = |key: &str| {
+            let member = crate::member_expr!("json", key);
+            crate::paren_expr!(crate::cond_expr!(
+                crate::chain_bin_exprs_and!(
+                    crate::bin_expr!(member.clone(), quote_ident!("undefined").into(), BinaryOp::NotEqEq),
+                    crate::bin_expr!(member, quote_ident!("null").into(), BinaryOp::NotEqEq)
+                ),
+                crate::lit_num!(1).into(),
+                crate::lit_num!(0).into()
+            ))
+        };
+        let keys_of = |field: &FieldDescriptorProto| {
+            let mut keys = vec![field.json_key_name()];
+            if field.name() != field.json_key_name() {
+                keys.push(field.name().to_string());
+            }
+            keys
+        };
+
+        let mut groups: Vec<(Vec<String>, Expr, String)> = vec![];
+        for (index, oneof) in self.oneof_decl.iter().enumerate() {
+            let members: Vec<&FieldDescriptorProto> = self
+                .field
+                .iter()
+                .filter(|field| {
+                    field.has_oneof_index()
+                        && field.oneof_index() == index as i32
+                        && !field.proto3_optional()
+                })
+                .collect();
+            if members.is_empty() {
+                continue;
+            }
+            groups.push((
+                members.into_iter().flat_map(keys_of).collect(),
+                quote_ident!("path").into(),
+                format!("multiple values for oneof {}", oneof.name()),
+            ));
+        }
+        for field in &self.field {
+            // members of real oneofs are covered by their oneof.
+            let in_oneof = field.has_oneof_index() && !field.proto3_optional();
+            if field.name() != field.json_key_name() && !in_oneof {
+                groups.push((
+                    keys_of(field),
+                    field.json_field_path(),
+                    format!("duplicate field {}", field.json_key_name()),
+                ));
+            }
+        }
+        if groups.is_empty() {
+            return None;
+        }
+
+        let stmts = groups
+            .into_iter()
+            .map(|(keys, path, message)| {
+                let count = keys
+                    .iter()
+                    .map(|key| present(key))
+                    .reduce(|sum, next| crate::bin_expr!(sum, next, BinaryOp::Add))
+                    .expect("a group has at least one key");
+                crate::if_stmt!(
+                    crate::bin_expr!(count, crate::lit_num!(1).into(), BinaryOp::Gt),
+                    throw_json_error(ctx, path, crate::lit_str!(message).into())
+                )
+            })
+            .collect();
+        Some(strict_checks_stmt(stmts))
+    }
+
+    // export interface MsgJson { name?: string; price?: MoneyJson; ... }
+    pub(super) fn print_json_interface(&self, ctx: &mut Context) -> ModuleItem {
+        let mut body = vec![];
+        for field in &self.field {
+            body.append(&mut field.print_json_props(ctx));
+        }
+
+        ModuleItem::ModuleDecl(ModuleDecl::ExportDecl(ExportDecl {
+            span: DUMMY_SP,
+            decl: Decl::TsInterface(Box::new(TsInterfaceDecl {
+                span: DUMMY_SP,
+                id: quote_ident!(format!("{}Json", ctx.normalize_name(self.name()))),
+                declare: false,
+                type_params: None,
+                extends: vec![],
+                body: TsInterfaceBody { span: DUMMY_SP, body },
+            })),
+        }))
+    }
+
+    fn print_to_json_fields(&self, ctx: &mut Context, fields: &[FieldDescriptorProto]) -> Vec<Stmt> {
         let mut statements = vec![];
 
         for field in fields {
+            let write_check = field.json_write_check(ctx);
+            // unboxed wrappers have the json representation of the wrapped value.
+            let unboxed = field.unboxed_wrapper(ctx);
+            let field = unboxed.as_ref().unwrap_or(field);
             let accessor_fn = if field.is_repeated() && !field.is_map(ctx) {
                 super::field::static_field_member
             } else {
                 super::field::this_field_member
             };
 
-            let mut value_expr = field.into_to_json_expr(ctx, accessor_fn);
+            let mut value_expr = if field.is_plain_struct(ctx) {
+                // already the json representation.
+                accessor_fn(field)
+            } else {
+                field.into_to_json_expr(ctx, accessor_fn)
+            };
             let mut stmts = vec![];
 
             if field.is_map(ctx) {
                 let descriptor = ctx
                     .get_map_type(field.type_name())
                     .expect(format!("can not find the map type {}", field.type_name()).as_str());
-                let key_type: &str = self.get_map_field_descriptor_str(&descriptor.field[0]);
-                let valur_type: &str = self.get_map_field_descriptor_str(&descriptor.field[1]);
-              
-                stmts.push(crate::expr_stmt!(
-                    Expr::Ident(quote_ident!(
-                        format!("json[\"{}\"] = new Map<{},{}>()", field.name(), key_type, valur_type)))));
-                stmts.push(crate::expr_stmt!(crate::call_expr!(
-                    crate::member_expr_bare!( crate::member_expr!("this", field.name()), "forEach"),
-                    vec![crate::expr_or_spread!(crate::arrow_func!(
-                        vec![crate::pat_ident!(quote_ident!("value")), crate::pat_ident!(quote_ident!("key"))],
-                        vec![
-                            crate::expr_stmt!(crate::call_expr!(
-                                crate::member_expr_bare!(
-                                    crate::member_expr_computed!(Expr::Ident(quote_ident!("json")), Expr::Ident(quote_ident!(format!("\"{}\"", field.name())))), "set"),
-                                vec![
-                                    crate::expr_or_spread!(Expr::TsNonNull(TsNonNullExpr {
-                                        expr: Box::new(Expr::Ident(quote_ident!("key"))),
-                                        span: DUMMY_SP
-                                    })),
-                                    crate::expr_or_spread!(Expr::TsNonNull(TsNonNullExpr {
-                                        expr: Box::new(Expr::Ident(quote_ident!("value"))),
-                                        span: DUMMY_SP
-                                    })),
-                                ]
-                            ))
-                        ]
-                    ))]
-                )))
+                let local = quote_ident!(format!("{}_json", field.name()));
+                let key = descriptor.field[0].into_to_stringified_map_expr(ctx);
+                let value = descriptor.field[1].into_to_stringified_map_expr(ctx);
+
+                stmts.push(Stmt::Decl(crate::let_decl!(
+                    local.sym.as_ref(),
+                    Some(Box::new(crate::type_annotation!(field.json_type(ctx)))),
+                    Expr::Object(ObjectLit {
+                        span: DUMMY_SP,
+                        props: vec![],
+                    })
+                )));
+                // for (let entry of binary_support.orderedEntries(this.m.entries(), ...)) { m_json[key] = value; }
+                stmts.push(Stmt::ForOf(ForOfStmt {
+                    is_await: false,
+                    left: ForHead::Pat(Box::new(crate::pat_ident!(quote_ident!("let entry")))),
+                    right: Box::new(field.ordered_entries_expr(ctx, crate::member_expr!("this", field.name()))),
+                    body: Box::new(crate::block_stmt!(vec![
+                        crate::expr_stmt!(Expr::Ident(quote_ident!("let key = entry[0]"))),
+                        crate::expr_stmt!(Expr::Ident(quote_ident!("let value = entry[1]"))),
+                        crate::expr_stmt!(crate::assign_expr!(
+                            PatOrExpr::Expr(Box::new(crate::member_expr_computed!(local.clone().into(), key))),
+                            value
+                        )),
+                    ])),
+                    span: DUMMY_SP,
+                }));
+                stmts.push(crate::expr_stmt!(crate::assign_expr!(
+                    PatOrExpr::Expr(Box::new(field.json_output_member())),
+                    local.into()
+                )));
             } else if field.is_repeated() {
                 let mut field_member_expr = crate::member_expr!("this", field.name());
                 if ctx.options.with_sendable {
@@ -539,14 +1054,14 @@ impl DescriptorProto {
 
             if field.is_map(ctx) {
                 statements.push(crate::if_stmt!(
-                    field.default_value_bin_expr(ctx, super::field::this_field_member),
+                    write_check,
                     crate::block_stmt!(stmts)
                 ))
             } else {
                 statements.push(crate::if_stmt!(
-                    field.default_value_bin_expr(ctx, super::field::this_field_member),
+                    write_check,
                     crate::expr_stmt!(crate::assign_expr!(
-                        PatOrExpr::Expr(Box::new(crate::member_expr_computed!(Expr::Ident(quote_ident!("json")), Expr::Ident(quote_ident!(format!("\"{}\"", field.json_key_name())))))),
+                        PatOrExpr::Expr(Box::new(field.json_output_member())),
                         value_expr
                     ))
                 ))
@@ -554,73 +1069,26 @@ impl DescriptorProto {
             
         }
 
-        ClassMember::Method(ClassMethod {
-            span: DUMMY_SP,
-            accessibility: None,
-            key: PropName::Ident(quote_ident!(format!("toJson_{}", index))),
-            is_abstract: false,
-            is_optional: false,
-            is_override: false,
-            is_static: false,
-            function: Box::new(Function {
-                body: Some(BlockStmt {
-                    span: DUMMY_SP,
-                    stmts: statements,
-                }),
-                decorators: vec![],
-                is_async: false,
-                is_generator: false,
-                params: vec![Param {
-                    span: DUMMY_SP,
-                    decorators: vec![],
-                    pat: swc_ecma_ast::Pat::Ident(swc_ecma_ast::BindingIdent {
-                        id: quote_ident!("json"),
-                        type_ann: Some(Box::new(crate::type_annotation!("object"))),
-                    }),
-                }   
-                ],
-                return_type: None,
-                span: DUMMY_SP,
-                type_params: None,
-            }),
-            kind: MethodKind::Method,
-        })
+        statements
     }
 
     pub(super) fn print_to_json(&self, ctx: &mut Context) -> Vec<ClassMember> {
         let mut statements = vec![Stmt::Decl(crate::const_decl!(
-            "json: object",
-            crate::new_expr!(Expr::Ident(quote_ident!("Object")))
+            format!("json: {}Json", ctx.normalize_name(self.name())),
+            Expr::Object(ObjectLit {
+                span: DUMMY_SP,
+                props: vec![],
+            })
         ))];
-        let mut class_member_vec = Vec::new();
-        let mut cur_field_vec = Vec::new();
-        let mut cur_method_index = 0;
-
-        let mut newst = |fields: &Vec<&FieldDescriptorProto>, index: i32| { 
-            class_member_vec.push(self.print_to_json_inner(ctx, index, fields));
-            // call inner method
-            statements.push(crate::expr_stmt!(crate::call_expr!(
-                crate::member_expr_bare!(Expr::Ident(quote_ident!("this")), format!("toJson_{}", index)),
-                vec![
-                    crate::expr_or_spread!(Expr::Ident(quote_ident!("json")))
-                ]
-            )));
-        };
-
-
-        for field in &self.field {
-            cur_field_vec.push(field);
-            if cur_field_vec.len() >= 30 {
-                // add class member
-                newst(&cur_field_vec, cur_method_index);
-                cur_method_index += 1;
-                cur_field_vec.clear();
-            }
-        }
-    
-        if !cur_field_vec.is_empty() {
-            newst(&cur_field_vec, cur_method_index);
-        }
+        let params = vec![
+            json_param("json", self.json_interface_type(ctx)),
+            json_options_param(ctx, "JsonWriteOptions"),
+        ];
+        let (mut field_stmts, mut class_member_vec) =
+            self.print_method_chunks(ctx, "toJson", false, params, |ctx, fields| {
+                self.print_to_json_fields(ctx, fields)
+            });
+        statements.append(&mut field_stmts);
 
         statements.push(crate::return_stmt!(quote_ident!("json").into()));
 
@@ -640,8 +1108,8 @@ impl DescriptorProto {
                 decorators: vec![],
                 is_async: false,
                 is_generator: false,
-                params: vec![],
-                return_type: Some(Box::new(crate::type_annotation!("Object"))),
+                params: vec![json_options_param(ctx, "JsonWriteOptions")],
+                return_type: Some(Box::new(crate::type_annotation!(self.json_interface_type(ctx)))),
                 span: DUMMY_SP,
                 type_params: None,
             }),
@@ -651,107 +1119,149 @@ impl DescriptorProto {
         class_member_vec
     }
 
-   fn print_from_json_inner(&self, ctx: &mut Context, index: i32, fields: &Vec<&FieldDescriptorProto>) -> ClassMember {
+    fn print_from_json_fields(&self, ctx: &mut Context, fields: &[FieldDescriptorProto]) -> Vec<Stmt> {
         let mut statements = vec![];
-        for field in fields {
+        for declared in fields {
+            let unboxed = declared.unboxed_wrapper(ctx);
+            let field = unboxed.as_ref().unwrap_or(declared);
             let accessor_fn = if field.is_repeated() && !field.is_map(ctx) {
                 super::field::static_field_member
             } else {
                 super::field::bare_field_member
             };
+            let value_path = if field.is_repeated() && !field.is_map(ctx) {
+                field.json_element_path(quote_ident!("i").into())
+            } else {
+                field.json_field_path()
+            };
 
-            let mut value_expr = field.into_from_json_expr(ctx, accessor_fn);
-            if field.is_enum() {
-                if field.is_repeated() {
-                    value_expr = Expr::Ident(quote_ident!("r"));
+            let mut value_expr = field.into_from_json_expr(ctx, accessor_fn, value_path.clone());
+            if field.is_plain_struct(ctx) {
+                // validated by the well-known type, stored as plain json.
+                value_expr = crate::call_expr!(crate::member_expr_bare!(value_expr, "toJson"));
+            }
+            let mut stmts = vec![];
+            if field.is_map(ctx) {
+                // for (const key of Object.keys(m)) { const value = m[key]; jsonMessage.m.set(K(key), V(value)); }
+                let descriptor = ctx
+                    .get_map_type(field.type_name())
+                    .unwrap_or_else(|| panic!("can not find the map type {}", field.type_name()));
+                let entry_path = field.json_map_value_path(quote_ident!("key").into());
+                let key = descriptor.field[0].into_from_json_expr_for_map_key(ctx, super::field::bare_field_member, entry_path.clone());
+                let value = descriptor.field[1].into_from_json_expr(ctx, super::field::bare_field_member, entry_path.clone());
+                let set = |value: Expr| {
+                    crate::expr_stmt!(crate::call_expr!(
+                        crate::member_expr_bare!(crate::member_expr!("jsonMessage", field.name()), "set"),
+                        vec![crate::expr_or_spread!(key.clone()), crate::expr_or_spread!(value)]
+                    ))
+                };
+                let mut entry_stmts = vec![
+                    Stmt::Decl(crate::const_decl!(
+                        "value",
+                        crate::member_expr_computed!(super::field::bare_field_member(field), Expr::Ident(quote_ident!("key")))
+                    )),
+                    strict_checks_stmt(vec![descriptor.field[1].value_check_stmt(
+                        ctx,
+                        quote_ident!("value").into(),
+                        entry_path
+                    )]),
+                ];
+                if descriptor.field[1].is_enum() && !descriptor.field[1].is_null_value() {
+                    entry_stmts.extend(known_enum_stmts(value, set));
                 } else {
-                    value_expr = Expr::Ident(quote_ident!(format!("{}",field.name())));
+                    entry_stmts.push(set(value));
                 }
-            } 
-
-            if field.is_map(ctx) {
-                value_expr = crate::call_expr!(
-                    crate::member_expr_bare!(Expr::Ident(quote_ident!(field.name())), "forEach"),
-                    vec![crate::expr_or_spread!(crate::arrow_func!(
-                        vec![crate::pat_ident!(quote_ident!("value")), crate::pat_ident!(quote_ident!("key"))],
-                        vec![
-                            crate::expr_stmt!(crate::call_expr!(
-                                crate::member_expr_bare!(
-                                    crate::member_expr!("jsonMessage", format!("{}?", field.name())), "set"),
-                                vec![
-                                    crate::expr_or_spread!(Expr::TsNonNull(TsNonNullExpr {
-                                        expr: Box::new(Expr::Ident(quote_ident!("key"))),
-                                        span: DUMMY_SP
-                                    })),
-                                    crate::expr_or_spread!(Expr::TsNonNull(TsNonNullExpr {
-                                        expr: Box::new(Expr::Ident(quote_ident!("value"))),
-                                        span: DUMMY_SP
-                                    })),
-                                ]
-                            ))
-                        ]
-                    ))]
-                );
+                stmts.push(strict_checks_stmt(vec![
+                    field.container_check_stmt(ctx, super::field::bare_field_member(field)),
+                ]));
+                stmts.push(Stmt::ForOf(ForOfStmt {
+                    is_await: false,
+                    left: ForHead::VarDecl(Box::new(crate::const_decl_uinit!("key"))),
+                    right: Box::new(crate::call_expr!(
+                        crate::member_expr!("Object", "keys"),
+                        vec![crate::expr_or_spread!(super::field::bare_field_member(field))]
+                    )),
+                    body: Box::new(crate::block_stmt!(entry_stmts)),
+                    span: DUMMY_SP,
+                }));
             } else if field.is_repeated() {
+                stmts.push(strict_checks_stmt(vec![
+                    field.container_check_stmt(ctx, super::field::bare_field_member(field)),
+                ]));
                 value_expr = crate::call_expr!(
                     crate::member_expr_bare!(super::field::bare_field_member(&field), "map"),
                     vec![crate::expr_or_spread!(crate::arrow_func!(
-                        vec![crate::pat_ident!(quote_ident!("r"))],
                         vec![
-                            // field.value_check_stmt(ctx, super::field::static_field_member),
+                            crate::pat_ident!(quote_ident!("r")),
+                            crate::pat_ident!(
+                                quote_ident!("i"),
+                                crate::type_annotation!(crate::keyword_type!(TsKeywordTypeKind::TsNumberKeyword))
+                            )
+                        ],
+                        vec![
+                            strict_checks_stmt(vec![field.value_check_stmt(
+                                ctx,
+                                super::field::static_field_member(field),
+                                value_path
+                            )]),
                             crate::return_stmt!(value_expr)
                         ]
                     ))]
                 );
+                if field.is_enum() && !field.is_null_value() {
+                    // history.map(...).filter((value: Enum | undefined): boolean => value !== undefined) as Enum[]
+                    let enum_type = crate::type_ref!(crate::entity_name_ident!(ctx.lazy_type_ref(field.type_name())));
+                    let known = crate::arrow_func_short!(
+                        crate::bin_expr!(quote_ident!("value").into(), quote_ident!("undefined").into(), BinaryOp::NotEqEq),
+                        vec![crate::pat_ident!(
+                            quote_ident!("value"),
+                            crate::type_union!(
+                                enum_type.clone(),
+                                crate::keyword_type!(TsKeywordTypeKind::TsUndefinedKeyword)
+                            )
+                        )]
+                    );
+                    value_expr = Expr::TsAs(TsAsExpr {
+                        span: DUMMY_SP,
+                        expr: Box::new(crate::call_expr!(
+                            crate::member_expr_bare!(value_expr, "filter"),
+                            vec![crate::expr_or_spread!(known)]
+                        )),
+                        type_ann: Box::new(TsType::TsArrayType(swc_ecma_ast::TsArrayType {
+                            span: DUMMY_SP,
+                            elem_type: Box::new(enum_type),
+                        })),
+                    });
+                }
                 if ctx.options.with_sendable {
                     value_expr = crate::call_expr!(
                         crate::member_expr_bare!(Expr::Ident(quote_ident!("collections.Array")), "from"),
                         vec![crate::expr_or_spread!(value_expr)]
                     )
                 }
+            } else {
+                stmts.push(strict_checks_stmt(vec![field.value_check_stmt(
+                    ctx,
+                    super::field::bare_field_member(field),
+                    value_path
+                )]));
             }
 
-            let mut stmts = vec![];
-
-//             if !field.is_repeated() {
-//                 stmts.push(field.value_check_stmt(ctx, accessor_fn))
-//             }
-            // if field.has_oneof_index() {
-            //     stmts.push(crate::if_stmt!(
-            //         crate::call_expr!(
-            //             crate::member_expr!("oneof", "has"),
-            //             vec![crate::expr_or_spread!(
-            //                 crate::lit_num!(field.oneof_index()).into()
-            //             )]
-            //         ),
-            //         crate::throw_stmt!(crate::new_expr!(
-            //             quote_ident!("Error").into(),
-            //             vec![crate::expr_or_spread!(crate::lit_str!(format!(
-            //                 "duplicate oneof field {}",
-            //                 field.json_key_name()
-            //             ))
-            //             .into())]
-            //         ))
-            //     ));
-            //     stmts.push(crate::expr_stmt!(crate::call_expr!(
-            //         crate::member_expr!("oneof", "add"),
-            //         vec![crate::expr_or_spread!(
-            //             crate::lit_num!(field.oneof_index()).into()
-            //         )]
-            //     )))
-            // }
-            if field.is_map(ctx) {
-                // nothing
-                stmts.push(crate::expr_stmt!(value_expr))
-            } else {
-                stmts.push(crate::expr_stmt!(crate::assign_expr!(
+            let assign = |value: Expr| {
+                crate::expr_stmt!(crate::assign_expr!(
                     PatOrExpr::Expr(Box::new(crate::member_expr!("jsonMessage", field.name()))),
-                    value_expr
-                )));
+                    value
+                ))
+            };
+            if field.is_enum() && !field.is_null_value() && !field.is_repeated() {
+                stmts.extend(known_enum_stmts(value_expr, assign));
+            } else if !field.is_map(ctx) {
+                stmts.push(assign(value_expr));
             }
            
-            statements.push(Stmt::Decl(crate::const_decl!(
-                format!("{}{}", field.name(), self.get_field_descriptor_str(ctx, &field)),
+            statements.push(Stmt::Decl(crate::let_decl!(
+                super::field::local_name(field),
+                Some(Box::new(crate::type_annotation!(field.json_type(ctx)))),
                 crate::cond_expr!(
                     Expr::Ident(quote_ident!(format!("{}[\"{}\"] !== undefined", "json", field.json_key_name()))),
                     Expr::Ident(quote_ident!(format!("{}[\"{}\"]", "json", field.json_key_name()))),
@@ -760,50 +1270,177 @@ impl DescriptorProto {
             )));
            
             statements.push(crate::if_stmt!(
-                field.default_value_bin_expr_for_json(ctx, super::field::bare_field_member),
+                declared.default_value_bin_expr_for_json(ctx, super::field::bare_field_member),
                 crate::block_stmt!(stmts)
             ))
         }
 
-        // statements.push(crate::return_stmt!(quote_ident!("jsonMessage").into()));
-        
+        statements
+    }
+
+
+    // toJsonString(options?: JsonStringifyOptions): string
+    // static fromJsonString(text: string, options?: JsonReadOptions): Msg
+    pub(super) fn print_json_string_members(&self, ctx: &mut Context) -> Vec<ClassMember> {
+        let class_name = ctx.normalize_name(self.name());
+        let to_json_string = crate::call_expr!(
+            crate::member_expr!("JSON", "stringify"),
+            vec![
+                crate::expr_or_spread!(crate::call_expr!(
+                    crate::member_expr!("this", "toJson"),
+                    vec![crate::expr_or_spread!(quote_ident!("options").into())]
+                )),
+                crate::expr_or_spread!(quote_ident!("null").into()),
+                crate::expr_or_spread!(crate::member_expr!("options?", "indent")),
+            ]
+        );
+        let is_int64_path = crate::arrow_func_short!(
+            crate::call_expr!(
+                crate::member_expr!(quote_ident!(class_name.clone()), "isInt64Path"),
+                vec![
+                    crate::expr_or_spread!(quote_ident!("path").into()),
+                    crate::expr_or_spread!(crate::lit_num!(0).into()),
+                ]
+            ),
+            vec![json_path_list_param().pat]
+        );
+        let parsed = Expr::TsAs(TsAsExpr {
+            span: DUMMY_SP,
+            expr: Box::new(crate::call_expr!(
+                ctx.lazy_support_ref(JSON_SUPPORT_MODULE, "parseJson").into(),
+                vec![
+                    crate::expr_or_spread!(quote_ident!("text").into()),
+                    crate::expr_or_spread!(is_int64_path),
+                ]
+            )),
+            type_ann: Box::new(self.json_interface_type(ctx)),
+        });
+        let from_json_string = crate::call_expr!(
+            crate::member_expr!(quote_ident!(class_name.clone()), "fromJson"),
+            vec![
+                crate::expr_or_spread!(parsed),
+                crate::expr_or_spread!(quote_ident!("options").into()),
+            ]
+        );
+
+        vec![
+            ClassMember::Method(ClassMethod {
+                span: DUMMY_SP,
+                accessibility: None,
+                key: PropName::Ident(quote_ident!("toJsonString")),
+                is_abstract: false,
+                is_optional: false,
+                is_override: false,
+                is_static: false,
+                function: Box::new(Function {
+                    body: Some(BlockStmt {
+                        span: DUMMY_SP,
+                        stmts: vec![crate::return_stmt!(to_json_string)],
+                    }),
+                    decorators: vec![],
+                    is_async: false,
+                    is_generator: false,
+                    params: vec![json_options_param(ctx, "JsonStringifyOptions")],
+                    return_type: Some(Box::new(crate::type_annotation!(crate::keyword_type!(
+                        TsKeywordTypeKind::TsStringKeyword
+                    )))),
+                    span: DUMMY_SP,
+                    type_params: None,
+                }),
+                kind: MethodKind::Method,
+            }),
+            ClassMember::Method(ClassMethod {
+                span: DUMMY_SP,
+                accessibility: None,
+                key: PropName::Ident(quote_ident!("fromJsonString")),
+                is_abstract: false,
+                is_optional: false,
+                is_override: false,
+                is_static: true,
+                function: Box::new(Function {
+                    body: Some(BlockStmt {
+                        span: DUMMY_SP,
+                        stmts: vec![crate::return_stmt!(from_json_string)],
+                    }),
+                    decorators: vec![],
+                    is_async: false,
+                    is_generator: false,
+                    params: vec![
+                        json_param("text", crate::keyword_type!(TsKeywordTypeKind::TsStringKeyword)),
+                        json_options_param(ctx, "JsonReadOptions"),
+                    ],
+                    return_type: Some(Box::new(crate::type_annotation!(crate::type_ref!(
+                        crate::entity_name_ident!(quote_ident!(class_name))
+                    )))),
+                    span: DUMMY_SP,
+                    type_params: None,
+                }),
+                kind: MethodKind::Method,
+            }),
+            self.print_int64_path(ctx),
+        ]
+    }
+
+    // tells parseJson which numbers to keep as strings, path holds the object keys leading to a
+    // number and depth the first of them which belongs to this message.
+    // static isInt64Path(path: string[], depth: number): boolean { switch (path[depth]) { case "a": return path.length === depth + 1; } return false; }
+    fn print_int64_path(&self, ctx: &mut Context) -> ClassMember {
+        let mut cases = vec![];
+        for field in &self.field {
+            let check = match field.int64_path_check(ctx) {
+                Some(check) => check,
+                None => continue,
+            };
+            let mut keys = vec![field.json_key_name()];
+            if field.name() != field.json_key_name() {
+                keys.push(field.name().to_string());
+            }
+            let last = keys.len() - 1;
+            for (i, key) in keys.into_iter().enumerate() {
+                cases.push(SwitchCase {
+                    span: DUMMY_SP,
+                    test: Some(Box::new(quote_str!(key).into())),
+                    cons: if i == last {
+                        vec![crate::return_stmt!(check.clone())]
+                    } else {
+                        vec![]
+                    },
+                });
+            }
+        }
+        let mut stmts = vec![];
+        if !cases.is_empty() {
+            stmts.push(Stmt::Switch(SwitchStmt {
+                span: DUMMY_SP,
+                discriminant: Box::new(crate::member_expr_computed!(
+                    quote_ident!("path").into(),
+                    quote_ident!("depth").into()
+                )),
+                cases,
+            }));
+        }
+        stmts.push(crate::return_stmt!(crate::lit_bool!(false).into()));
+
         ClassMember::Method(ClassMethod {
             span: DUMMY_SP,
             accessibility: None,
-            key: PropName::Ident(quote_ident!(format!("{}_{}", "fromJson", index))),
+            key: PropName::Ident(quote_ident!("isInt64Path")),
             is_abstract: false,
             is_optional: false,
             is_override: false,
             is_static: true,
             function: Box::new(Function {
-                body: Some(BlockStmt {
-                    span: DUMMY_SP,
-                    stmts: statements,
-                }),
+                body: Some(BlockStmt { span: DUMMY_SP, stmts }),
                 decorators: vec![],
                 is_async: false,
                 is_generator: false,
                 params: vec![
-                    Param {
-                        span: DUMMY_SP,
-                        decorators: vec![],
-                        pat: swc_ecma_ast::Pat::Ident(swc_ecma_ast::BindingIdent {
-                           id: quote_ident!("json"),
-                            type_ann: Some(Box::new(crate::type_annotation!("object"))),
-                        }),
-                    },
-                    Param {
-                        span: DUMMY_SP,
-                        decorators: vec![],
-                        pat: swc_ecma_ast::Pat::Ident(swc_ecma_ast::BindingIdent {
-                           id: quote_ident!("jsonMessage"),
-                            type_ann: Some(Box::new(crate::type_annotation!(crate::type_ref!(crate::entity_name_ident!(
-                                quote_ident!(ctx.normalize_name(self.name()))
-                            ))))),
-                        }),
-                    },
+                    json_path_list_param(),
+                    json_param("depth", crate::keyword_type!(TsKeywordTypeKind::TsNumberKeyword)),
                 ],
-                return_type: None,
+                return_type: Some(Box::new(crate::type_annotation!(crate::keyword_type!(
+                    TsKeywordTypeKind::TsBooleanKeyword
+                )))),
                 span: DUMMY_SP,
                 type_params: None,
             }),
@@ -811,7 +1448,6 @@ impl DescriptorProto {
         })
     }
 
-
     pub(super) fn print_from_json(&self, ctx: &mut Context) -> Vec<ClassMember> {
         let mut statements = vec![
             Stmt::Decl(crate::const_decl!(
@@ -819,42 +1455,26 @@ impl DescriptorProto {
                 crate::new_expr!(Expr::Ident(quote_ident!(ctx.normalize_name(self.name()))))
             )),
 
+            self.print_unknown_fields_check(ctx),
         ];
 
-        // let mut has_oneof = false;
-        // for field in self.field.clone() {
-        //     if field.has_oneof_index() {
-        //         has_oneof = true;
-        //         break;
-        //     }
-        // }
-        // if has_oneof {
-        //     statements.push(Stmt::Decl(crate::const_decl!(
-        //         "oneof",
-        //         crate::new_expr!(Expr::Ident(quote_ident!("Set")))
-        //     )));
-        // }
-        let mut class_member_vec: Vec<ClassMember> = [].to_vec();
-        let mut cur_field_vec = Vec::new();
-        let mut cur_method_index = 0;
-        let mut newst = |fields: &Vec<&FieldDescriptorProto>, index: i32| {
-            // add class member
-            class_member_vec.push(self.print_from_json_inner(ctx, index, fields));
-            // call inner method
-            statements.push(
-                crate::expr_stmt!(Expr::Ident(quote_ident!(format!("{}.fromJson_{}(json, jsonMessage)", ctx.normalize_name(self.name()), index)))));
-        };
-        for field in &self.field {
-            cur_field_vec.push(field);
-            if cur_field_vec.len() >= 30 {
-                newst(&cur_field_vec, cur_method_index);
-                cur_method_index +=1;
-                cur_field_vec.clear();
-            }
-        }
-        if !cur_field_vec.is_empty() {
-            newst(&cur_field_vec, cur_method_index);
+        if let Some(check) = self.print_duplicate_fields_check(ctx) {
+            statements.push(check);
         }
+        let params = vec![
+            json_param("json", self.json_interface_type(ctx)),
+            json_param(
+                "jsonMessage",
+                crate::type_ref!(crate::entity_name_ident!(quote_ident!(ctx.normalize_name(self.name())))),
+            ),
+            json_options_param(ctx, "JsonReadOptions"),
+            json_path_param(),
+        ];
+        let (mut field_stmts, mut class_member_vec) =
+            self.print_method_chunks(ctx, "fromJson", true, params, |ctx, fields| {
+                self.print_from_json_fields(ctx, fields)
+            });
+        statements.append(&mut field_stmts);
 
         statements.push(crate::return_stmt!(quote_ident!("jsonMessage").into()));
         
@@ -879,9 +1499,12 @@ impl DescriptorProto {
                     decorators: vec![],
                     pat: swc_ecma_ast::Pat::Ident(swc_ecma_ast::BindingIdent {
                         id: quote_ident!("json"),
-                        type_ann: Some(Box::new(crate::type_annotation!("object"))),
+                        type_ann: Some(Box::new(crate::type_annotation!(self.json_interface_type(ctx)))),
                     }),
-                }],
+                },
+                json_options_param(ctx, "JsonReadOptions"),
+                json_path_param(),
+                ],
                 return_type: Some(Box::new(crate::type_annotation!(crate::type_ref!(crate::entity_name_ident!(
                     quote_ident!(ctx.normalize_name(self.name()))
                 ))))),
@@ -0,0 +1,506 @@
+use crate::context::Context;
+use crate::descriptor::{DescriptorProto, FieldDescriptorProto};
+use swc_common::DUMMY_SP;
+use swc_ecma_ast::{
+    ArrayLit, BinaryOp, BlockStmt, ClassMember, ClassMethod, Expr, ForHead, ForOfStmt, ForStmt,
+    Function, IfStmt, MethodKind, ObjectLit, Param, PatOrExpr, PropName, Stmt, TsAsExpr,
+    TsKeywordTypeKind, TsType, UpdateExpr, UpdateOp, VarDeclOrExpr,
+};
+use swc_ecma_utils::{quote_ident, quote_str};
+
+use super::json::{
+    json_option_set, json_options_param, json_param, json_path_param, known_enum_stmts, strict_checks_stmt,
+};
+
+impl FieldDescriptorProto {
+    // stores a value read from json, leaving out the unknown enum values.
+    fn strict_store_stmts(&self, value: Expr, store: impl FnOnce(Expr) -> Stmt) -> Vec<Stmt> {
+        if self.is_enum() && !self.is_null_value() {
+            known_enum_stmts(value, store)
+        } else {
+            vec![store(value)]
+        }
+    }
+
+    fn strict_to_json_value_expr(&self, ctx: &mut Context, value: Expr) -> Expr {
+        if self.is_plain_struct(ctx) {
+            value
+        } else if self.is_message() {
+            match self.well_known_json_type(ctx) {
+                Some(type_ann) => Expr::TsAs(TsAsExpr {
+                    span: DUMMY_SP,
+                    expr: Box::new(crate::call_expr!(crate::member_expr_bare!(value, "toJson"))),
+                    type_ann: Box::new(type_ann),
+                }),
+                None => crate::call_expr!(
+                    crate::member_expr_bare!(value, "toJson"),
+                    vec![crate::expr_or_spread!(quote_ident!("options").into())]
+                ),
+            }
+        } else if self.is_bytes() {
+            ctx.get_base64_import(ctx.options.base64_package.as_str());
+            let bytes = if ctx.options.with_sendable {
+                crate::call_expr!(
+                    crate::member_expr!("Uint8Array", "from"),
+                    vec![crate::expr_or_spread!(value)]
+                )
+            } else {
+                value
+            };
+            crate::call_expr!(
+                quote_ident!("fromUint8Array").into(),
+                vec![crate::expr_or_spread!(bytes)]
+            )
+        } else if self.is_bigint() {
+            crate::call_expr!(crate::member_expr_bare!(value, "toString"))
+        } else if self.is_float() {
+            // NaN and the infinities are written as strings.
+            crate::cond_expr!(
+                crate::call_expr!(
+                    crate::member_expr!("Number", "isFinite"),
+                    vec![crate::expr_or_spread!(value.clone())]
+                ),
+                value.clone(),
+                crate::call_expr!(crate::member_expr_bare!(value, "toString"))
+            )
+        } else if self.is_null_value() {
+            quote_ident!("null").into()
+        } else if self.is_enum() {
+            crate::call_expr!(
+                ctx.lazy_companion_type_ref(self.type_name(), "_toJson").into(),
+                vec![
+                    crate::expr_or_spread!(value),
+                    crate::expr_or_spread!(quote_ident!("options").into())
+                ]
+            )
+        } else {
+            value
+        }
+    }
+
+    fn strict_from_json_value_expr(&self, ctx: &mut Context, value: Expr, path: Expr) -> Expr {
+        if self.is_plain_struct(ctx) {
+            value
+        } else if self.is_message() {
+            let mut args = vec![crate::expr_or_spread!(value)];
+            if self.well_known_json_type(ctx).is_none() {
+                args.push(crate::expr_or_spread!(quote_ident!("options").into()));
+                args.push(crate::expr_or_spread!(path));
+            }
+            crate::call_expr!(
+                crate::member_expr!(ctx.lazy_type_ref(self.type_name()), "fromJson"),
+                args
+            )
+        } else if self.is_bytes() {
+            ctx.get_base64_import(ctx.options.base64_package.as_str());
+            let bytes = crate::call_expr!(
+                quote_ident!("toUint8Array").into(),
+                vec![crate::expr_or_spread!(value)]
+            );
+            if ctx.options.with_sendable {
+                crate::call_expr!(
+                    crate::member_expr!("collections.Uint8Array", "from"),
+                    vec![crate::expr_or_spread!(bytes)]
+                )
+            } else {
+                bytes
+            }
+        } else if self.is_bigint() {
+            crate::call_expr!(
+                quote_ident!("BigInt").into(),
+                vec![crate::expr_or_spread!(value)]
+            )
+        } else if self.is_float() {
+            crate::call_expr!(
+                quote_ident!("Number").into(),
+                vec![crate::expr_or_spread!(value)]
+            )
+        } else if self.is_null_value() {
+            crate::member_expr!(ctx.lazy_type_ref(self.type_name()), "NULL_VALUE")
+        } else if self.is_enum() {
+            crate::call_expr!(
+                ctx.lazy_companion_type_ref(self.type_name(), "_fromJson").into(),
+                vec![
+                    crate::expr_or_spread!(value),
+                    crate::expr_or_spread!(quote_ident!("options").into()),
+                    crate::expr_or_spread!(path)
+                ]
+            )
+        } else {
+            value
+        }
+    }
+
+    // map keys are parsed back from their string form.
+    fn strict_from_json_key_expr(&self, key: Expr) -> Expr {
+        if self.is_string() {
+            key
+        } else if self.is_booelan() {
+            crate::bin_expr!(key, quote_str!("true").into(), BinaryOp::EqEqEq)
+        } else if self.is_bigint() {
+            crate::call_expr!(quote_ident!("BigInt").into(), vec![crate::expr_or_spread!(key)])
+        } else {
+            crate::call_expr!(quote_ident!("Number").into(), vec![crate::expr_or_spread!(key)])
+        }
+    }
+
+    // if (options?.useProtoFieldName === true) { json.field_name = value; } else { json.fieldName = value; }
+    fn strict_json_assign(&self, value: Expr) -> Stmt {
+        let assign = |key: &str, value: Expr| {
+            crate::expr_stmt!(crate::assign_expr!(
+                PatOrExpr::Expr(Box::new(crate::member_expr!("json", key))),
+                value
+            ))
+        };
+        if self.name() == self.json_key_name() {
+            return assign(self.name(), value);
+        }
+        Stmt::If(IfStmt {
+            span: DUMMY_SP,
+            test: Box::new(json_option_set("useProtoFieldName")),
+            cons: Box::new(crate::block_stmt!(vec![assign(self.name(), value.clone())])),
+            alt: Some(Box::new(crate::block_stmt!(vec![assign(
+                self.json_key_name().as_str(),
+                value
+            )]))),
+        })
+    }
+
+    // json.fieldName !== undefined ? json.fieldName : json.field_name
+    fn strict_json_field(&self) -> Expr {
+        let json_field = crate::member_expr!("json", self.json_key_name());
+        if self.name() == self.json_key_name() {
+            return json_field;
+        }
+        crate::paren_expr!(crate::cond_expr!(
+            crate::bin_expr!(
+                json_field.clone(),
+                quote_ident!("undefined").into(),
+                BinaryOp::NotEqEq
+            ),
+            json_field,
+            crate::member_expr!("json", self.name())
+        ))
+    }
+
+    fn print_strict_to_json_stmt(&self, ctx: &mut Context) -> Stmt {
+        let write_check = self.json_write_check(ctx);
+        let unboxed = self.unboxed_wrapper(ctx);
+        let field = unboxed.as_ref().unwrap_or(self);
+        let this_field = crate::member_expr!("this", self.name());
+        let local = quote_ident!(format!("{}_json", self.name()));
+
+        let stmts = if field.is_map(ctx) {
+            // const m_json: Record<string, V> = {};
+            // for (let entry of binary_support.orderedEntries(this.m.entries(), ...)) { ...; m_json[String(key)] = value; }
+            let descriptor = ctx
+                .get_map_type(field.type_name())
+                .unwrap_or_else(|| panic!("can not find the map type {}", field.type_name()));
+            let value =
+                descriptor.field[1].strict_to_json_value_expr(ctx, quote_ident!("value").into());
+            vec![
+                Stmt::Decl(crate::let_decl!(
+                    local.sym.as_ref(),
+                    Some(Box::new(crate::type_annotation!(field.json_type(ctx)))),
+                    Expr::Object(ObjectLit {
+                        span: DUMMY_SP,
+                        props: vec![],
+                    })
+                )),
+                Stmt::ForOf(ForOfStmt {
+                    is_await: false,
+                    left: ForHead::Pat(Box::new(crate::pat_ident!(quote_ident!("let entry")))),
+                    right: Box::new(field.ordered_entries_expr(ctx, this_field)),
+                    body: Box::new(crate::block_stmt!(vec![
+                        crate::expr_stmt!(Expr::Ident(quote_ident!("let key = entry[0]"))),
+                        crate::expr_stmt!(Expr::Ident(quote_ident!("let value = entry[1]"))),
+                        crate::expr_stmt!(crate::assign_expr!(
+                            PatOrExpr::Expr(Box::new(crate::member_expr_computed!(
+                                local.clone().into(),
+                                crate::call_expr!(
+                                    quote_ident!("String").into(),
+                                    vec![crate::expr_or_spread!(quote_ident!("key").into())]
+                                )
+                            ))),
+                            value
+                        )),
+                    ])),
+                    span: DUMMY_SP,
+                }),
+                self.strict_json_assign(local.into()),
+            ]
+        } else if field.is_repeated() {
+            // const r_json: Array<V> = [];
+            // for (const value of this.r) { r_json.push(value); }
+            let value = field.strict_to_json_value_expr(ctx, quote_ident!("value").into());
+            vec![
+                Stmt::Decl(crate::let_decl!(
+                    local.sym.as_ref(),
+                    Some(Box::new(crate::type_annotation!(field.json_type(ctx)))),
+                    Expr::Array(ArrayLit {
+                        span: DUMMY_SP,
+                        elems: vec![],
+                    })
+                )),
+                Stmt::ForOf(ForOfStmt {
+                    is_await: false,
+                    left: ForHead::VarDecl(Box::new(crate::const_decl_uinit!("value"))),
+                    right: Box::new(this_field),
+                    body: Box::new(crate::block_stmt!(vec![crate::expr_stmt!(crate::call_expr!(
+                        crate::member_expr_bare!(local.clone().into(), "push"),
+                        vec![crate::expr_or_spread!(value)]
+                    ))])),
+                    span: DUMMY_SP,
+                }),
+                self.strict_json_assign(local.into()),
+            ]
+        } else {
+            vec![self.strict_json_assign(field.strict_to_json_value_expr(ctx, this_field))]
+        };
+
+        crate::if_stmt!(write_check, crate::block_stmt!(stmts))
+    }
+
+    fn print_strict_from_json_stmt(&self, ctx: &mut Context) -> Vec<Stmt> {
+        let unboxed = self.unboxed_wrapper(ctx);
+        let field = unboxed.as_ref().unwrap_or(self);
+        let local = quote_ident!(format!("{}_json", self.name()));
+        let message_field = crate::member_expr!("jsonMessage", self.name());
+
+        let stmts = if field.is_map(ctx) {
+            // for (const key of Object.keys(m_json)) { let value: V = m_json[key]; jsonMessage.m.set(K(key), V(value)); }
+            let descriptor = ctx
+                .get_map_type(field.type_name())
+                .unwrap_or_else(|| panic!("can not find the map type {}", field.type_name()));
+            let entry_path = self.json_map_value_path(quote_ident!("key").into());
+            let key = descriptor.field[0].strict_from_json_key_expr(quote_ident!("key").into());
+            let value = descriptor.field[1].strict_from_json_value_expr(
+                ctx,
+                quote_ident!("value").into(),
+                entry_path.clone(),
+            );
+            vec![
+                strict_checks_stmt(vec![field.container_check_stmt(ctx, local.clone().into())]),
+                Stmt::ForOf(ForOfStmt {
+                    is_await: false,
+                    left: ForHead::VarDecl(Box::new(crate::const_decl_uinit!("key"))),
+                    right: Box::new(crate::call_expr!(
+                        crate::member_expr!("Object", "keys"),
+                        vec![crate::expr_or_spread!(local.clone().into())]
+                    )),
+                    body: Box::new(crate::block_stmt!([
+                        vec![
+                            Stmt::Decl(crate::let_decl!(
+                                "value",
+                                Some(Box::new(crate::type_annotation!(
+                                    descriptor.field[1].json_value_type(ctx)
+                                ))),
+                                crate::member_expr_computed!(local.clone().into(), quote_ident!("key").into())
+                            )),
+                            strict_checks_stmt(vec![descriptor.field[1].value_check_stmt(
+                                ctx,
+                                quote_ident!("value").into(),
+                                entry_path
+                            )]),
+                        ],
+                        descriptor.field[1].strict_store_stmts(value, |value| {
+                            crate::expr_stmt!(crate::call_expr!(
+                                crate::member_expr_bare!(message_field, "set"),
+                                vec![crate::expr_or_spread!(key), crate::expr_or_spread!(value)]
+                            ))
+                        }),
+                    ]
+                    .concat())),
+                    span: DUMMY_SP,
+                }),
+            ]
+        } else if field.is_repeated() {
+            // for (let i: number = 0; i < r_json.length; i++) { let value: V = r_json[i]; jsonMessage.r.push(V(value)); }
+            let index: Expr = quote_ident!("i").into();
+            let element_path = self.json_element_path(index.clone());
+            let value = field.strict_from_json_value_expr(
+                ctx,
+                quote_ident!("value").into(),
+                element_path.clone(),
+            );
+            vec![
+                strict_checks_stmt(vec![field.container_check_stmt(ctx, local.clone().into())]),
+                Stmt::For(ForStmt {
+                    span: DUMMY_SP,
+                    init: Some(VarDeclOrExpr::Expr(Box::new(Expr::Ident(quote_ident!(
+                        "let i: number = 0"
+                    ))))),
+                    test: Some(Box::new(crate::bin_expr!(
+                        index.clone(),
+                        crate::member_expr_bare!(local.clone().into(), "length"),
+                        BinaryOp::Lt
+                    ))),
+                    update: Some(Box::new(Expr::Update(UpdateExpr {
+                        span: DUMMY_SP,
+                        op: UpdateOp::PlusPlus,
+                        prefix: false,
+                        arg: Box::new(index.clone()),
+                    }))),
+                    body: Box::new(crate::block_stmt!([
+                        vec![
+                            Stmt::Decl(crate::let_decl!(
+                                "value",
+                                Some(Box::new(crate::type_annotation!(field.json_value_type(ctx)))),
+                                crate::member_expr_computed!(local.clone().into(), index)
+                            )),
+                            strict_checks_stmt(vec![field.value_check_stmt(
+                                ctx,
+                                quote_ident!("value").into(),
+                                element_path
+                            )]),
+                        ],
+                        field.strict_store_stmts(value, |value| {
+                            crate::expr_stmt!(crate::call_expr!(
+                                crate::member_expr_bare!(message_field, "push"),
+                                vec![crate::expr_or_spread!(value)]
+                            ))
+                        }),
+                    ]
+                    .concat())),
+                }),
+            ]
+        } else {
+            let path = self.json_field_path();
+            let value = field.strict_from_json_value_expr(ctx, local.clone().into(), path.clone());
+            let mut stmts = vec![strict_checks_stmt(vec![field.value_check_stmt(
+                ctx,
+                local.clone().into(),
+                path
+            )])];
+            stmts.extend(field.strict_store_stmts(value, |value| {
+                crate::expr_stmt!(crate::assign_expr!(PatOrExpr::Expr(Box::new(message_field)), value))
+            }));
+            stmts
+        };
+
+        // let f_json: T | undefined = json.f;
+        vec![
+            Stmt::Decl(crate::let_decl!(
+                local.sym.as_ref(),
+                Some(Box::new(crate::type_union!(
+                    field.json_type(ctx),
+                    crate::keyword_type!(TsKeywordTypeKind::TsUndefinedKeyword)
+                ))),
+                self.strict_json_field()
+            )),
+            crate::if_stmt!(
+                crate::bin_expr!(local.into(), quote_ident!("undefined").into(), BinaryOp::NotEqEq),
+                crate::block_stmt!(stmts)
+            ),
+        ]
+    }
+}
+
+impl DescriptorProto {
+    fn class_type_ref(&self, ctx: &Context) -> TsType {
+        crate::type_ref!(crate::entity_name_ident!(quote_ident!(ctx.normalize_name(self.name()))))
+    }
+
+    fn strict_json_method(
+        &self,
+        name: String,
+        is_static: bool,
+        params: Vec<Param>,
+        stmts: Vec<Stmt>,
+        return_type: Option<TsType>,
+    ) -> ClassMember {
+        ClassMember::Method(ClassMethod {
+            span: DUMMY_SP,
+            accessibility: None,
+            key: PropName::Ident(quote_ident!(name)),
+            is_abstract: false,
+            is_optional: false,
+            is_override: false,
+            is_static,
+            function: Box::new(Function {
+                body: Some(BlockStmt {
+                    span: DUMMY_SP,
+                    stmts,
+                }),
+                decorators: vec![],
+                is_async: false,
+                is_generator: false,
+                params,
+                return_type: return_type.map(|type_ann| Box::new(crate::type_annotation!(type_ann))),
+                span: DUMMY_SP,
+                type_params: None,
+            }),
+            kind: MethodKind::Method,
+        })
+    }
+
+    // toJson(options?): MsgJson, large messages are split into private toJson_N methods like the default codec.
+    pub(super) fn print_strict_to_json(&self, ctx: &mut Context) -> Vec<ClassMember> {
+        let mut statements = vec![Stmt::Decl(crate::const_decl!(
+            format!("json: {}Json", ctx.normalize_name(self.name())),
+            Expr::Object(ObjectLit {
+                span: DUMMY_SP,
+                props: vec![],
+            })
+        ))];
+
+        let params = vec![
+            json_param("json", self.json_interface_type(ctx)),
+            json_options_param(ctx, "JsonWriteOptions"),
+        ];
+        let (mut field_stmts, mut members) =
+            self.print_method_chunks(ctx, "toJson", false, params, |ctx, fields| {
+                fields.iter().map(|field| field.print_strict_to_json_stmt(ctx)).collect()
+            });
+        statements.append(&mut field_stmts);
+        statements.push(crate::return_stmt!(quote_ident!("json").into()));
+
+        members.push(self.strict_json_method(
+            "toJson".to_string(),
+            false,
+            vec![json_options_param(ctx, "JsonWriteOptions")],
+            statements,
+            Some(self.json_interface_type(ctx)),
+        ));
+        members
+    }
+
+    // static fromJson(json: MsgJson, options?): Msg, large messages are split into private fromJson_N methods.
+    pub(super) fn print_strict_from_json(&self, ctx: &mut Context) -> Vec<ClassMember> {
+        let class_name = ctx.normalize_name(self.name());
+        let mut statements = vec![Stmt::Decl(crate::const_decl!(
+            format!("jsonMessage: {}", class_name),
+            crate::new_expr!(quote_ident!(class_name.clone()).into())
+        ))];
+        statements.push(self.print_unknown_fields_check(ctx));
+        if let Some(check) = self.print_duplicate_fields_check(ctx) {
+            statements.push(check);
+        }
+
+        let params = vec![
+            json_param("json", self.json_interface_type(ctx)),
+            json_param("jsonMessage", self.class_type_ref(ctx)),
+            json_options_param(ctx, "JsonReadOptions"),
+            json_path_param(),
+        ];
+        let (mut field_stmts, mut members) =
+            self.print_method_chunks(ctx, "fromJson", true, params, |ctx, fields| {
+                fields.iter().flat_map(|field| field.print_strict_from_json_stmt(ctx)).collect()
+            });
+        statements.append(&mut field_stmts);
+        statements.push(crate::return_stmt!(quote_ident!("jsonMessage").into()));
+
+        members.push(self.strict_json_method(
+            "fromJson".to_string(),
+            true,
+            vec![
+                json_param("json", self.json_interface_type(ctx)),
+                json_options_param(ctx, "JsonReadOptions"),
+                json_path_param(),
+            ],
+            statements,
+            Some(self.class_type_ref(ctx)),
+        ));
+        members
+    }
+}
@@ -0,0 +1,134 @@
+use crate::{context::Context, descriptor::FieldDescriptorProto, runtime::Runtime};
+use swc_common::DUMMY_SP;
+use swc_ecma_ast::{
+    Accessibility, BinaryOp, BindingIdent, BlockStmt, ClassMember, ClassMethod, ClassProp, Expr,
+    Function, MethodKind, Param, Pat, PropName, Stmt,
+};
+use swc_ecma_utils::{quote_ident, ExprFactory};
+
+impl FieldDescriptorProto {
+    // the private property holding the decoded message of a lazy field.
+    pub fn lazy_value_name(&self) -> String {
+        format!("_{}", self.name())
+    }
+
+    // the private property holding the encoded message of a lazy field until it is first read.
+    pub fn lazy_bytes_name(&self) -> String {
+        format!("_{}_bytes", self.name())
+    }
+
+    fn lazy_accessor(&self, kind: MethodKind, params: Vec<Param>, stmts: Vec<Stmt>, ctx: &mut Context) -> ClassMember {
+        let return_type = if kind == MethodKind::Getter {
+            self.lazy_type_annotation(ctx)
+        } else {
+            None
+        };
+        ClassMember::Method(ClassMethod {
+            span: DUMMY_SP,
+            key: quote_ident!(self.name()).into(),
+            kind,
+            function: Box::new(Function {
+                decorators: vec![],
+                is_async: false,
+                is_generator: false,
+                params,
+                return_type,
+                span: DUMMY_SP,
+                type_params: None,
+                body: Some(BlockStmt { span: DUMMY_SP, stmts }),
+            }),
+            is_static: false,
+            accessibility: None,
+            is_abstract: false,
+            is_optional: false,
+            is_override: false,
+        })
+    }
+
+    fn lazy_type_annotation(&self, ctx: &mut Context) -> Option<Box<swc_ecma_ast::TsTypeAnn>> {
+        if self.is_optional() {
+            self.nullish_type_annotation(ctx)
+        } else {
+            self.type_annotation(ctx)
+        }
+    }
+
+    // private _field?: Msg = undefined;
+    // private _field_bytes?: Uint8Array = undefined;
+    // get field(): Msg | undefined { decodes _field_bytes into _field on first access }
+    // set field(value: Msg | undefined) { drops _field_bytes }
+    pub(super) fn print_lazy_members<T: Runtime>(&self, ctx: &mut Context, runtime: &T) -> Vec<ClassMember> {
+        let mut value = self.print_prop(ctx, runtime);
+        if let ClassMember::ClassProp(prop) = &mut value {
+            let mut key = quote_ident!(self.lazy_value_name());
+            key.optional = self.is_optional();
+            prop.key = PropName::Ident(key);
+            prop.accessibility = Some(Accessibility::Private);
+        }
+        let bytes = ClassMember::ClassProp(ClassProp {
+            span: DUMMY_SP,
+            key: PropName::Ident(crate::quote_ident_optional!(self.lazy_bytes_name())),
+            value: Some(Box::new(quote_ident!("undefined").into())),
+            type_ann: Some(Box::new(crate::type_annotation!("Uint8Array"))),
+            declare: false,
+            is_static: false,
+            decorators: vec![],
+            accessibility: Some(Accessibility::Private),
+            is_abstract: false,
+            is_optional: false,
+            is_override: false,
+            readonly: false,
+            definite: false,
+        });
+
+        let value_member = crate::member_expr!("this", self.lazy_value_name());
+        let bytes_member = crate::member_expr!("this", self.lazy_bytes_name());
+        let clear_bytes = crate::expr_stmt!(crate::assign_expr!(
+            bytes_member.clone().as_pat_or_expr(),
+            quote_ident!("undefined").into()
+        ));
+
+        let getter = self.lazy_accessor(
+            MethodKind::Getter,
+            vec![],
+            vec![
+                crate::if_stmt!(
+                    crate::bin_expr!(bytes_member.clone(), quote_ident!("undefined").into(), BinaryOp::NotEqEq),
+                    crate::block_stmt!(vec![
+                        crate::expr_stmt!(crate::assign_expr!(
+                            value_member.clone().as_pat_or_expr(),
+                            crate::call_expr!(
+                                crate::member_expr!(ctx.lazy_type_ref(self.type_name()), "fromBinary"),
+                                vec![crate::expr_or_spread!(bytes_member)]
+                            )
+                        )),
+                        clear_bytes.clone(),
+                    ])
+                ),
+                crate::return_stmt!(value_member.clone()),
+            ],
+            ctx,
+        );
+        let setter = self.lazy_accessor(
+            MethodKind::Setter,
+            vec![Param {
+                span: DUMMY_SP,
+                decorators: vec![],
+                pat: Pat::Ident(BindingIdent {
+                    id: quote_ident!("value"),
+                    type_ann: self.lazy_type_annotation(ctx),
+                }),
+            }],
+            vec![
+                crate::expr_stmt!(crate::assign_expr!(
+                    value_member.as_pat_or_expr(),
+                    Expr::Ident(quote_ident!("value"))
+                )),
+                clear_bytes,
+            ],
+            ctx,
+        );
+
+        vec![value, bytes, getter, setter]
+    }
+}
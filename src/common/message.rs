@@ -8,15 +8,17 @@
 
 use std::vec;
 
+use super::binary::binary_options_param;
 use crate::context::Context;
-use crate::descriptor::DescriptorProto;
+use crate::descriptor::{DescriptorProto, FieldDescriptorProto};
 use crate::print::Print;
 use crate::runtime::Runtime;
 
 use swc_common::DUMMY_SP;
 use swc_ecma_ast::{
-    ArrayLit, BlockStmt, Class, ClassDecl, ClassMember, ClassMethod, Decl, ExportDecl, Expr, Decorator,
-    Function, MethodKind, ModuleDecl, ModuleItem, Param, PrivateName, PrivateProp, PropName, Stmt, ClassProp,
+    Accessibility, ArrayLit, BlockStmt, Class, ClassDecl, ClassMember, ClassMethod, Decl, ExportDecl, Expr, Decorator,
+    Function, MethodKind, ModuleDecl, ModuleItem, Param, Pat, PrivateName, PrivateProp, PropName, Stmt, ClassProp,
+    TsType,
 };
 use swc_ecma_utils::{quote_ident, quote_str};
 
@@ -62,16 +64,110 @@ impl DescriptorProto {
         })
     }
 
-    fn print_serialize<T: Runtime + Sized>(&self, ctx: &mut Context, runtime: &T) -> ClassMember {
+    // fields handled by each codec method, messages with more than max_method_fields fields
+    // are split into chunks to keep the generated methods within the ArkTS size limits.
+    pub fn method_field_chunks(&self, ctx: &Context) -> Vec<&[FieldDescriptorProto]> {
+        match ctx.options.max_method_fields {
+            0 => vec![&self.field[..]],
+            max => self.field.chunks(max).collect(),
+        }
+    }
+
+    pub fn print_private_method(
+        &self,
+        name: String,
+        is_static: bool,
+        params: Vec<Param>,
+        stmts: Vec<Stmt>,
+        return_type: Option<TsType>,
+    ) -> ClassMember {
+        ClassMember::Method(ClassMethod {
+            span: DUMMY_SP,
+            accessibility: Some(Accessibility::Private),
+            key: PropName::Ident(quote_ident!(name)),
+            is_abstract: false,
+            is_optional: false,
+            is_override: false,
+            is_static,
+            function: Box::new(Function {
+                body: Some(BlockStmt {
+                    span: DUMMY_SP,
+                    stmts,
+                }),
+                decorators: vec![],
+                is_async: false,
+                is_generator: false,
+                params,
+                return_type: return_type.map(|type_ann| Box::new(crate::type_annotation!(type_ann))),
+                span: DUMMY_SP,
+                type_params: None,
+            }),
+            kind: MethodKind::Method,
+        })
+    }
+
+    // the field statements are inlined when the message fits into a single method, otherwise each
+    // chunk becomes a private `<name>_N` helper called with the same arguments as the method itself.
+    pub fn print_method_chunks<F>(
+        &self,
+        ctx: &mut Context,
+        name: &str,
+        is_static: bool,
+        params: Vec<Param>,
+        mut print_fields: F,
+    ) -> (Vec<Stmt>, Vec<ClassMember>)
+    where
+        F: FnMut(&mut Context, &[FieldDescriptorProto]) -> Vec<Stmt>,
+    {
+        let chunks = self.method_field_chunks(ctx);
+        if chunks.len() <= 1 {
+            let stmts = chunks.into_iter().flat_map(|fields| print_fields(ctx, fields)).collect();
+            return (stmts, vec![]);
+        }
+
+        let receiver = if is_static {
+            quote_ident!(ctx.normalize_name(self.name()))
+        } else {
+            quote_ident!("this")
+        };
+        // optional params are named `options?`.
+        let args: Vec<_> = params
+            .iter()
+            .map(|param| {
+                let ident = match &param.pat {
+                    Pat::Ident(ident) => ident,
+                    Pat::Assign(assign) => assign.left.as_ident().expect("expected a named parameter"),
+                    _ => panic!("expected a named parameter"),
+                };
+                crate::expr_or_spread!(quote_ident!(ident.id.sym.trim_end_matches('?')).into())
+            })
+            .collect();
+
+        let mut stmts = vec![];
+        let mut members = vec![];
+        for (index, fields) in chunks.into_iter().enumerate() {
+            let helper = format!("{}_{}", name, index);
+            let body = print_fields(ctx, fields);
+            members.push(self.print_private_method(helper.clone(), is_static, params.clone(), body, None));
+            stmts.push(crate::expr_stmt!(crate::call_expr!(
+                crate::member_expr!(receiver.clone(), helper),
+                args.clone()
+            )));
+        }
+        (stmts, members)
+    }
+
+    fn print_serialize<T: Runtime + Sized>(&self, ctx: &mut Context, runtime: &T) -> Vec<ClassMember> {
         let mut statements = vec![];
 
-        statements.extend(runtime.to_binary(ctx, &self));
+        let (stmts, mut members) = runtime.to_binary(ctx, &self);
+        statements.extend(stmts);
         statements.push(crate::return_stmt!(crate::call_expr!(crate::member_expr!(
             "bw",
             "getResultBuffer"
         ))));
 
-        ClassMember::Method(ClassMethod {
+        members.push(ClassMember::Method(ClassMethod {
             span: DUMMY_SP,
             accessibility: None,
             key: PropName::Ident(quote_ident!("toBinary")),
@@ -87,13 +183,14 @@ impl DescriptorProto {
                 decorators: vec![],
                 is_async: false,
                 is_generator: false,
-                params: vec![],
+                params: vec![binary_options_param(ctx)],
                 return_type: Some(Box::new(crate::type_annotation!("Uint8Array"))),
                 span: DUMMY_SP,
                 type_params: None,
             }),
             kind: MethodKind::Method,
-        })
+        }));
+        members
     }
 
     fn print_deserialize(&self, ctx: &mut Context) -> ClassMember {
@@ -138,12 +235,12 @@ impl DescriptorProto {
         })
     }
 
-    fn print_merge_from<T: Runtime + Sized>(&self, ctx: &mut Context, runtime: &T) -> ClassMember {
-        let mut statements = runtime.from_binary(ctx, &self);
+    fn print_merge_from<T: Runtime + Sized>(&self, ctx: &mut Context, runtime: &T) -> Vec<ClassMember> {
+        let (mut statements, mut members) = runtime.from_binary(ctx, &self);
 
         statements.push(crate::return_stmt!(quote_ident!("this").into()));
 
-        ClassMember::Method(ClassMethod {
+        members.push(ClassMember::Method(ClassMethod {
             span: DUMMY_SP,
             accessibility: None,
             key: PropName::Ident(quote_ident!("mergeFrom")),
@@ -172,7 +269,8 @@ impl DescriptorProto {
                 type_params: None,
             }),
             kind: MethodKind::Method,
-        })
+        }));
+        members
     }
 }
 
@@ -188,9 +286,16 @@ where
         let mut members: Vec<ClassMember> = Vec::new();
 
         members.push(self.print_message_type(ctx));
+        if ctx.options.with_descriptor {
+            members.push(self.print_descriptor_getter(ctx));
+        }
         // members.push(self.print_unknown_fields());
 
         for member in self.field.clone() {
+            if member.is_lazy(ctx) {
+                members.append(&mut member.print_lazy_members(ctx, runtime));
+                continue;
+            }
                 members.push(member.print_prop(ctx, runtime));
             if member.has_oneof_index() && ! member.proto3_optional(){
                 let other_oneofs = self.get_oneof_fields(&member);
@@ -198,28 +303,45 @@ where
                 members.push(member.print_oneof_setter(ctx, runtime, &other_oneofs));
             }
         }
-        members.push(self.print_merge_from(ctx, runtime));
+        members.push(self.print_create(ctx));
+        members.append(&mut self.print_merge_from(ctx, runtime));
         members.push(self.print_deserialize(ctx));
-        members.push(self.print_serialize(ctx, runtime));
-
-        let to_json_class_member = self.print_to_json(ctx);
-        for class_member in to_json_class_member {
-            members.push(
-                runtime
-                    .to_json(ctx, self)
-                    .unwrap_or_else(|| class_member),
-            );
+        members.append(&mut self.print_serialize(ctx, runtime));
+        if ctx.options.with_delimited {
+            members.append(&mut self.print_delimited_members(ctx));
         }
-        
-        let from_json_class_members = self.print_from_json(ctx);
-        for class_member in from_json_class_members {
-            members.push(
-                runtime
-                    .from_json(ctx, self)
-                    .unwrap_or_else(|| class_member),
-            );
+        if ctx.options.with_byte_size {
+            members.append(&mut self.print_sized_members(ctx));
         }
-    
+
+        // well known types replace the whole generated json codec.
+        let to_json = runtime.to_json(ctx, self);
+        let has_json_interface = to_json.is_none();
+        match to_json {
+            Some(member) => members.push(member),
+            None if ctx.options.arkts_strict => members.append(&mut self.print_strict_to_json(ctx)),
+            None => members.append(&mut self.print_to_json(ctx)),
+        }
+
+        match runtime.from_json(ctx, self) {
+            Some(member) => members.push(member),
+            None if ctx.options.arkts_strict => members.append(&mut self.print_strict_from_json(ctx)),
+            None => members.append(&mut self.print_from_json(ctx)),
+        }
+        if has_json_interface {
+            members.append(&mut self.print_json_string_members(ctx));
+        }
+        if ctx.options.with_text_format {
+            members.append(&mut self.print_text_members(ctx));
+        }
+
+        members.append(&mut self.print_value_members(ctx));
+        members.append(&mut runtime.print_members(ctx, self));
+
+        if ctx.options.with_field_mask {
+            members.append(&mut self.print_field_mask_members(ctx));
+        }
+
         let mut decorators = Vec::new();
         if ctx.options.with_sendable {
             let sendable_decorator = Decorator {
@@ -229,6 +351,11 @@ where
             decorators.push(sendable_decorator)
         }
 
+        let mut implements = vec![];
+        if ctx.options.with_text_format {
+            implements.push(self.text_message_implements(ctx));
+        }
+
         let class_decl = ClassDecl {
             ident: quote_ident!(ctx.normalize_name(self.name())),
             declare: false,
@@ -236,7 +363,7 @@ where
                 span: DUMMY_SP,
                 body: members,
                 decorators: decorators,
-                implements: vec![],
+                implements,
                 is_abstract: false,
                 type_params: None,
                 super_class: None,
@@ -249,7 +376,10 @@ where
             span: DUMMY_SP,
         }));
 
-        let mut modules = vec![module];
+        let mut modules = vec![module, self.print_init_interface(ctx)];
+        if has_json_interface {
+            modules.push(self.print_json_interface(ctx));
+        }
 
         if self.nested_type.len() != 0 || self.enum_type.len() != 0 {
             let mut ctx = ctx.descend(self.name().to_string());
@@ -9,4 +9,14 @@ pub mod macros;
 pub mod oneof;
 pub mod json;
 pub mod service;
-pub mod method;
\ No newline at end of file
+pub mod method;
+pub mod reflection;
+pub mod registry;pub mod field_mask;
+pub mod value;
+pub mod init;
+pub mod json_strict;
+pub mod text;
+pub mod delimited;
+pub mod lazy;
+pub mod sized;
+pub mod binary;
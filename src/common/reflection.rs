@@ -0,0 +1,219 @@
+use crate::context::Context;
+use crate::descriptor::{DescriptorProto, EnumDescriptorProto, FileDescriptorProto};
+use protobuf::{Message, MessageField};
+use swc_common::DUMMY_SP;
+use swc_ecma_ast::{
+    BlockStmt, ClassMember, ClassMethod, Decl, ExportDecl, Expr, FnDecl, Function, MethodKind,
+    ModuleDecl, ModuleItem, PatOrExpr, PropName, Stmt,
+};
+use swc_ecma_utils::{quote_ident, quote_str};
+
+pub const DESCRIPTOR_PROTO: &str = "google/protobuf/descriptor.proto";
+
+fn file_descriptor_fn_name(ctx: &Context) -> String {
+    format!("file_{}", ctx.file_alias())
+}
+
+impl FileDescriptorProto {
+    // let file_foo_cache: FileDescriptorProto | undefined = undefined;
+    // export function file_foo(): FileDescriptorProto {
+    //     if (file_foo_cache === undefined) {
+    //         file_foo_cache = FileDescriptorProto.fromBinary(toUint8Array("..."));
+    //     }
+    //     return file_foo_cache;
+    // }
+    pub(super) fn print_file_descriptor(&self, ctx: &mut Context) -> Vec<ModuleItem> {
+        let fn_name = file_descriptor_fn_name(ctx);
+        let cache_name = format!("{}_cache", fn_name);
+        let descriptor_type = ctx.lazy_type_ref(".google.protobuf.FileDescriptorProto");
+
+        let mut embedded = self.clone();
+        embedded.source_code_info = MessageField::none();
+        let bytes = embedded
+            .write_to_bytes()
+            .expect("can not serialize the file descriptor");
+
+        ctx.get_base64_import(&ctx.options.base64_package);
+        let decode_expr = crate::call_expr!(
+            crate::member_expr!(descriptor_type.clone(), "fromBinary"),
+            vec![crate::expr_or_spread!(crate::call_expr!(
+                quote_ident!("toUint8Array").into(),
+                vec![crate::expr_or_spread!(crate::lit_str!(
+                    super::util::base64_encode(&bytes)
+                )
+                .into())]
+            ))]
+        );
+
+        let cache_decl = ModuleItem::Stmt(Stmt::Decl(crate::let_decl!(
+            cache_name.clone(),
+            Some(Box::new(crate::type_union!(
+                crate::type_ref!(crate::entity_name_ident!(descriptor_type.clone())),
+                crate::undefined_type!()
+            ))),
+            quote_ident!("undefined").into()
+        )));
+
+        let function = Function {
+            params: vec![],
+            decorators: vec![],
+            span: DUMMY_SP,
+            body: Some(BlockStmt {
+                span: DUMMY_SP,
+                stmts: vec![
+                    crate::if_stmt!(
+                        crate::bin_expr!(
+                            quote_ident!(cache_name.clone()).into(),
+                            quote_ident!("undefined").into(),
+                            swc_ecma_ast::BinaryOp::EqEqEq
+                        ),
+                        crate::block_stmt!(vec![crate::expr_stmt!(crate::assign_expr!(
+                            PatOrExpr::Expr(Box::new(quote_ident!(cache_name.clone()).into())),
+                            decode_expr
+                        ))])
+                    ),
+                    crate::return_stmt!(quote_ident!(cache_name).into()),
+                ],
+            }),
+            is_generator: false,
+            is_async: false,
+            type_params: None,
+            return_type: Some(Box::new(crate::type_annotation!(crate::type_ref!(
+                crate::entity_name_ident!(descriptor_type)
+            )))),
+        };
+
+        let export = ModuleItem::ModuleDecl(ModuleDecl::ExportDecl(ExportDecl {
+            span: DUMMY_SP,
+            decl: Decl::Fn(FnDecl {
+                ident: quote_ident!(fn_name.clone()),
+                declare: false,
+                function: Box::new(function),
+            }),
+        }));
+
+        // descriptor_registry.register("foo.proto", file_foo);
+        let registry = ctx.lazy_symbol_ref(DESCRIPTOR_PROTO, "descriptor_registry");
+        let register = ModuleItem::Stmt(crate::expr_stmt!(crate::call_expr!(
+            crate::member_expr!(registry, "register"),
+            vec![
+                crate::expr_or_spread!(quote_str!(ctx.file_name()).into()),
+                crate::expr_or_spread!(quote_ident!(fn_name).into()),
+            ]
+        )));
+
+        vec![cache_decl, export, register]
+    }
+}
+
+// file_foo().message_type[0].nested_type[1] for messages, followed by .enum_type[0] for enums.
+fn descriptor_lookup_expr(ctx: &Context, type_name: &str, is_enum: bool) -> Expr {
+    let file = ctx
+        .get_file(ctx.file_name())
+        .expect("expected the file of the type to be registered");
+    let mut relative = type_name.trim_start_matches('.');
+    if file.has_package() && !file.package().is_empty() {
+        relative = relative
+            .strip_prefix(file.package())
+            .and_then(|name| name.strip_prefix('.'))
+            .expect("expected type to be in the package of its file");
+    }
+
+    let mut expr = crate::call_expr!(quote_ident!(file_descriptor_fn_name(ctx)).into());
+    let mut names: Vec<&str> = relative.split('.').collect();
+    let enum_name = if is_enum { names.pop() } else { None };
+    let mut messages = file.message_type.clone();
+    let mut enums = file.enum_type.clone();
+    let mut collection = "message_type";
+    for name in names {
+        let index = messages
+            .iter()
+            .position(|message| message.name() == name)
+            .unwrap_or_else(|| panic!("can not find {} in its file descriptor", type_name));
+        expr = crate::member_expr_computed!(
+            crate::member_expr_bare!(expr, collection),
+            crate::lit_num!(index).into()
+        );
+        enums = messages[index].enum_type.clone();
+        messages = messages[index].nested_type.clone();
+        collection = "nested_type";
+    }
+    if let Some(name) = enum_name {
+        let index = enums
+            .iter()
+            .position(|e| e.name() == name)
+            .unwrap_or_else(|| panic!("can not find {} in its file descriptor", type_name));
+        expr = crate::member_expr_computed!(
+            crate::member_expr_bare!(expr, "enum_type"),
+            crate::lit_num!(index).into()
+        );
+    }
+    expr
+}
+
+impl DescriptorProto {
+    pub(super) fn print_descriptor_getter(&self, ctx: &mut Context) -> ClassMember {
+        let descriptor_type = ctx.lazy_type_ref(".google.protobuf.DescriptorProto");
+        ClassMember::Method(ClassMethod {
+            span: DUMMY_SP,
+            accessibility: None,
+            key: PropName::Ident(quote_ident!("descriptor")),
+            is_abstract: false,
+            is_optional: false,
+            is_override: false,
+            is_static: true,
+            function: Box::new(Function {
+                body: Some(BlockStmt {
+                    span: DUMMY_SP,
+                    stmts: vec![crate::return_stmt!(descriptor_lookup_expr(
+                        ctx,
+                        &ctx.calculate_type_name(self.name()),
+                        false
+                    ))],
+                }),
+                decorators: vec![],
+                is_async: false,
+                is_generator: false,
+                params: vec![],
+                return_type: Some(Box::new(crate::type_annotation!(crate::type_ref!(
+                    crate::entity_name_ident!(descriptor_type)
+                )))),
+                span: DUMMY_SP,
+                type_params: None,
+            }),
+            kind: MethodKind::Method,
+        })
+    }
+}
+
+impl EnumDescriptorProto {
+    // export function foo_Kind_descriptor(): EnumDescriptorProto {
+    //     return file_foo().enum_type[0];
+    // }
+    pub(super) fn print_descriptor_function(&self, ctx: &mut Context) -> ModuleItem {
+        let descriptor_type = ctx.lazy_type_ref(".google.protobuf.EnumDescriptorProto");
+        let lookup = descriptor_lookup_expr(ctx, &ctx.calculate_type_name(self.name()), true);
+        ModuleItem::ModuleDecl(ModuleDecl::ExportDecl(ExportDecl {
+            span: DUMMY_SP,
+            decl: Decl::Fn(FnDecl {
+                ident: quote_ident!(format!("{}_descriptor", ctx.normalize_name(self.name()))),
+                declare: false,
+                function: Box::new(Function {
+                    params: vec![],
+                    decorators: vec![],
+                    span: DUMMY_SP,
+                    body: Some(BlockStmt {
+                        span: DUMMY_SP,
+                        stmts: vec![crate::return_stmt!(lookup)],
+                    }),
+                    is_generator: false,
+                    is_async: false,
+                    type_params: None,
+                    return_type: Some(Box::new(crate::type_annotation!(crate::type_ref!(
+                        crate::entity_name_ident!(descriptor_type)
+                    )))),
+                }),
+            }),
+        }))
+    }
+}
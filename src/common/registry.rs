@@ -0,0 +1,178 @@
+use crate::context::{import_alias, Context};
+use crate::descriptor::{DescriptorProto, FileDescriptorProto};
+use std::collections::HashSet;
+use swc_common::DUMMY_SP;
+use swc_ecma_ast::{
+    BlockStmt, Decl, ExportDecl, Expr, FnDecl, Function, ModuleDecl, ModuleItem, PatOrExpr,
+    ReturnStmt, Stmt,
+};
+use swc_ecma_utils::{quote_ident, quote_str};
+
+use super::text::TEXT_SUPPORT_MODULE;
+
+const ANY_TYPE_NAME: &str = ".google.protobuf.Any";
+
+fn register_fn_name(file_name: &str) -> String {
+    format!("register_{}", import_alias(file_name))
+}
+
+/// Whether the output of `file_name` exports a register function, that is, it
+/// defines messages itself or pulls them in through its dependencies.
+fn has_type_registration(ctx: &Context, file_name: &str) -> bool {
+    reaches_messages(ctx, file_name, &mut HashSet::new())
+}
+
+// files reached before were already searched, which keeps diamond imports linear.
+fn reaches_messages(ctx: &Context, file_name: &str, visited: &mut HashSet<String>) -> bool {
+    if !visited.insert(file_name.to_string()) {
+        return false;
+    }
+    match ctx.get_file(file_name) {
+        Some(file) => {
+            !file.message_type.is_empty()
+                || file
+                    .dependency
+                    .iter()
+                    .any(|dependency| reaches_messages(ctx, dependency, visited))
+        }
+        None => false,
+    }
+}
+
+impl DescriptorProto {
+    fn collect_registered_types(&self, ctx: &mut Context, types: &mut Vec<(String, Expr)>) {
+        if self.options.map_entry() {
+            return;
+        }
+        let class_name = if ctx.options.namespaces {
+            ctx.calculate_type_name(self.name())
+                .trim_start_matches('.')
+                .to_string()
+        } else {
+            ctx.normalize_name(self.name())
+        };
+        types.push((
+            ctx.calculate_type_name(self.name())
+                .trim_start_matches('.')
+                .to_string(),
+            quote_ident!(class_name).into(),
+        ));
+
+        let mut ctx = ctx.descend(self.name().to_string());
+        for nested in &self.nested_type {
+            nested.collect_registered_types(&mut ctx, types);
+        }
+    }
+}
+
+impl FileDescriptorProto {
+    // let file_foo_registered: boolean = false;
+    // export function register_foo(): void {
+    //     if (file_foo_registered) {
+    //         return;
+    //     }
+    //     file_foo_registered = true;
+    //     bar.register_bar();
+    //     type_registry.set("foo.Foo", foo_Foo);
+    // }
+    // register_foo();
+    pub(super) fn print_type_registration(&self, ctx: &mut Context) -> Vec<ModuleItem> {
+        // the registry lives next to google.protobuf.Any, nothing reads it without Any.
+        let registry_provider = match ctx.find_type_provider(&ANY_TYPE_NAME.to_string()) {
+            Some(provider) => provider,
+            None => return vec![],
+        };
+        if !has_type_registration(ctx, ctx.file_name()) {
+            return vec![];
+        }
+
+        let fn_name = register_fn_name(ctx.file_name());
+        let flag_name = format!("file_{}_registered", ctx.file_alias());
+
+        let mut stmts = vec![
+            crate::if_stmt!(
+                quote_ident!(flag_name.clone()).into(),
+                crate::block_stmt!(vec![Stmt::Return(ReturnStmt {
+                    span: DUMMY_SP,
+                    arg: None,
+                })])
+            ),
+            crate::expr_stmt!(crate::assign_expr!(
+                PatOrExpr::Expr(Box::new(quote_ident!(flag_name.clone()).into())),
+                quote_ident!("true").into()
+            )),
+        ];
+
+        // register imported files first, their messages may be packed into Any as well.
+        for dependency in &self.dependency {
+            if !has_type_registration(ctx, dependency) {
+                continue;
+            }
+            let register = ctx.lazy_symbol_ref(dependency, &register_fn_name(dependency));
+            stmts.push(crate::expr_stmt!(crate::call_expr!(register.into())));
+        }
+
+        let mut types = vec![];
+        for message in &self.message_type {
+            message.collect_registered_types(ctx, &mut types);
+        }
+        let registry = ctx.lazy_symbol_ref(&registry_provider, "type_registry");
+        // the text format expands Any through a registry of its own.
+        let text_registry = if ctx.options.with_text_format {
+            Some(ctx.lazy_support_ref(TEXT_SUPPORT_MODULE, "text_registry"))
+        } else {
+            None
+        };
+        for (type_name, class) in types {
+            stmts.push(crate::expr_stmt!(crate::call_expr!(
+                crate::member_expr!(registry.clone(), "set"),
+                vec![
+                    crate::expr_or_spread!(quote_str!(type_name.clone()).into()),
+                    crate::expr_or_spread!(class.clone()),
+                ]
+            )));
+            if let Some(text_registry) = &text_registry {
+                stmts.push(crate::expr_stmt!(crate::call_expr!(
+                    crate::member_expr!(text_registry.clone(), "set"),
+                    vec![
+                        crate::expr_or_spread!(quote_str!(type_name).into()),
+                        crate::expr_or_spread!(class),
+                    ]
+                )));
+            }
+        }
+
+        let flag_decl = ModuleItem::Stmt(Stmt::Decl(crate::let_decl!(
+            flag_name,
+            Some(Box::new(crate::type_annotation!("boolean"))),
+            quote_ident!("false").into()
+        )));
+
+        let register_fn = ModuleItem::ModuleDecl(ModuleDecl::ExportDecl(ExportDecl {
+            span: DUMMY_SP,
+            decl: Decl::Fn(FnDecl {
+                ident: quote_ident!(fn_name.clone()),
+                declare: false,
+                function: Box::new(Function {
+                    params: vec![],
+                    decorators: vec![],
+                    span: DUMMY_SP,
+                    body: Some(BlockStmt {
+                        span: DUMMY_SP,
+                        stmts,
+                    }),
+                    is_generator: false,
+                    is_async: false,
+                    type_params: None,
+                    return_type: Some(Box::new(crate::type_annotation!("void"))),
+                }),
+            }),
+        }));
+
+        let register_call = ModuleItem::Stmt(crate::expr_stmt!(crate::call_expr!(
+            quote_ident!(fn_name).into()
+        )));
+
+        vec![flag_decl, register_fn, register_call]
+    }
+}
@@ -0,0 +1,677 @@
+use crate::context::Context;
+use crate::descriptor::field_descriptor_proto::Type;
+use crate::descriptor::{DescriptorProto, FieldDescriptorProto};
+use swc_common::DUMMY_SP;
+use swc_ecma_ast::{
+    AssignOp, BinaryOp, BlockStmt, ClassMember, ClassMethod, Expr, ForHead, ForOfStmt, Function,
+    MethodKind, Param, PatOrExpr, PropName, Stmt, TsAsExpr, TsKeywordTypeKind, TsNonNullExpr, TsType,
+};
+use swc_ecma_utils::quote_ident;
+
+use super::binary::binary_options_param;
+use super::field::this_field_member;
+use super::json::json_param;
+
+// the sizes and the writer of the preallocated binary encoding, emitted at the root of the output.
+pub const SIZED_SUPPORT_MODULE: &str = "sized_support";
+pub const SIZED_SUPPORT_SOURCE: &str = include_str!("../../js/runtime/sized_support.ts");
+
+const WIRE_VARINT: u32 = 0;
+const WIRE_FIXED64: u32 = 1;
+const WIRE_DELIMITED: u32 = 2;
+const WIRE_FIXED32: u32 = 5;
+
+// the encoded size of a tag is known when generating the code.
+fn tag_size(number: i32, wire_type: u32) -> usize {
+    let mut tag = ((number as u32) << 3) | wire_type;
+    let mut size = 1;
+    while tag >= 0x80 {
+        tag >>= 7;
+        size += 1;
+    }
+    size
+}
+
+fn writer_call(method: &str, args: Vec<Expr>) -> Stmt {
+    crate::expr_stmt!(crate::call_expr!(
+        crate::member_expr!("w", method),
+        args.into_iter().map(|arg| crate::expr_or_spread!(arg)).collect()
+    ))
+}
+
+fn support_call(ctx: &Context, function: &str, args: Vec<Expr>) -> Expr {
+    crate::call_expr!(
+        ctx.lazy_support_ref(SIZED_SUPPORT_MODULE, function).into(),
+        args.into_iter().map(|arg| crate::expr_or_spread!(arg)).collect()
+    )
+}
+
+fn delimited_size(ctx: &Context, length: Expr) -> Expr {
+    support_call(ctx, "delimitedSize", vec![length])
+}
+
+fn add(left: Expr, right: Expr) -> Expr {
+    crate::bin_expr!(left, right, BinaryOp::Add)
+}
+
+// name += value
+fn add_assign_stmt(name: &str, value: Expr) -> Stmt {
+    crate::expr_stmt!(crate::assign_expr!(
+        PatOrExpr::Expr(Box::new(quote_ident!(name).into())),
+        value,
+        AssignOp::AddAssign
+    ))
+}
+
+fn number_decl(name: &str, init: Expr) -> Stmt {
+    Stmt::Decl(crate::let_decl!(
+        name,
+        Some(Box::new(crate::type_annotation!(crate::keyword_type!(TsKeywordTypeKind::TsNumberKeyword)))),
+        init
+    ))
+}
+
+fn for_of_stmt(name: &str, iterable: Expr, stmts: Vec<Stmt>) -> Stmt {
+    Stmt::ForOf(ForOfStmt {
+        is_await: false,
+        left: ForHead::VarDecl(Box::new(crate::const_decl_uinit!(name))),
+        right: Box::new(iterable),
+        body: Box::new(crate::block_stmt!(stmts)),
+        span: DUMMY_SP,
+    })
+}
+
+// options?: binary_support.BinaryWriteOptions, cache?: sized_support.SizeCache
+fn byte_size_params(ctx: &Context) -> Vec<Param> {
+    vec![
+        binary_options_param(ctx),
+        json_param(
+            "cache?",
+            crate::type_ref!(crate::entity_name_ident!(ctx.lazy_support_ref(SIZED_SUPPORT_MODULE, "SizeCache"))),
+        ),
+    ]
+}
+
+fn byte_size_call(message: Expr, args: Vec<&str>) -> Expr {
+    crate::call_expr!(
+        crate::member_expr_bare!(message, "byteSize"),
+        args.into_iter()
+            .map(|arg| crate::expr_or_spread!(quote_ident!(arg).into()))
+            .collect()
+    )
+}
+
+// sized_support.recordSize(cache, message, message.byteSize(options, cache)) while sizing and
+// w.cachedSize(message) ?? message.byteSize(options) while writing, which takes the recorded size.
+fn message_size(ctx: &Context, message: Expr, writing: bool) -> Expr {
+    if writing {
+        crate::bin_expr!(
+            crate::call_expr!(
+                crate::member_expr!("w", "cachedSize"),
+                vec![crate::expr_or_spread!(message.clone())]
+            ),
+            byte_size_call(message, vec!["options"]),
+            BinaryOp::NullishCoalescing
+        )
+    } else {
+        support_call(
+            ctx,
+            "recordSize",
+            vec![
+                quote_ident!("cache").into(),
+                message.clone(),
+                byte_size_call(message, vec!["options", "cache"]),
+            ],
+        )
+    }
+}
+
+// options?.deterministic !== true
+fn not_deterministic() -> Expr {
+    crate::bin_expr!(
+        crate::member_expr!("options?", "deterministic"),
+        quote_ident!("true").into(),
+        BinaryOp::NotEqEq
+    )
+}
+
+fn to_binary_call(message: Expr) -> Expr {
+    crate::call_expr!(
+        crate::member_expr_bare!(message, "toBinary"),
+        vec![crate::expr_or_spread!(quote_ident!("options").into())]
+    )
+}
+
+fn non_null(expr: Expr) -> Expr {
+    Expr::TsNonNull(TsNonNullExpr {
+        span: DUMMY_SP,
+        expr: Box::new(expr),
+    })
+}
+
+impl FieldDescriptorProto {
+    fn wire_type(&self) -> u32 {
+        match self.type_() {
+            Type::TYPE_FIXED64 | Type::TYPE_SFIXED64 | Type::TYPE_DOUBLE => WIRE_FIXED64,
+            Type::TYPE_FIXED32 | Type::TYPE_SFIXED32 | Type::TYPE_FLOAT => WIRE_FIXED32,
+            Type::TYPE_STRING | Type::TYPE_BYTES | Type::TYPE_MESSAGE => WIRE_DELIMITED,
+            _ => WIRE_VARINT,
+        }
+    }
+
+    fn sized_tag_size(&self, wire_type: u32) -> Expr {
+        crate::lit_num!(tag_size(self.number(), wire_type) as f64).into()
+    }
+
+    // w.writeTag(1, 2)
+    fn sized_tag_stmt(&self, wire_type: u32) -> Stmt {
+        writer_call(
+            "writeTag",
+            vec![
+                crate::lit_num!(self.number() as f64).into(),
+                crate::lit_num!(wire_type as f64).into(),
+            ],
+        )
+    }
+
+    // the suffix of the writer method and of the size function of the value.
+    fn sized_type_name(&self) -> &'static str {
+        match self.type_() {
+            Type::TYPE_INT32 | Type::TYPE_ENUM => "Int32",
+            Type::TYPE_UINT32 => "Uint32",
+            Type::TYPE_SINT32 => "Sint32",
+            Type::TYPE_INT64 => "Int64",
+            Type::TYPE_UINT64 => "Uint64",
+            Type::TYPE_SINT64 => "Sint64",
+            Type::TYPE_FIXED32 => "Fixed32",
+            Type::TYPE_SFIXED32 => "Sfixed32",
+            Type::TYPE_FIXED64 => "Fixed64",
+            Type::TYPE_SFIXED64 => "Sfixed64",
+            Type::TYPE_FLOAT => "Float",
+            Type::TYPE_DOUBLE => "Double",
+            Type::TYPE_BOOL => "Bool",
+            Type::TYPE_STRING => "String",
+            Type::TYPE_BYTES => "Bytes",
+            typ => unimplemented!("sized_type_name {:?}", typ),
+        }
+    }
+
+    fn sized_fixed_size(&self) -> Option<usize> {
+        match self.type_() {
+            Type::TYPE_BOOL => Some(1),
+            Type::TYPE_FIXED32 | Type::TYPE_SFIXED32 | Type::TYPE_FLOAT => Some(4),
+            Type::TYPE_FIXED64 | Type::TYPE_SFIXED64 | Type::TYPE_DOUBLE => Some(8),
+            _ => None,
+        }
+    }
+
+    // well-known types of a prebuilt package have no byteSize method, they are written as bytes.
+    fn sized_as_bytes(&self, ctx: &Context) -> bool {
+        !ctx.options.wkt_package.is_empty() && self.is_well_known_message()
+    }
+
+    // the size of a value without its tag, writing tells whether it is computed by writeTo.
+    fn sized_value_size(&self, ctx: &mut Context, value: Expr, writing: bool) -> Expr {
+        if self.is_message() && self.sized_as_bytes(ctx) {
+            return delimited_size(ctx, crate::member_expr_bare!(to_binary_call(value), "length"));
+        }
+        if self.is_message() {
+            return delimited_size(ctx, message_size(ctx, value, writing));
+        }
+        if self.is_bytes() {
+            return delimited_size(ctx, crate::member_expr_bare!(value, "length"));
+        }
+        match self.sized_fixed_size() {
+            Some(size) => crate::lit_num!(size as f64).into(),
+            None => {
+                let function = format!("{}Size", self.sized_type_name().to_lowercase());
+                support_call(ctx, &function, vec![value])
+            }
+        }
+    }
+
+    // writes a value without its tag.
+    fn sized_value_stmts(&self, ctx: &mut Context, value: Expr) -> Vec<Stmt> {
+        if self.is_message() && self.sized_as_bytes(ctx) {
+            return vec![writer_call("writeBytes", vec![to_binary_call(value)])];
+        }
+        if self.is_message() {
+            // w.writeUint32(w.cachedSize(value) ?? value.byteSize(options)); value.writeTo(w, options);
+            return vec![
+                writer_call("writeUint32", vec![message_size(ctx, value.clone(), true)]),
+                crate::expr_stmt!(crate::call_expr!(
+                    crate::member_expr_bare!(value, "writeTo"),
+                    vec![
+                        crate::expr_or_spread!(quote_ident!("w").into()),
+                        crate::expr_or_spread!(quote_ident!("options").into()),
+                    ]
+                )),
+            ];
+        }
+        let mut value = value;
+        if self.is_bytes() && ctx.options.with_sendable {
+            value = crate::call_expr!(
+                crate::member_expr!("Uint8Array", "from"),
+                vec![crate::expr_or_spread!(value)]
+            );
+        }
+        vec![writer_call(&format!("write{}", self.sized_type_name()), vec![value])]
+    }
+
+    // the size of the key and value fields of a map entry.
+    fn sized_map_entry_size(&self, ctx: &mut Context, map: &DescriptorProto, writing: bool) -> Expr {
+        let (key, value) = (&map.field[0], &map.field[1]);
+        let entry = |index: f64| crate::member_expr_computed!(quote_ident!("entry").into(), crate::lit_num!(index).into());
+        let key_size = key.sized_value_size(ctx, entry(0.0), writing);
+        let value_size = value.sized_value_size(ctx, entry(1.0), writing);
+        add(
+            add(add(key.sized_tag_size(key.wire_type()), key_size), value.sized_tag_size(value.wire_type())),
+            value_size,
+        )
+    }
+
+    // the size of the value wrapped by an unboxed wrapper message, 0 for the default value.
+    fn sized_wrapped_stmts(&self, ctx: &mut Context, unboxed: &FieldDescriptorProto) -> (FieldDescriptorProto, Vec<Stmt>) {
+        let mut value_field = unboxed.clone();
+        value_field.set_number(1);
+        value_field.set_proto3_optional(false);
+        let value_size = add(
+            value_field.sized_tag_size(value_field.wire_type()),
+            value_field.sized_value_size(ctx, this_field_member(self), false),
+        );
+        let stmts = vec![
+            number_decl("wrapped", crate::lit_num!(0).into()),
+            crate::if_stmt!(
+                value_field.default_value_bin_expr(ctx, this_field_member),
+                crate::expr_stmt!(crate::assign_expr!(
+                    PatOrExpr::Expr(Box::new(quote_ident!("wrapped").into())),
+                    value_size
+                ))
+            ),
+        ];
+        (value_field, stmts)
+    }
+
+    // the size of the elements of a packed field, without the tag and length prefix.
+    fn sized_packed_stmts(&self, ctx: &mut Context) -> Vec<Stmt> {
+        let elements = this_field_member(self);
+        match self.sized_fixed_size() {
+            Some(size) => vec![number_decl(
+                "packed",
+                crate::bin_expr!(
+                    crate::member_expr_bare!(elements, "length"),
+                    crate::lit_num!(size as f64).into(),
+                    BinaryOp::Mul
+                ),
+            )],
+            None => vec![
+                number_decl("packed", crate::lit_num!(0).into()),
+                for_of_stmt(
+                    "value",
+                    elements,
+                    vec![add_assign_stmt("packed", self.sized_value_size(ctx, quote_ident!("value").into(), false))],
+                ),
+            ],
+        }
+    }
+
+    // adds the encoded size of the field to `size`.
+    fn print_byte_size_stmt(&self, ctx: &mut Context) -> Option<Stmt> {
+        if self.is_group() {
+            return None;
+        }
+        let value = this_field_member(self);
+        let tag = self.sized_tag_size(self.wire_type());
+        let delimited_tag = self.sized_tag_size(WIRE_DELIMITED);
+
+        if self.is_lazy(ctx) {
+            // bytes which were never decoded are written back as they are, unless the output has to be
+            // deterministic and the field is decoded through its getter.
+            let bytes = crate::member_expr!("this", self.lazy_bytes_name());
+            let decoded = crate::member_expr!("this", self.name());
+            return Some(crate::if_stmt!(
+                crate::bin_expr!(
+                    crate::bin_expr!(bytes.clone(), quote_ident!("undefined").into(), BinaryOp::NotEqEq),
+                    not_deterministic()
+                ),
+                add_assign_stmt(
+                    "size",
+                    add(delimited_tag.clone(), delimited_size(ctx, crate::member_expr_bare!(bytes, "length")))
+                ),
+                crate::if_stmt!(
+                    crate::bin_expr!(decoded.clone(), quote_ident!("undefined").into(), BinaryOp::NotEqEq),
+                    add_assign_stmt("size", add(delimited_tag, self.sized_value_size(ctx, decoded, false)))
+                )
+            ));
+        }
+
+        let stmts = if self.is_map(ctx) {
+            let map = ctx
+                .get_map_type(self.type_name())
+                .unwrap_or_else(|| panic!("can not find the map type {}", self.type_name()));
+            let entry_size = self.sized_map_entry_size(ctx, &map, false);
+            vec![for_of_stmt(
+                "entry",
+                crate::call_expr!(crate::member_expr_bare!(value, "entries")),
+                vec![add_assign_stmt("size", add(delimited_tag, delimited_size(ctx, entry_size)))],
+            )]
+        } else if self.is_packed(ctx) {
+            let mut stmts = self.sized_packed_stmts(ctx);
+            stmts.push(add_assign_stmt(
+                "size",
+                add(delimited_tag, delimited_size(ctx, quote_ident!("packed").into())),
+            ));
+            stmts
+        } else if self.is_repeated() {
+            match self.sized_fixed_size() {
+                Some(size) => vec![add_assign_stmt(
+                    "size",
+                    crate::bin_expr!(
+                        crate::member_expr_bare!(value, "length"),
+                        crate::lit_num!((tag_size(self.number(), self.wire_type()) + size) as f64).into(),
+                        BinaryOp::Mul
+                    ),
+                )],
+                None => {
+                    let value_size = self.sized_value_size(ctx, quote_ident!("value").into(), false);
+                    vec![for_of_stmt("value", value, vec![add_assign_stmt("size", add(tag, value_size))])]
+                }
+            }
+        } else if let Some(unboxed) = self.unboxed_wrapper(ctx) {
+            let (_, mut stmts) = self.sized_wrapped_stmts(ctx, &unboxed);
+            stmts.push(add_assign_stmt(
+                "size",
+                add(delimited_tag, delimited_size(ctx, quote_ident!("wrapped").into())),
+            ));
+            stmts
+        } else if self.is_plain_struct(ctx) {
+            // the converted message is kept for writeTo.
+            let message_type = ctx.lazy_type_ref(self.type_name());
+            let message = crate::call_expr!(
+                crate::member_expr!(message_type.clone(), "fromJson"),
+                vec![crate::expr_or_spread!(value.clone())]
+            );
+            vec![
+                Stmt::Decl(crate::const_decl!(format!("message: {}", message_type.sym), message)),
+                crate::if_stmt!(
+                    crate::bin_expr!(quote_ident!("cache").into(), quote_ident!("undefined").into(), BinaryOp::NotEqEq),
+                    crate::expr_stmt!(crate::call_expr!(
+                        crate::member_expr!("cache", "setMessage"),
+                        vec![
+                            crate::expr_or_spread!(value),
+                            crate::expr_or_spread!(quote_ident!("message").into()),
+                        ]
+                    ))
+                ),
+                add_assign_stmt(
+                    "size",
+                    add(delimited_tag, self.sized_value_size(ctx, quote_ident!("message").into(), false)),
+                ),
+            ]
+        } else if self.is_message() {
+            vec![add_assign_stmt("size", add(tag, self.sized_value_size(ctx, non_null(value), false)))]
+        } else {
+            vec![add_assign_stmt("size", add(tag, self.sized_value_size(ctx, value, false)))]
+        };
+
+        Some(crate::if_stmt!(
+            self.default_value_bin_expr(ctx, this_field_member),
+            crate::block_stmt!(stmts)
+        ))
+    }
+
+    // writes the field like toBinary does.
+    fn print_write_to_stmt(&self, ctx: &mut Context) -> Option<Stmt> {
+        if self.is_group() {
+            return None;
+        }
+        let value = this_field_member(self);
+
+        if self.is_lazy(ctx) {
+            let bytes = crate::member_expr!("this", self.lazy_bytes_name());
+            let decoded = crate::member_expr!("this", self.name());
+            let mut decoded_stmts = vec![self.sized_tag_stmt(WIRE_DELIMITED)];
+            decoded_stmts.append(&mut self.sized_value_stmts(ctx, decoded.clone()));
+            return Some(crate::if_stmt!(
+                crate::bin_expr!(
+                    crate::bin_expr!(bytes.clone(), quote_ident!("undefined").into(), BinaryOp::NotEqEq),
+                    not_deterministic()
+                ),
+                crate::block_stmt!(vec![
+                    self.sized_tag_stmt(WIRE_DELIMITED),
+                    writer_call("writeBytes", vec![bytes]),
+                ]),
+                crate::if_stmt!(
+                    crate::bin_expr!(decoded, quote_ident!("undefined").into(), BinaryOp::NotEqEq),
+                    crate::block_stmt!(decoded_stmts)
+                )
+            ));
+        }
+
+        let stmts = if self.is_map(ctx) {
+            let map = ctx
+                .get_map_type(self.type_name())
+                .unwrap_or_else(|| panic!("can not find the map type {}", self.type_name()));
+            let (key, map_value) = (&map.field[0], &map.field[1]);
+            let entry = |index: f64| crate::member_expr_computed!(quote_ident!("entry").into(), crate::lit_num!(index).into());
+            let mut entry_stmts = vec![
+                self.sized_tag_stmt(WIRE_DELIMITED),
+                writer_call("writeUint32", vec![self.sized_map_entry_size(ctx, &map, true)]),
+                key.sized_tag_stmt(key.wire_type()),
+            ];
+            entry_stmts.append(&mut key.sized_value_stmts(ctx, entry(0.0)));
+            entry_stmts.push(map_value.sized_tag_stmt(map_value.wire_type()));
+            entry_stmts.append(&mut map_value.sized_value_stmts(ctx, entry(1.0)));
+            vec![for_of_stmt("entry", self.ordered_entries_expr(ctx, value), entry_stmts)]
+        } else if self.is_packed(ctx) {
+            let mut stmts = vec![self.sized_tag_stmt(WIRE_DELIMITED)];
+            stmts.append(&mut self.sized_packed_stmts(ctx));
+            stmts.push(writer_call("writeUint32", vec![quote_ident!("packed").into()]));
+            stmts.push(for_of_stmt(
+                "value",
+                value,
+                self.sized_value_stmts(ctx, quote_ident!("value").into()),
+            ));
+            stmts
+        } else if self.is_repeated() {
+            let mut element_stmts = vec![self.sized_tag_stmt(self.wire_type())];
+            element_stmts.append(&mut self.sized_value_stmts(ctx, quote_ident!("value").into()));
+            vec![for_of_stmt("value", value, element_stmts)]
+        } else if let Some(unboxed) = self.unboxed_wrapper(ctx) {
+            let (value_field, wrapped) = self.sized_wrapped_stmts(ctx, &unboxed);
+            let mut stmts = vec![self.sized_tag_stmt(WIRE_DELIMITED)];
+            stmts.extend(wrapped);
+            stmts.push(writer_call("writeUint32", vec![quote_ident!("wrapped").into()]));
+            let mut value_stmts = vec![value_field.sized_tag_stmt(value_field.wire_type())];
+            value_stmts.append(&mut value_field.sized_value_stmts(ctx, value));
+            stmts.push(crate::if_stmt!(
+                value_field.default_value_bin_expr(ctx, this_field_member),
+                crate::block_stmt!(value_stmts)
+            ));
+            stmts
+        } else if self.is_plain_struct(ctx) {
+            // the message byteSize converted, converted again when writing without its cache.
+            let message_type = ctx.lazy_type_ref(self.type_name());
+            let converted = crate::call_expr!(
+                crate::member_expr!("w", "convertedMessage"),
+                vec![crate::expr_or_spread!(value.clone())]
+            );
+            let message = crate::cond_expr!(
+                crate::bin_expr!(quote_ident!("converted").into(), quote_ident!("undefined").into(), BinaryOp::NotEqEq),
+                Expr::TsAs(TsAsExpr {
+                    span: DUMMY_SP,
+                    expr: Box::new(quote_ident!("converted").into()),
+                    type_ann: Box::new(crate::type_ref!(crate::entity_name_ident!(message_type.clone()))),
+                }),
+                crate::call_expr!(
+                    crate::member_expr!(message_type.clone(), "fromJson"),
+                    vec![crate::expr_or_spread!(value)]
+                )
+            );
+            let mut stmts = vec![
+                Stmt::Decl(crate::const_decl!("converted: Object | undefined", converted)),
+                Stmt::Decl(crate::const_decl!(format!("message: {}", message_type.sym), message)),
+                self.sized_tag_stmt(WIRE_DELIMITED),
+            ];
+            stmts.append(&mut self.sized_value_stmts(ctx, quote_ident!("message").into()));
+            stmts
+        } else {
+            let value = if self.is_message() { non_null(value) } else { value };
+            let mut stmts = vec![self.sized_tag_stmt(self.wire_type())];
+            stmts.append(&mut self.sized_value_stmts(ctx, value));
+            stmts
+        };
+
+        Some(crate::if_stmt!(
+            self.default_value_bin_expr(ctx, this_field_member),
+            crate::block_stmt!(stmts)
+        ))
+    }
+}
+
+impl DescriptorProto {
+    fn sized_method(
+        &self,
+        name: &str,
+        params: Vec<Param>,
+        stmts: Vec<Stmt>,
+        return_type: TsType,
+    ) -> ClassMember {
+        ClassMember::Method(ClassMethod {
+            span: DUMMY_SP,
+            accessibility: None,
+            key: PropName::Ident(quote_ident!(name)),
+            is_abstract: false,
+            is_optional: false,
+            is_override: false,
+            is_static: false,
+            function: Box::new(Function {
+                body: Some(BlockStmt {
+                    span: DUMMY_SP,
+                    stmts,
+                }),
+                decorators: vec![],
+                is_async: false,
+                is_generator: false,
+                params,
+                return_type: Some(Box::new(crate::type_annotation!(return_type))),
+                span: DUMMY_SP,
+                type_params: None,
+            }),
+            kind: MethodKind::Method,
+        })
+    }
+
+    // byteSize(options?: BinaryWriteOptions, cache?: SizeCache): number, the size of toBinary(options).
+    // Large messages sum the sizes of private byteSize_N() helpers. toBinaryInto passes a cache which
+    // keeps the sizes of the nested messages for writeTo.
+    fn print_byte_size(&self, ctx: &mut Context) -> Vec<ClassMember> {
+        let number_type = || crate::keyword_type!(TsKeywordTypeKind::TsNumberKeyword);
+        let mut members = vec![];
+        let mut sizes = vec![];
+        let chunks: Vec<Vec<FieldDescriptorProto>> =
+            self.method_field_chunks(ctx).into_iter().map(|fields| fields.to_vec()).collect();
+        for (index, fields) in chunks.iter().enumerate() {
+            let mut stmts = vec![number_decl("size", crate::lit_num!(0).into())];
+            stmts.extend(fields.iter().filter_map(|field| field.print_byte_size_stmt(ctx)));
+            stmts.push(crate::return_stmt!(quote_ident!("size").into()));
+            if chunks.len() == 1 {
+                return vec![self.sized_method("byteSize", byte_size_params(ctx), stmts, number_type())];
+            }
+            let helper = format!("byteSize_{}", index);
+            members.push(self.print_private_method(
+                helper.clone(),
+                false,
+                byte_size_params(ctx),
+                stmts,
+                Some(number_type()),
+            ));
+            sizes.push(crate::call_expr!(
+                crate::member_expr!("this", helper),
+                vec![
+                    crate::expr_or_spread!(quote_ident!("options").into()),
+                    crate::expr_or_spread!(quote_ident!("cache").into()),
+                ]
+            ));
+        }
+        let size = sizes
+            .into_iter()
+            .reduce(add)
+            .expect("expected at least one chunk of fields");
+        members.push(self.sized_method(
+            "byteSize",
+            byte_size_params(ctx),
+            vec![crate::return_stmt!(size)],
+            number_type(),
+        ));
+        members
+    }
+
+    // writeTo(w: sized_support.SizedWriter, options?: BinaryWriteOptions): void
+    fn print_write_to(&self, ctx: &mut Context) -> Vec<ClassMember> {
+        let params = vec![
+            json_param(
+                "w",
+                crate::type_ref!(crate::entity_name_ident!(ctx.lazy_support_ref(SIZED_SUPPORT_MODULE, "SizedWriter"))),
+            ),
+            binary_options_param(ctx),
+        ];
+        let (stmts, mut members) = self.print_method_chunks(ctx, "writeTo", false, params.clone(), |ctx, fields| {
+            fields.iter().filter_map(|field| field.print_write_to_stmt(ctx)).collect()
+        });
+        members.push(self.sized_method(
+            "writeTo",
+            params,
+            stmts,
+            crate::keyword_type!(TsKeywordTypeKind::TsVoidKeyword),
+        ));
+        members
+    }
+
+    // toBinaryInto(buffer?: Uint8Array, offset?: number, options?: BinaryWriteOptions): Uint8Array
+    // the sizes are computed once, writeTo takes those of the nested messages from the cache.
+    fn print_to_binary_into(&self, ctx: &mut Context) -> ClassMember {
+        let writer_class = ctx.lazy_support_ref(SIZED_SUPPORT_MODULE, "SizedWriter");
+        let cache_class = ctx.lazy_support_ref(SIZED_SUPPORT_MODULE, "SizeCache");
+        let writer = crate::new_expr!(
+            Expr::Ident(writer_class.clone()),
+            vec![
+                crate::expr_or_spread!(byte_size_call(quote_ident!("this").into(), vec!["options", "cache"])),
+                crate::expr_or_spread!(quote_ident!("buffer").into()),
+                crate::expr_or_spread!(quote_ident!("offset").into()),
+                crate::expr_or_spread!(quote_ident!("cache").into()),
+            ]
+        );
+        self.sized_method(
+            "toBinaryInto",
+            vec![
+                json_param("buffer?", crate::type_ref!("Uint8Array")),
+                json_param("offset?", crate::keyword_type!(TsKeywordTypeKind::TsNumberKeyword)),
+                binary_options_param(ctx),
+            ],
+            vec![
+                Stmt::Decl(crate::const_decl!(
+                    format!("cache: {}", cache_class.sym),
+                    crate::new_expr!(Expr::Ident(cache_class.clone()))
+                )),
+                Stmt::Decl(crate::const_decl!(format!("w: {}", writer_class.sym), writer)),
+                crate::expr_stmt!(crate::call_expr!(
+                    crate::member_expr!("this", "writeTo"),
+                    vec![
+                        crate::expr_or_spread!(quote_ident!("w").into()),
+                        crate::expr_or_spread!(quote_ident!("options").into()),
+                    ]
+                )),
+                crate::return_stmt!(crate::call_expr!(crate::member_expr!("w", "finish"))),
+            ],
+            crate::type_ref!("Uint8Array"),
+        )
+    }
+
+    pub(super) fn print_sized_members(&self, ctx: &mut Context) -> Vec<ClassMember> {
+        let mut members = self.print_byte_size(ctx);
+        members.append(&mut self.print_write_to(ctx));
+        members.push(self.print_to_binary_into(ctx));
+        members
+    }
+}
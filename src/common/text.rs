@@ -0,0 +1,538 @@
+use crate::context::Context;
+use crate::descriptor::field_descriptor_proto::Type;
+use crate::descriptor::{DescriptorProto, FieldDescriptorProto};
+use swc_common::DUMMY_SP;
+use swc_ecma_ast::{
+    BinaryOp, BlockStmt, BreakStmt, ClassMember, ClassMethod, Expr, ForHead, ForOfStmt, Function,
+    MethodKind, PatOrExpr, PropName, Stmt, SwitchCase, SwitchStmt, ThrowStmt, TsAsExpr,
+    TsExprWithTypeArgs, TsKeywordTypeKind, TsNonNullExpr, TsType, WhileStmt,
+};
+use swc_ecma_utils::{quote_ident, quote_str};
+
+use super::json::json_param;
+
+// the text format reader and writer shared by every generated file, emitted at the root of the output.
+pub const TEXT_SUPPORT_MODULE: &str = "text_support";
+pub const TEXT_SUPPORT_SOURCE: &str = include_str!("../../js/runtime/text_support.ts");
+
+const ANY_TYPE_NAME: &str = ".google.protobuf.Any";
+
+fn support_type(ctx: &Context, symbol: &str) -> TsType {
+    crate::type_ref!(crate::entity_name_ident!(ctx.lazy_support_ref(TEXT_SUPPORT_MODULE, symbol)))
+}
+
+fn reader_call(method: &str, args: Vec<Expr>) -> Expr {
+    crate::call_expr!(
+        crate::member_expr!("r", method),
+        args.into_iter().map(|arg| crate::expr_or_spread!(arg)).collect()
+    )
+}
+
+fn writer_call(method: &str, args: Vec<Expr>) -> Stmt {
+    crate::expr_stmt!(crate::call_expr!(
+        crate::member_expr!("w", method),
+        args.into_iter().map(|arg| crate::expr_or_spread!(arg)).collect()
+    ))
+}
+
+fn break_stmt() -> Stmt {
+    Stmt::Break(BreakStmt {
+        label: None,
+        span: DUMMY_SP,
+    })
+}
+
+// for (const <name> of <iterable>) { ... }
+fn for_of_stmt(name: &str, iterable: Expr, stmts: Vec<Stmt>) -> Stmt {
+    Stmt::ForOf(ForOfStmt {
+        is_await: false,
+        left: ForHead::VarDecl(Box::new(crate::const_decl_uinit!(name))),
+        right: Box::new(iterable),
+        body: Box::new(crate::block_stmt!(stmts)),
+        span: DUMMY_SP,
+    })
+}
+
+impl FieldDescriptorProto {
+    // w.writeString("name", value)
+    fn text_write_value_stmt(&self, ctx: &mut Context, name: &str, value: Expr) -> Stmt {
+        let name = quote_str!(name).into();
+        match self.type_() {
+            Type::TYPE_MESSAGE => writer_call("writeMessage", vec![name, value]),
+            Type::TYPE_ENUM => writer_call(
+                "writeEnum",
+                vec![
+                    name,
+                    crate::call_expr!(
+                        ctx.lazy_companion_type_ref(self.type_name(), "_toJson").into(),
+                        vec![crate::expr_or_spread!(value)]
+                    ),
+                ],
+            ),
+            Type::TYPE_STRING => writer_call("writeString", vec![name, value]),
+            Type::TYPE_BYTES => writer_call("writeBytes", vec![name, value]),
+            Type::TYPE_BOOL => writer_call("writeBool", vec![name, value]),
+            _ if self.is_bigint() => writer_call("writeBigInt", vec![name, value]),
+            _ => writer_call("writeNumber", vec![name, value]),
+        }
+    }
+
+    // r.readString()
+    fn text_read_value_expr(&self, ctx: &mut Context) -> Expr {
+        match self.type_() {
+            Type::TYPE_MESSAGE => crate::call_expr!(
+                ctx.lazy_support_ref(TEXT_SUPPORT_MODULE, "readMessage").into(),
+                vec![
+                    crate::expr_or_spread!(quote_ident!("r").into()),
+                    crate::expr_or_spread!(crate::new_expr!(ctx.lazy_type_ref(self.type_name()).into())),
+                ]
+            ),
+            // without ignoreUnknownFields an unknown name throws instead of reading as undefined.
+            Type::TYPE_ENUM => Expr::TsNonNull(TsNonNullExpr {
+                span: DUMMY_SP,
+                expr: Box::new(crate::call_expr!(
+                    ctx.lazy_companion_type_ref(self.type_name(), "_fromJson").into(),
+                    vec![crate::expr_or_spread!(Expr::TsAs(TsAsExpr {
+                        span: DUMMY_SP,
+                        expr: Box::new(reader_call("readEnum", vec![])),
+                        type_ann: Box::new(crate::type_ref!(crate::entity_name_ident!(
+                            ctx.lazy_companion_type_ref(self.type_name(), "Json")
+                        ))),
+                    }))]
+                )),
+            }),
+            Type::TYPE_STRING => reader_call("readString", vec![]),
+            Type::TYPE_BYTES if ctx.options.with_sendable => crate::call_expr!(
+                crate::member_expr_bare!(crate::member_expr!("collections", "Uint8Array"), "from"),
+                vec![crate::expr_or_spread!(reader_call("readBytes", vec![]))]
+            ),
+            Type::TYPE_BYTES => reader_call("readBytes", vec![]),
+            Type::TYPE_BOOL => reader_call("readBool", vec![]),
+            Type::TYPE_FLOAT | Type::TYPE_DOUBLE => reader_call("readFloat", vec![]),
+            Type::TYPE_UINT32 | Type::TYPE_FIXED32 => reader_call("readUint32", vec![]),
+            Type::TYPE_UINT64 | Type::TYPE_FIXED64 => reader_call("readUint64", vec![]),
+            _ if self.is_bigint() => reader_call("readInt64", vec![]),
+            _ => reader_call("readInt32", vec![]),
+        }
+    }
+
+    // the separator before the value, optional before messages.
+    fn text_colon_stmt(&self, ctx: &Context) -> Stmt {
+        let method = if self.is_message() || self.unboxed_wrapper(ctx).is_some() {
+            "tryConsume"
+        } else {
+            "consume"
+        };
+        crate::expr_stmt!(reader_call(method, vec![quote_str!(":").into()]))
+    }
+
+    fn print_write_text_stmt(&self, ctx: &mut Context) -> Option<Stmt> {
+        if self.is_group() {
+            return None;
+        }
+        let value = super::field::this_field_member(self);
+
+        let stmts = if self.is_map(ctx) {
+            // for (const entry of this.m.entries()) { w.beginMessage("m"); ...; w.endMessage(); }
+            let descriptor = ctx
+                .get_map_type(self.type_name())
+                .unwrap_or_else(|| panic!("can not find the map type {}", self.type_name()));
+            vec![for_of_stmt(
+                "entry",
+                crate::call_expr!(crate::member_expr_bare!(value, "entries")),
+                vec![
+                    writer_call("beginMessage", vec![quote_str!(self.name()).into()]),
+                    descriptor.field[0].text_write_value_stmt(
+                        ctx,
+                        "key",
+                        crate::member_expr_computed!(quote_ident!("entry").into(), crate::lit_num!(0).into()),
+                    ),
+                    descriptor.field[1].text_write_value_stmt(
+                        ctx,
+                        "value",
+                        crate::member_expr_computed!(quote_ident!("entry").into(), crate::lit_num!(1).into()),
+                    ),
+                    writer_call("endMessage", vec![]),
+                ],
+            )]
+        } else if self.is_repeated() {
+            let write = self.text_write_value_stmt(ctx, self.name(), quote_ident!("value").into());
+            vec![for_of_stmt("value", value, vec![write])]
+        } else if let Some(unboxed) = self.unboxed_wrapper(ctx) {
+            vec![
+                writer_call("beginMessage", vec![quote_str!(self.name()).into()]),
+                unboxed.text_write_value_stmt(ctx, "value", value),
+                writer_call("endMessage", vec![]),
+            ]
+        } else if self.is_plain_struct(ctx) {
+            let message = crate::call_expr!(
+                crate::member_expr!(ctx.lazy_type_ref(self.type_name()), "fromJson"),
+                vec![crate::expr_or_spread!(value)]
+            );
+            vec![writer_call("writeMessage", vec![quote_str!(self.name()).into(), message])]
+        } else {
+            vec![self.text_write_value_stmt(ctx, self.name(), value)]
+        };
+
+        Some(crate::if_stmt!(
+            self.default_value_bin_expr(ctx, super::field::this_field_member),
+            crate::block_stmt!(stmts)
+        ))
+    }
+
+    fn print_merge_text_case(&self, ctx: &mut Context) -> Option<SwitchCase> {
+        if self.is_group() {
+            return None;
+        }
+        let member = PatOrExpr::Expr(Box::new(super::field::this_field_member(self)));
+
+        let read_stmt = if self.is_map(ctx) {
+            // r.readMapEntries(key, value, () => r.readString(), () => ..., (key: K, value: V) => { this.m.set(key, value); })
+            let descriptor = ctx
+                .get_map_type(self.type_name())
+                .unwrap_or_else(|| panic!("can not find the map type {}", self.type_name()));
+            let (key, value) = (&descriptor.field[0], &descriptor.field[1]);
+            let add = crate::arrow_func!(
+                vec![
+                    crate::pat_ident!(quote_ident!("key"), key.type_annotation(ctx);),
+                    crate::pat_ident!(quote_ident!("value"), value.type_annotation(ctx);),
+                ],
+                vec![crate::expr_stmt!(crate::call_expr!(
+                    crate::member_expr_bare!(super::field::this_field_member(self), "set"),
+                    vec![
+                        crate::expr_or_spread!(quote_ident!("key").into()),
+                        crate::expr_or_spread!(quote_ident!("value").into()),
+                    ]
+                ))]
+            );
+            crate::expr_stmt!(reader_call(
+                "readMapEntries",
+                vec![
+                    key.default_value_expr(ctx, true),
+                    value.default_value_expr(ctx, true),
+                    crate::arrow_func_short!(key.text_read_value_expr(ctx)),
+                    crate::arrow_func_short!(value.text_read_value_expr(ctx)),
+                    add,
+                ]
+            ))
+        } else if self.is_repeated() {
+            // for (const value of r.readList(() => r.readString())) { this.f.push(value); }
+            let read = crate::arrow_func_short!(self.text_read_value_expr(ctx));
+            for_of_stmt(
+                "value",
+                reader_call("readList", vec![read]),
+                vec![crate::expr_stmt!(crate::call_expr!(
+                    crate::member_expr_bare!(super::field::this_field_member(self), "push"),
+                    vec![crate::expr_or_spread!(quote_ident!("value").into())]
+                ))],
+            )
+        } else if let Some(mut unboxed) = self.unboxed_wrapper(ctx) {
+            // an absent value reads as the default of the wrapped type.
+            unboxed.set_proto3_optional(false);
+            let read = reader_call(
+                "readWrapper",
+                vec![
+                    unboxed.default_value_expr(ctx, true),
+                    crate::arrow_func_short!(unboxed.text_read_value_expr(ctx)),
+                ],
+            );
+            crate::expr_stmt!(crate::assign_expr!(member, read))
+        } else if self.is_plain_struct(ctx) {
+            let read = crate::call_expr!(crate::member_expr_bare!(self.text_read_value_expr(ctx), "toJson"));
+            crate::expr_stmt!(crate::assign_expr!(member, read))
+        } else if self.is_message() {
+            // merges into the message already set, like the binary format.
+            let read = crate::call_expr!(
+                ctx.lazy_support_ref(TEXT_SUPPORT_MODULE, "readMessage").into(),
+                vec![
+                    crate::expr_or_spread!(quote_ident!("r").into()),
+                    crate::expr_or_spread!(crate::bin_expr!(
+                        super::field::this_field_member(self),
+                        crate::new_expr!(ctx.lazy_type_ref(self.type_name()).into()),
+                        BinaryOp::NullishCoalescing
+                    )),
+                ]
+            );
+            crate::expr_stmt!(crate::assign_expr!(member, read))
+        } else {
+            crate::expr_stmt!(crate::assign_expr!(member, self.text_read_value_expr(ctx)))
+        };
+
+        Some(SwitchCase {
+            span: DUMMY_SP,
+            test: Some(Box::new(quote_str!(self.name()).into())),
+            cons: vec![self.text_colon_stmt(ctx), read_stmt, break_stmt()],
+        })
+    }
+}
+
+impl DescriptorProto {
+    fn is_any(&self, ctx: &Context) -> bool {
+        ctx.calculate_type_name(self.name()) == ANY_TYPE_NAME
+    }
+
+    // implements text_support.TextMessage
+    pub(super) fn text_message_implements(&self, ctx: &Context) -> TsExprWithTypeArgs {
+        TsExprWithTypeArgs {
+            span: DUMMY_SP,
+            expr: Box::new(ctx.lazy_support_ref(TEXT_SUPPORT_MODULE, "TextMessage").into()),
+            type_args: None,
+        }
+    }
+
+    fn text_method(
+        &self,
+        name: &str,
+        is_static: bool,
+        params: Vec<swc_ecma_ast::Param>,
+        stmts: Vec<Stmt>,
+        return_type: TsType,
+    ) -> ClassMember {
+        ClassMember::Method(ClassMethod {
+            span: DUMMY_SP,
+            accessibility: None,
+            key: PropName::Ident(quote_ident!(name)),
+            is_abstract: false,
+            is_optional: false,
+            is_override: false,
+            is_static,
+            function: Box::new(Function {
+                body: Some(BlockStmt {
+                    span: DUMMY_SP,
+                    stmts,
+                }),
+                decorators: vec![],
+                is_async: false,
+                is_generator: false,
+                params,
+                return_type: Some(Box::new(crate::type_annotation!(return_type))),
+                span: DUMMY_SP,
+                type_params: None,
+            }),
+            kind: MethodKind::Method,
+        })
+    }
+
+    // writeText(w: text_support.TextWriter): void
+    fn print_write_text(&self, ctx: &mut Context) -> Vec<ClassMember> {
+        let mut statements = vec![];
+        if self.is_any(ctx) {
+            // if (text_support.writeAny(w, this)) { return; }
+            statements.push(crate::if_stmt!(
+                crate::call_expr!(
+                    ctx.lazy_support_ref(TEXT_SUPPORT_MODULE, "writeAny").into(),
+                    vec![
+                        crate::expr_or_spread!(quote_ident!("w").into()),
+                        crate::expr_or_spread!(quote_ident!("this").into()),
+                    ]
+                ),
+                crate::block_stmt!(vec![Stmt::Return(swc_ecma_ast::ReturnStmt {
+                    span: DUMMY_SP,
+                    arg: None,
+                })])
+            ));
+        }
+
+        let params = vec![json_param("w", support_type(ctx, "TextWriter"))];
+        let (mut field_stmts, mut members) =
+            self.print_method_chunks(ctx, "writeText", false, params.clone(), |ctx, fields| {
+                fields
+                    .iter()
+                    .filter_map(|field| field.print_write_text_stmt(ctx))
+                    .collect()
+            });
+        statements.append(&mut field_stmts);
+
+        members.push(self.text_method(
+            "writeText",
+            false,
+            params,
+            statements,
+            crate::keyword_type!(TsKeywordTypeKind::TsVoidKeyword),
+        ));
+        members
+    }
+
+    // mergeText(r: text_support.TextReader): void
+    // reads fields until the end of the enclosing message or of the text.
+    fn print_merge_text(&self, ctx: &mut Context) -> Vec<ClassMember> {
+        let params = vec![json_param("r", support_type(ctx, "TextReader"))];
+        let mut members = vec![];
+
+        let chunks = self.method_field_chunks(ctx);
+        let mut cases: Vec<SwitchCase> = vec![];
+        let mut handled: Vec<Expr> = vec![];
+        if chunks.len() <= 1 {
+            for field in chunks.into_iter().flatten() {
+                cases.extend(field.print_merge_text_case(ctx));
+            }
+        } else {
+            // private mergeText_N(r, name: string): boolean methods, like mergeFrom_N.
+            for (index, fields) in chunks.into_iter().enumerate() {
+                let chunk_cases = fields
+                    .iter()
+                    .filter_map(|field| field.print_merge_text_case(ctx))
+                    .map(|mut case| {
+                        if let Some(last @ Stmt::Break(_)) = case.cons.last_mut() {
+                            *last = crate::return_stmt!(crate::lit_bool!(true).into());
+                        }
+                        case
+                    })
+                    .collect();
+                let helper = format!("mergeText_{}", index);
+                let mut helper_params = params.clone();
+                helper_params.push(json_param("name", crate::keyword_type!(TsKeywordTypeKind::TsStringKeyword)));
+                members.push(self.print_private_method(
+                    helper.clone(),
+                    false,
+                    helper_params,
+                    vec![
+                        Stmt::Switch(SwitchStmt {
+                            span: DUMMY_SP,
+                            discriminant: Box::new(quote_ident!("name").into()),
+                            cases: chunk_cases,
+                        }),
+                        crate::return_stmt!(crate::lit_bool!(false).into()),
+                    ],
+                    Some(crate::keyword_type!(TsKeywordTypeKind::TsBooleanKeyword)),
+                ));
+                handled.push(crate::call_expr!(
+                    crate::member_expr!("this", helper),
+                    vec![
+                        crate::expr_or_spread!(quote_ident!("r").into()),
+                        crate::expr_or_spread!(quote_ident!("name").into()),
+                    ]
+                ));
+            }
+        }
+
+        // throw r.error("unknown field " + name + " of pkg.Msg")
+        let mut unknown = Stmt::Throw(ThrowStmt {
+            span: DUMMY_SP,
+            arg: Box::new(reader_call(
+                "error",
+                vec![crate::bin_expr!(
+                    crate::bin_expr!(
+                        quote_str!("unknown field ").into(),
+                        quote_ident!("name").into(),
+                        BinaryOp::Add
+                    ),
+                    quote_str!(format!(
+                        " of {}",
+                        ctx.calculate_type_name(self.name()).trim_start_matches('.')
+                    ))
+                    .into(),
+                    BinaryOp::Add
+                )],
+            )),
+        });
+        if self.is_any(ctx) {
+            // [type.googleapis.com/pkg.Msg] { ... }
+            unknown = crate::if_stmt!(
+                crate::call_expr!(
+                    crate::member_expr!("name", "startsWith"),
+                    vec![crate::expr_or_spread!(quote_str!("[").into())]
+                ),
+                crate::expr_stmt!(crate::call_expr!(
+                    ctx.lazy_support_ref(TEXT_SUPPORT_MODULE, "readAny").into(),
+                    vec![
+                        crate::expr_or_spread!(quote_ident!("r").into()),
+                        crate::expr_or_spread!(quote_ident!("name").into()),
+                        crate::expr_or_spread!(quote_ident!("this").into()),
+                    ]
+                )),
+                unknown
+            );
+        }
+        if let Some(known) = handled
+            .into_iter()
+            .reduce(|left, right| crate::bin_expr!(left, right, BinaryOp::LogicalOr))
+        {
+            unknown = crate::if_stmt!(crate::unary_expr!(crate::paren_expr!(known)), unknown);
+        }
+        cases.push(SwitchCase {
+            span: DUMMY_SP,
+            test: None,
+            cons: vec![unknown],
+        });
+
+        let body = vec![
+            Stmt::Decl(crate::const_decl!("name: string", reader_call("readFieldName", vec![]))),
+            Stmt::Switch(SwitchStmt {
+                span: DUMMY_SP,
+                discriminant: Box::new(quote_ident!("name").into()),
+                cases,
+            }),
+        ];
+        let statements = vec![Stmt::While(WhileStmt {
+            span: DUMMY_SP,
+            test: Box::new(crate::unary_expr!(reader_call("endMessage", vec![]))),
+            body: Box::new(crate::block_stmt!(body)),
+        })];
+
+        members.push(self.text_method(
+            "mergeText",
+            false,
+            params,
+            statements,
+            crate::keyword_type!(TsKeywordTypeKind::TsVoidKeyword),
+        ));
+        members
+    }
+
+    // toText(): string
+    // static fromText(text: string): Msg
+    pub(super) fn print_text_members(&self, ctx: &mut Context) -> Vec<ClassMember> {
+        let class_name = ctx.normalize_name(self.name());
+        let writer = ctx.lazy_support_ref(TEXT_SUPPORT_MODULE, "TextWriter");
+        let reader = ctx.lazy_support_ref(TEXT_SUPPORT_MODULE, "TextReader");
+
+        let to_text = vec![
+            Stmt::Decl(crate::const_decl!(
+                format!("w: {}", writer.sym),
+                crate::new_expr!(writer.clone().into())
+            )),
+            crate::expr_stmt!(crate::call_expr!(
+                crate::member_expr!("this", "writeText"),
+                vec![crate::expr_or_spread!(quote_ident!("w").into())]
+            )),
+            crate::return_stmt!(crate::call_expr!(crate::member_expr!("w", "toString"))),
+        ];
+        let from_text = vec![
+            Stmt::Decl(crate::const_decl!(
+                format!("r: {}", reader.sym),
+                crate::new_expr!(
+                    reader.clone().into(),
+                    vec![crate::expr_or_spread!(quote_ident!("text").into())]
+                )
+            )),
+            Stmt::Decl(crate::const_decl!(
+                format!("message: {}", class_name),
+                crate::new_expr!(quote_ident!(class_name.clone()).into())
+            )),
+            crate::expr_stmt!(crate::call_expr!(
+                crate::member_expr!("message", "mergeText"),
+                vec![crate::expr_or_spread!(quote_ident!("r").into())]
+            )),
+            crate::return_stmt!(quote_ident!("message").into()),
+        ];
+
+        let mut members = self.print_write_text(ctx);
+        members.append(&mut self.print_merge_text(ctx));
+        members.push(self.text_method(
+            "toText",
+            false,
+            vec![],
+            to_text,
+            crate::keyword_type!(TsKeywordTypeKind::TsStringKeyword),
+        ));
+        members.push(self.text_method(
+            "fromText",
+            true,
+            vec![json_param("text", crate::keyword_type!(TsKeywordTypeKind::TsStringKeyword))],
+            from_text,
+            crate::type_ref!(crate::entity_name_ident!(quote_ident!(class_name))),
+        ));
+        members
+    }
+}
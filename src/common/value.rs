@@ -0,0 +1,546 @@
+use crate::context::Context;
+use crate::descriptor::{DescriptorProto, FieldDescriptorProto};
+use swc_common::DUMMY_SP;
+use swc_ecma_ast::{
+    BinaryOp, BindingIdent, BlockStmt, ClassMember, ClassMethod, Expr, ForHead, ForOfStmt,
+    ForStmt, Function, MethodKind, Param, Pat, PatOrExpr, PropName, Stmt, TsKeywordTypeKind,
+    UpdateExpr, UpdateOp, VarDeclOrExpr,
+};
+use swc_ecma_utils::quote_ident;
+
+impl FieldDescriptorProto {
+    // the descriptor used to compare and copy single values of this field.
+    fn value_descriptor(&self, ctx: &Context) -> FieldDescriptorProto {
+        if self.is_map(ctx) {
+            let descriptor = ctx
+                .get_map_type(self.type_name())
+                .unwrap_or_else(|| panic!("can not find the map type {}", self.type_name()));
+            return descriptor.field[1].clone();
+        }
+        self.unboxed_wrapper(ctx).unwrap_or(self.clone())
+    }
+
+    // whether values of this field can be compared and copied with plain assignment.
+    fn is_plain_value(&self, ctx: &Context) -> bool {
+        !self.is_bytes() && !self.is_message() && !self.is_plain_struct(ctx)
+    }
+
+    // an expression that is true when two values of this field differ. `plain` tells whether the
+    // field holds a plain struct, the values of a map never do.
+    fn value_not_equals_expr(&self, ctx: &mut Context, plain: bool, left: Expr, right: Expr) -> Expr {
+        if plain {
+            let type_ref = ctx.lazy_type_ref(self.type_name());
+            crate::unary_expr!(crate::call_expr!(
+                crate::member_expr_bare!(
+                    crate::call_expr!(
+                        crate::member_expr!(type_ref.clone(), "fromJson"),
+                        vec![crate::expr_or_spread!(left)]
+                    ),
+                    "equals"
+                ),
+                vec![crate::expr_or_spread!(crate::call_expr!(
+                    crate::member_expr!(type_ref, "fromJson"),
+                    vec![crate::expr_or_spread!(right)]
+                ))]
+            ))
+        } else if self.is_message() {
+            crate::unary_expr!(crate::call_expr!(
+                crate::member_expr_bare!(left, "equals"),
+                vec![crate::expr_or_spread!(right)]
+            ))
+        } else if self.is_float() {
+            // NaN differs from itself, a message holding it still equals its clone.
+            crate::bin_expr!(
+                crate::bin_expr!(left.clone(), right.clone(), BinaryOp::NotEqEq),
+                crate::unary_expr!(crate::paren_expr!(crate::bin_expr!(
+                    crate::call_expr!(
+                        crate::member_expr!("Number", "isNaN"),
+                        vec![crate::expr_or_spread!(left)]
+                    ),
+                    crate::call_expr!(
+                        crate::member_expr!("Number", "isNaN"),
+                        vec![crate::expr_or_spread!(right)]
+                    ),
+                    BinaryOp::LogicalAnd
+                ))),
+                BinaryOp::LogicalAnd
+            )
+        } else {
+            crate::bin_expr!(left, right, BinaryOp::NotEqEq)
+        }
+    }
+
+    // bytes are compared through locals, so the narrowing of undefined holds inside the loop.
+    // if (value.length !== otherValue.length) { return false; }
+    // for (let j: number = 0; j < otherValue.length; j++) { if (value[j] !== otherValue[j]) { return false; } }
+    fn bytes_equals_stmts(&self) -> Vec<Stmt> {
+        let value: Expr = quote_ident!("value").into();
+        let other: Expr = quote_ident!("otherValue").into();
+        let index: Expr = quote_ident!("j").into();
+        let return_false =
+            || crate::block_stmt!(vec![crate::return_stmt!(crate::lit_bool!(false).into())]);
+        vec![
+            crate::if_stmt!(
+                crate::bin_expr!(
+                    crate::member_expr_bare!(value.clone(), "length"),
+                    crate::member_expr_bare!(other.clone(), "length"),
+                    BinaryOp::NotEqEq
+                ),
+                return_false()
+            ),
+            Stmt::For(ForStmt {
+                span: DUMMY_SP,
+                init: Some(VarDeclOrExpr::Expr(Box::new(Expr::Ident(quote_ident!(
+                    "let j: number = 0"
+                ))))),
+                test: Some(Box::new(crate::bin_expr!(
+                    index.clone(),
+                    crate::member_expr_bare!(other.clone(), "length"),
+                    BinaryOp::Lt
+                ))),
+                update: Some(Box::new(Expr::Update(UpdateExpr {
+                    span: DUMMY_SP,
+                    op: UpdateOp::PlusPlus,
+                    prefix: false,
+                    arg: Box::new(index.clone()),
+                }))),
+                body: Box::new(crate::block_stmt!(vec![crate::if_stmt!(
+                    crate::bin_expr!(
+                        crate::member_expr_computed!(value, index.clone()),
+                        crate::member_expr_computed!(other, index),
+                        BinaryOp::NotEqEq
+                    ),
+                    return_false()
+                )])),
+            }),
+        ]
+    }
+
+    // a deep copy of a single value of this field.
+    fn value_clone_expr(&self, ctx: &mut Context, plain: bool, value: Expr) -> Expr {
+        if plain {
+            let type_ref = ctx.lazy_type_ref(self.type_name());
+            crate::call_expr!(crate::member_expr_bare!(
+                crate::call_expr!(
+                    crate::member_expr!(type_ref, "fromJson"),
+                    vec![crate::expr_or_spread!(value)]
+                ),
+                "toJson"
+            ))
+        } else if self.is_message() {
+            crate::call_expr!(crate::member_expr_bare!(value, "clone"))
+        } else if self.is_bytes() {
+            crate::call_expr!(crate::member_expr_bare!(value, "slice"))
+        } else {
+            value
+        }
+    }
+
+    // for (let entry of src.m.entries()) {
+    //     let key = entry[0];
+    //     let value = entry[1];
+    //     ...
+    // }
+    fn for_each_map_entry_stmt(&self, map: Expr, mut body: Vec<Stmt>) -> Stmt {
+        let mut stmts = vec![
+            crate::expr_stmt!(Expr::Ident(quote_ident!("let key = entry[0]"))),
+            crate::expr_stmt!(Expr::Ident(quote_ident!("let value = entry[1]"))),
+        ];
+        stmts.append(&mut body);
+        Stmt::ForOf(ForOfStmt {
+            is_await: false,
+            left: ForHead::Pat(Box::new(crate::pat_ident!(quote_ident!("let entry")))),
+            right: Box::new(crate::member_expr_bare!(map, "entries()")),
+            body: Box::new(crate::block_stmt!(stmts)),
+            span: DUMMY_SP,
+        })
+    }
+
+    // for (const value of src.r) {
+    //     ...
+    // }
+    fn for_each_value_stmt(&self, array: Expr, body: Vec<Stmt>) -> Stmt {
+        Stmt::ForOf(ForOfStmt {
+            is_await: false,
+            left: ForHead::VarDecl(Box::new(crate::const_decl_uinit!("value"))),
+            right: Box::new(array),
+            body: Box::new(crate::block_stmt!(body)),
+            span: DUMMY_SP,
+        })
+    }
+
+    fn print_equals_stmts(&self, ctx: &mut Context) -> Vec<Stmt> {
+        let this_field = crate::member_expr!("this", self.name());
+        let other_field = crate::member_expr!("other", self.name());
+        let value = self.value_descriptor(ctx);
+        let plain = self.is_plain_struct(ctx);
+        let return_false =
+            || crate::block_stmt!(vec![crate::return_stmt!(crate::lit_bool!(false).into())]);
+        let undefined_check = |expr: Expr| {
+            crate::bin_expr!(expr, quote_ident!("undefined").into(), BinaryOp::EqEqEq)
+        };
+
+        if self.is_map(ctx) {
+            let mut stmts = vec![
+                Stmt::Decl(crate::const_decl!(
+                    "otherValue",
+                    crate::call_expr!(
+                        crate::member_expr_bare!(other_field.clone(), "get"),
+                        vec![crate::expr_or_spread!(quote_ident!("key").into())]
+                    )
+                )),
+            ];
+            if value.is_bytes() {
+                stmts.push(crate::if_stmt!(
+                    undefined_check(quote_ident!("otherValue").into()),
+                    return_false()
+                ));
+                stmts.append(&mut value.bytes_equals_stmts());
+            } else {
+                let not_equals = value.value_not_equals_expr(
+                    ctx,
+                    plain,
+                    quote_ident!("value").into(),
+                    quote_ident!("otherValue").into(),
+                );
+                stmts.push(crate::if_stmt!(
+                    crate::bin_expr!(
+                        undefined_check(quote_ident!("otherValue").into()),
+                        not_equals,
+                        BinaryOp::LogicalOr
+                    ),
+                    return_false()
+                ));
+            }
+            return vec![
+                crate::if_stmt!(
+                    crate::bin_expr!(
+                        crate::member_expr_bare!(this_field.clone(), "size"),
+                        crate::member_expr_bare!(other_field, "size"),
+                        BinaryOp::NotEqEq
+                    ),
+                    return_false()
+                ),
+                self.for_each_map_entry_stmt(this_field, stmts),
+            ];
+        }
+
+        if self.is_repeated() {
+            let index: Expr = quote_ident!("i").into();
+            let this_value = crate::member_expr_computed!(this_field.clone(), index.clone());
+            let other_value = crate::member_expr_computed!(other_field.clone(), index.clone());
+            let body = if value.is_bytes() {
+                let mut stmts = vec![
+                    Stmt::Decl(crate::const_decl!("value", this_value)),
+                    Stmt::Decl(crate::const_decl!("otherValue", other_value)),
+                ];
+                stmts.append(&mut value.bytes_equals_stmts());
+                stmts
+            } else {
+                vec![crate::if_stmt!(
+                    value.value_not_equals_expr(ctx, plain, this_value, other_value),
+                    return_false()
+                )]
+            };
+            return vec![
+                crate::if_stmt!(
+                    crate::bin_expr!(
+                        crate::member_expr_bare!(this_field.clone(), "length"),
+                        crate::member_expr_bare!(other_field, "length"),
+                        BinaryOp::NotEqEq
+                    ),
+                    return_false()
+                ),
+                // for (let i = 0; i < this.r.length; i++)
+                Stmt::For(ForStmt {
+                    span: DUMMY_SP,
+                    init: Some(VarDeclOrExpr::Expr(Box::new(Expr::Ident(quote_ident!(
+                        "let i: number = 0"
+                    ))))),
+                    test: Some(Box::new(crate::bin_expr!(
+                        index.clone(),
+                        crate::member_expr_bare!(this_field, "length"),
+                        BinaryOp::Lt
+                    ))),
+                    update: Some(Box::new(Expr::Update(UpdateExpr {
+                        span: DUMMY_SP,
+                        op: UpdateOp::PlusPlus,
+                        prefix: false,
+                        arg: Box::new(index),
+                    }))),
+                    body: Box::new(crate::block_stmt!(body)),
+                }),
+            ];
+        }
+
+        if value.is_plain_value(ctx) {
+            return vec![crate::if_stmt!(
+                value.value_not_equals_expr(ctx, plain, this_field, other_field),
+                return_false()
+            )];
+        }
+
+        if value.is_bytes() {
+            // if (this.b !== other.b) { const value = this.b; const otherValue = other.b; ... }
+            let mut stmts = vec![
+                Stmt::Decl(crate::const_decl!("value", this_field.clone())),
+                Stmt::Decl(crate::const_decl!("otherValue", other_field.clone())),
+                crate::if_stmt!(
+                    crate::chain_bin_exprs_or!(
+                        undefined_check(quote_ident!("value").into()),
+                        undefined_check(quote_ident!("otherValue").into())
+                    ),
+                    return_false()
+                ),
+            ];
+            stmts.append(&mut value.bytes_equals_stmts());
+            return vec![crate::if_stmt!(
+                crate::bin_expr!(this_field, other_field, BinaryOp::NotEqEq),
+                crate::block_stmt!(stmts)
+            )];
+        }
+
+        // values are only compared when both sides are set.
+        let not_equals = value.value_not_equals_expr(ctx, plain, this_field.clone(), other_field.clone());
+        vec![crate::if_stmt!(
+            crate::bin_expr!(
+                crate::bin_expr!(this_field.clone(), other_field.clone(), BinaryOp::NotEqEq),
+                crate::paren_expr!(crate::chain_bin_exprs_or!(
+                    undefined_check(this_field),
+                    undefined_check(other_field),
+                    not_equals
+                ))
+            ),
+            return_false()
+        )]
+    }
+
+    fn print_clone_stmts(&self, ctx: &mut Context) -> Vec<Stmt> {
+        let value = self.value_descriptor(ctx);
+        let plain = self.is_plain_struct(ctx);
+
+        if self.is_map(ctx) {
+            let cloned = value.value_clone_expr(ctx, plain, quote_ident!("value").into());
+            return vec![self.for_each_map_entry_stmt(
+                crate::member_expr!("this", self.name()),
+                vec![crate::expr_stmt!(crate::call_expr!(
+                    crate::member_expr!("m", format!("{}.set", self.name())),
+                    vec![
+                        crate::expr_or_spread!(quote_ident!("key").into()),
+                        crate::expr_or_spread!(cloned),
+                    ]
+                ))],
+            )];
+        }
+
+        if self.is_repeated() {
+            let cloned = value.value_clone_expr(ctx, plain, quote_ident!("value").into());
+            return vec![self.for_each_value_stmt(
+                crate::member_expr!("this", self.name()),
+                vec![crate::expr_stmt!(crate::call_expr!(
+                    crate::member_expr!("m", format!("{}.push", self.name())),
+                    vec![crate::expr_or_spread!(cloned)]
+                ))],
+            )];
+        }
+
+        // oneof members are copied through their backing property to keep the case intact.
+        let this_field = crate::member_expr!("this", self.prop_name());
+        let cloned = if value.is_plain_value(ctx) {
+            this_field
+        } else {
+            crate::cond_expr!(
+                crate::bin_expr!(
+                    this_field.clone(),
+                    quote_ident!("undefined").into(),
+                    BinaryOp::EqEqEq
+                ),
+                quote_ident!("undefined").into(),
+                value.value_clone_expr(ctx, plain, this_field)
+            )
+        };
+        vec![crate::expr_stmt!(crate::assign_expr!(
+            PatOrExpr::Expr(Box::new(crate::member_expr!("m", self.prop_name()))),
+            cloned
+        ))]
+    }
+
+    fn print_merge_from_message_stmts(&self, ctx: &mut Context) -> Vec<Stmt> {
+        let this_field = crate::member_expr!("this", self.name());
+        let other_field = crate::member_expr!("other", self.name());
+        let value = self.value_descriptor(ctx);
+        let plain = self.is_plain_struct(ctx);
+
+        if self.is_map(ctx) {
+            // map entries replace the existing value per key.
+            let cloned = value.value_clone_expr(ctx, plain, quote_ident!("value").into());
+            return vec![self.for_each_map_entry_stmt(
+                other_field,
+                vec![crate::expr_stmt!(crate::call_expr!(
+                    crate::member_expr_bare!(this_field, "set"),
+                    vec![
+                        crate::expr_or_spread!(quote_ident!("key").into()),
+                        crate::expr_or_spread!(cloned),
+                    ]
+                ))],
+            )];
+        }
+
+        if self.is_repeated() {
+            // repeated values are appended, from a copy since other may be this.
+            let cloned = value.value_clone_expr(ctx, plain, quote_ident!("value").into());
+            return vec![self.for_each_value_stmt(
+                crate::call_expr!(crate::member_expr_bare!(other_field, "slice")),
+                vec![crate::expr_stmt!(crate::call_expr!(
+                    crate::member_expr_bare!(this_field, "push"),
+                    vec![crate::expr_or_spread!(cloned)]
+                ))],
+            )];
+        }
+
+        if value.is_message() && !plain {
+            // set messages are merged recursively.
+            return vec![crate::if_stmt!(
+                crate::bin_expr!(
+                    other_field.clone(),
+                    quote_ident!("undefined").into(),
+                    BinaryOp::NotEqEq
+                ),
+                crate::block_stmt!(vec![crate::if_stmt!(
+                    crate::bin_expr!(
+                        this_field.clone(),
+                        quote_ident!("undefined").into(),
+                        BinaryOp::EqEqEq
+                    ),
+                    crate::block_stmt!(vec![crate::expr_stmt!(crate::assign_expr!(
+                        PatOrExpr::Expr(Box::new(this_field.clone())),
+                        crate::call_expr!(crate::member_expr_bare!(other_field.clone(), "clone"))
+                    ))]),
+                    crate::block_stmt!(vec![crate::expr_stmt!(crate::call_expr!(
+                        crate::member_expr_bare!(this_field, "mergeFromMessage"),
+                        vec![crate::expr_or_spread!(other_field)]
+                    ))])
+                )])
+            )];
+        }
+
+        // scalars overwrite when they are present on the wire, a set wrapper is present even if it wraps the default.
+        let present = if self.unboxed_wrapper(ctx).is_some() { self } else { &value };
+        let presence = present.default_value_bin_expr(ctx, |field| {
+            crate::member_expr!("other", field.name())
+        });
+        let cloned = value.value_clone_expr(ctx, plain, other_field);
+        vec![crate::if_stmt!(
+            presence,
+            crate::block_stmt!(vec![crate::expr_stmt!(crate::assign_expr!(
+                PatOrExpr::Expr(Box::new(this_field)),
+                cloned
+            ))])
+        )]
+    }
+}
+
+fn value_method(
+    name: &str,
+    params: Vec<Param>,
+    stmts: Vec<Stmt>,
+    return_type: swc_ecma_ast::TsType,
+) -> ClassMember {
+    ClassMember::Method(ClassMethod {
+        span: DUMMY_SP,
+        accessibility: None,
+        key: PropName::Ident(quote_ident!(name)),
+        is_abstract: false,
+        is_optional: false,
+        is_override: false,
+        is_static: false,
+        function: Box::new(Function {
+            body: Some(BlockStmt {
+                span: DUMMY_SP,
+                stmts,
+            }),
+            decorators: vec![],
+            is_async: false,
+            is_generator: false,
+            params,
+            return_type: Some(Box::new(crate::type_annotation!(return_type))),
+            span: DUMMY_SP,
+            type_params: None,
+        }),
+        kind: MethodKind::Method,
+    })
+}
+
+impl DescriptorProto {
+    fn other_param(&self, ctx: &Context) -> Param {
+        Param {
+            span: DUMMY_SP,
+            decorators: vec![],
+            pat: Pat::Ident(BindingIdent {
+                id: quote_ident!("other"),
+                type_ann: Some(Box::new(crate::type_annotation!(self.class_type(ctx)))),
+            }),
+        }
+    }
+
+    fn class_type(&self, ctx: &Context) -> swc_ecma_ast::TsType {
+        crate::type_ref!(crate::entity_name_ident!(quote_ident!(
+            ctx.normalize_name(self.name())
+        )))
+    }
+
+    // equals(other: Msg): boolean
+    fn print_equals(&self, ctx: &mut Context) -> ClassMember {
+        let mut stmts = vec![];
+        for field in &self.field {
+            stmts.append(&mut field.print_equals_stmts(ctx));
+        }
+        stmts.push(crate::return_stmt!(crate::lit_bool!(true).into()));
+
+        value_method(
+            "equals",
+            vec![self.other_param(ctx)],
+            stmts,
+            crate::keyword_type!(TsKeywordTypeKind::TsBooleanKeyword),
+        )
+    }
+
+    // clone(): Msg
+    fn print_clone(&self, ctx: &mut Context) -> ClassMember {
+        let class_name = ctx.normalize_name(self.name());
+        let mut stmts = vec![Stmt::Decl(crate::const_decl!(
+            format!("m: {}", class_name),
+            crate::new_expr!(quote_ident!(class_name.clone()).into())
+        ))];
+        for field in &self.field {
+            stmts.append(&mut field.print_clone_stmts(ctx));
+        }
+        stmts.push(crate::return_stmt!(quote_ident!("m").into()));
+
+        value_method("clone", vec![], stmts, self.class_type(ctx))
+    }
+
+    // mergeFromMessage(other: Msg): Msg
+    fn print_merge_from_message(&self, ctx: &mut Context) -> ClassMember {
+        let mut stmts = vec![];
+        for field in &self.field {
+            stmts.append(&mut field.print_merge_from_message_stmts(ctx));
+        }
+        stmts.push(crate::return_stmt!(quote_ident!("this").into()));
+
+        value_method(
+            "mergeFromMessage",
+            vec![self.other_param(ctx)],
+            stmts,
+            self.class_type(ctx),
+        )
+    }
+
+    pub(super) fn print_value_members(&self, ctx: &mut Context) -> Vec<ClassMember> {
+        vec![
+            self.print_equals(ctx),
+            self.print_clone(ctx),
+            self.print_merge_from_message(ctx),
+        ]
+    }
+}
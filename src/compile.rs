@@ -13,7 +13,9 @@ use std::string::*;
 use std::sync::{Arc, Mutex};
 use std::thread;
 
+use crate::common::{binary, delimited, field_mask, json, reflection, sized, text};
 use crate::context::{Context, Syntax};
+use crate::descriptor::FileDescriptorProto;
 use crate::emit::emit;
 use crate::mapper::Mapper;
 use crate::options::Options;
@@ -21,10 +23,47 @@ use crate::plugin::{code_generator_response::File, CodeGeneratorRequest, CodeGen
 use crate::runtime::google_protobuf::GooglePBRuntime;
 use crate::runtime::grpc_web::GrpcWebRuntime;
 
+/// adds a file shipped with the protobuf crate to the request unless protoc already sent it.
+fn require_bundled_file(
+    request: &mut CodeGeneratorRequest,
+    name: &str,
+    bundled: &protobuf::reflect::FileDescriptor,
+) {
+    if request.proto_file.iter().any(|file| file.name() == name) {
+        return;
+    }
+    let bytes = bundled
+        .proto()
+        .write_to_bytes()
+        .expect("can not serialize the bundled file");
+    let mut descriptor = FileDescriptorProto::parse_from_bytes(&bytes)
+        .expect("can not parse the bundled file");
+    descriptor.set_name(name.to_string());
+    request.proto_file.push(descriptor);
+    request.file_to_generate.push(name.to_string());
+}
+
 pub fn compile(buffer: Vec<u8>) -> Vec<u8> {
-    let request = CodeGeneratorRequest::parse_from_bytes(&buffer).unwrap();
+    let mut request = CodeGeneratorRequest::parse_from_bytes(&buffer).unwrap();
 
     let options: Options = Options::parse(request.parameter());
+    // reflection refers to descriptor.proto types even if no file imports it.
+    if options.with_descriptor {
+        require_bundled_file(
+            &mut request,
+            reflection::DESCRIPTOR_PROTO,
+            protobuf::descriptor::file_descriptor(),
+        );
+    }
+    // field mask helpers refer to FieldMask even if no file imports it.
+    if options.with_field_mask {
+        require_bundled_file(
+            &mut request,
+            field_mask::FIELD_MASK_PROTO,
+            protobuf::well_known_types::field_mask::file_descriptor(),
+        );
+    }
+
     let mut ctx = Context::new(&options, &Syntax::Unspecified);
     // walk the descriptor recursively to make a map of what symbols are exported by proto files.
     request.map(&mut ctx);
@@ -34,7 +73,7 @@ pub fn compile(buffer: Vec<u8>) -> Vec<u8> {
     let outputs = Arc::new(Mutex::new(vec![]));
 
     thread::scope(|_s| {
-        for descriptor in request.proto_file.to_vec() {
+        for (index, descriptor) in request.proto_file.to_vec().into_iter().enumerate() {
             if !request
                 .file_to_generate
                 .contains(&descriptor.name().to_string())
@@ -42,7 +81,8 @@ pub fn compile(buffer: Vec<u8>) -> Vec<u8> {
                 continue;
             }
 
-            if descriptor.name().contains("descriptor.proto") {
+            // well-known types are provided by a prebuilt package.
+            if !options.wkt_package.is_empty() && ctx.is_well_known_file(descriptor.name()) {
                 continue;
             }
 
@@ -65,7 +105,7 @@ pub fn compile(buffer: Vec<u8>) -> Vec<u8> {
                 let mut file = File::new();
                 file.set_name(descriptor.name().replace(".proto", ".ets"));
                 file.set_content(ts);
-                outputs.lock().unwrap().push(file)
+                outputs.lock().unwrap().push((index, file))
             };
 
             #[cfg(not(target_family = "wasm"))]
@@ -76,8 +116,29 @@ pub fn compile(buffer: Vec<u8>) -> Vec<u8> {
         }
     });
 
+    // files are generated concurrently, restore the order of the request.
+    let mut outputs = outputs.lock().unwrap().to_vec();
+    outputs.sort_by_key(|(index, _)| *index);
+
     let mut response = CodeGeneratorResponse::new();
-    response.file = outputs.lock().unwrap().to_vec();
+    response.file = outputs.into_iter().map(|(_, file)| file).collect();
+
+    // the support modules are imported by the generated files, not by a proto file, and only
+    // emitted when one of them does.
+    for (module, source) in [
+        (json::JSON_SUPPORT_MODULE, json::JSON_SUPPORT_SOURCE),
+        (binary::BINARY_SUPPORT_MODULE, binary::BINARY_SUPPORT_SOURCE),
+        (delimited::DELIMITED_SUPPORT_MODULE, delimited::DELIMITED_SUPPORT_SOURCE),
+        (text::TEXT_SUPPORT_MODULE, text::TEXT_SUPPORT_SOURCE),
+        (sized::SIZED_SUPPORT_MODULE, sized::SIZED_SUPPORT_SOURCE),
+    ] {
+        if ctx.uses_support(module) {
+            let mut support = File::new();
+            support.set_name(format!("{}.ets", module));
+            support.set_content(source.to_string());
+            response.file.push(support);
+        }
+    }
     response.supported_features = Some(code_generator_response::Feature::FEATURE_PROTO3_OPTIONAL as u64);
 
     response.write_to_bytes().unwrap()
@@ -4,10 +4,7 @@ use pathdiff::diff_paths;
 use std::{
     path::PathBuf,
     str::FromStr,
-    sync::{
-        atomic::{AtomicU64, Ordering},
-        Arc, Mutex,
-    },
+    sync::{Arc, Mutex},
 };
 use swc_common::DUMMY_SP;
 use swc_ecma_ast::{
@@ -60,17 +57,55 @@ fn resolve_relative(from: PathBuf, to: PathBuf) -> PathBuf {
     root.join(from.file_name().expect("expected path to have filename"))
 }
 
+pub(crate) const RESERVED_WORDS: &[&str] = &[
+    "break", "case", "catch", "class", "const", "continue", "debugger", "default", "delete", "do",
+    "else", "enum", "export", "extends", "false", "finally", "for", "function", "if", "import",
+    "in", "instanceof", "new", "null", "return", "super", "switch", "this", "throw", "true", "try",
+    "typeof", "var", "void", "while", "with", "yield", "let", "static", "implements", "interface",
+    "package", "private", "protected", "public", "await",
+];
+
+/// Locals of the named imports of the runtime packages, which namespace imports must not shadow.
+/// Reserved up front as they may be imported after the namespaces.
+const RUNTIME_NAMED_IMPORTS: &[&str] = &[
+    "collections", "BinaryReader", "BinaryWriter", "toUint8Array", "fromUint8Array",
+];
+
+/// Derives an identifier from a proto path or module specifier,
+/// e.g. `foo/bar.proto` becomes `foo_bar` and `js-base64` becomes `js_base64`.
+pub(crate) fn import_alias(hint: &str) -> String {
+    let hint = hint.strip_suffix(".proto").unwrap_or(hint);
+    let mut alias = String::new();
+    for c in hint.chars() {
+        if c.is_ascii_alphanumeric() {
+            alias.push(c);
+        } else if !alias.is_empty() && !alias.ends_with('_') {
+            alias.push('_');
+        }
+    }
+    let mut alias = alias.trim_end_matches('_').to_string();
+    if alias.is_empty() || alias.starts_with(|c: char| c.is_ascii_digit()) {
+        alias.insert(0, '_');
+    }
+    if RESERVED_WORDS.contains(&alias.as_str()) {
+        alias.push('_');
+    }
+    alias
+}
+
 pub struct Context<'a> {
     pub options: &'a Options,
     pub syntax: &'a Syntax,
     namespace: Vec<String>,
     name: String,
-    counter: Arc<AtomicU64>,
     imports: Arc<Mutex<Vec<ImportDecl>>>,
-    import_identifier_map: Arc<DashMap<String, u64>>,
+    import_identifier_map: Arc<DashMap<String, String>>,
     type_reg: Arc<DashMap<String, String>>,
     map_type_reg: Arc<DashMap<String, descriptor::DescriptorProto>>,
-    leading_enum_member_reg: Arc<DashMap<String, i32>>,
+    file_reg: Arc<DashMap<String, descriptor::FileDescriptorProto>>,
+    enum_type_reg: Arc<DashMap<String, descriptor::EnumDescriptorProto>>,
+    // the support modules imported by any of the generated files, only those are emitted.
+    support_reg: Arc<DashMap<String, ()>>,
 }
 
 impl<'a> Clone for Context<'a> {
@@ -80,12 +115,13 @@ impl<'a> Clone for Context<'a> {
             syntax: self.syntax,
             namespace: self.namespace.clone(),
             name: self.name.clone(),
-            counter: self.counter.clone(),
             imports: Arc::new(Mutex::new(Vec::new())),
             import_identifier_map: Arc::new(DashMap::new()),
             type_reg: Arc::clone(&self.type_reg),
             map_type_reg: Arc::clone(&self.map_type_reg),
-            leading_enum_member_reg: Arc::clone(&self.leading_enum_member_reg),
+            file_reg: Arc::clone(&self.file_reg),
+            enum_type_reg: Arc::clone(&self.enum_type_reg),
+            support_reg: Arc::clone(&self.support_reg),
         }
     }
 }
@@ -93,7 +129,6 @@ impl<'a> Clone for Context<'a> {
 impl<'a> Context<'a> {
     pub fn new(options: &'a Options, syntax: &'a Syntax) -> Self {
         Self {
-            counter: Arc::new(AtomicU64::new(0)),
             options,
             syntax,
             namespace: vec![],
@@ -102,7 +137,9 @@ impl<'a> Context<'a> {
             import_identifier_map: Arc::new(DashMap::new()),
             type_reg: Arc::new(DashMap::new()),
             map_type_reg: Arc::new(DashMap::new()),
-            leading_enum_member_reg: Arc::new(DashMap::new()),
+            file_reg: Arc::new(DashMap::new()),
+            enum_type_reg: Arc::new(DashMap::new()),
+            support_reg: Arc::new(DashMap::new()),
         }
     }
 
@@ -122,12 +159,13 @@ impl<'a> Context<'a> {
             syntax: self.syntax,
             namespace,
             name: self.name.clone(),
-            counter: self.counter.clone(),
             import_identifier_map: self.import_identifier_map.clone(),
             imports: self.imports.clone(),
             type_reg: self.type_reg.clone(),
             map_type_reg: self.map_type_reg.clone(),
-            leading_enum_member_reg: self.leading_enum_member_reg.clone(),
+            file_reg: self.file_reg.clone(),
+            enum_type_reg: self.enum_type_reg.clone(),
+            support_reg: self.support_reg.clone(),
         }
     }
 
@@ -138,7 +176,9 @@ impl<'a> Context<'a> {
     pub fn drain_imports(&mut self) -> Vec<ModuleItem> {
         let mut imps = vec![];
         let mut imports = self.imports.lock().unwrap();
-        for import in imports.to_vec() {
+        let mut sorted = imports.to_vec();
+        sorted.sort_by(|a, b| a.src.value.cmp(&b.src.value));
+        for import in sorted {
             imps.push(ModuleItem::ModuleDecl(ModuleDecl::Import(import)))
         }
         imports.clear();
@@ -146,72 +186,49 @@ impl<'a> Context<'a> {
     }
 
     pub fn get_sendable_import(&self, source: &str) {
-        let name: Ident = quote_ident!("collections");
-
-        if !self.import_identifier_map.contains_key(&String::from(source)) {
-            let decl = ImportDecl {
-                span: DUMMY_SP,
-                specifiers: vec![ImportSpecifier::Named(ImportNamedSpecifier {
-                    span: DUMMY_SP,
-                    local: name,
-                    imported: None,
-                    is_type_only:false
-
-                })],
-                src: Box::new(Str {
-                    span: DUMMY_SP,
-                    raw: None,
-                    value: source.into(),
-                }),
-                type_only: false,
-                asserts: None,
-            };
-            self.imports.lock().unwrap().push(decl);
-            self.import_identifier_map.insert(String::from(source), u64::MAX);
-        }
-
+        self.get_named_import(source, "collections");
     }
 
     pub fn get_protobuf_import(&self, source: &str) {
-        let name: Ident = quote_ident!("BinaryReader, BinaryWriter");
-
-        if !self.import_identifier_map.contains_key(&String::from(source)) {
-            let decl = ImportDecl {
-                span: DUMMY_SP,
-                specifiers: vec![ImportSpecifier::Named(ImportNamedSpecifier {
-                    span: DUMMY_SP,
-                    local: name,
-                    imported: None,
-                    is_type_only:false
-
-                })],
-                src: Box::new(Str {
-                    span: DUMMY_SP,
-                    raw: None,
-                    value: source.into(),
-                }),
-                type_only: false,
-                asserts: None,
-            };
-            self.imports.lock().unwrap().push(decl);
-            self.import_identifier_map.insert(String::from(source), u64::MAX);
-        }
-
+        self.get_named_import(source, "BinaryReader");
+        self.get_named_import(source, "BinaryWriter");
     }
 
     pub fn get_base64_import(&self, source: &str) {
-        let name: Ident = quote_ident!("toUint8Array, fromUint8Array");
+        self.get_named_import(source, "toUint8Array");
+        self.get_named_import(source, "fromUint8Array");
+    }
 
-        if !self.import_identifier_map.contains_key(&String::from(source)) {
-            let decl = ImportDecl {
-                span: DUMMY_SP,
-                specifiers: vec![ImportSpecifier::Named(ImportNamedSpecifier {
-                    span: DUMMY_SP,
-                    local: name,
-                    imported: None,
-                    is_type_only:false
+    pub fn get_named_import(&self, source: &str, name: &str) -> Ident {
+        let local = quote_ident!(name);
+        let specifier = ImportSpecifier::Named(ImportNamedSpecifier {
+            span: DUMMY_SP,
+            local: local.clone(),
+            imported: None,
+            is_type_only: false,
+        });
 
-                })],
+        let mut imports = self.imports.lock().unwrap();
+        let existing = imports.iter_mut().find(|decl| {
+            &*decl.src.value == source
+                && decl
+                    .specifiers
+                    .iter()
+                    .all(|s| matches!(s, ImportSpecifier::Named(_)))
+        });
+
+        if let Some(decl) = existing {
+            let imported = decl.specifiers.iter().any(|s| match s {
+                ImportSpecifier::Named(named) => named.local.sym == local.sym,
+                _ => false,
+            });
+            if !imported {
+                decl.specifiers.push(specifier);
+            }
+        } else {
+            imports.push(ImportDecl {
+                span: DUMMY_SP,
+                specifiers: vec![specifier],
                 src: Box::new(Str {
                     span: DUMMY_SP,
                     raw: None,
@@ -219,27 +236,35 @@ impl<'a> Context<'a> {
                 }),
                 type_only: false,
                 asserts: None,
-            };
-            self.imports.lock().unwrap().push(decl);
-            self.import_identifier_map.insert(String::from(source), u64::MAX);
+            });
         }
 
+        local
     }
 
     pub fn get_import(&self, source: &str) -> Ident {
-        let cached_counter = self.import_identifier_map.get(&String::from(source));
+        self.get_import_as(source, source)
+    }
 
-        if let Some(counter) = cached_counter {
-            return quote_ident!(format!("imp_{}", *counter));
+    /// Imports `source` as a namespace, naming the binding after `hint`
+    /// (a proto path or module specifier). Aliases are deduplicated per file.
+    pub fn get_import_as(&self, source: &str, hint: &str) -> Ident {
+        if let Some(alias) = self.import_identifier_map.get(source) {
+            return quote_ident!(alias.clone());
         }
 
-        let counter = self.counter.fetch_add(1, Ordering::Relaxed);
-        let name = quote_ident!(format!("imp_{}", counter));
+        let base = import_alias(hint);
+        let mut alias = base.clone();
+        let mut suffix = 1;
+        while self.is_local_taken(&alias) {
+            alias = format!("{}_{}", base, suffix);
+            suffix += 1;
+        }
 
         let decl = ImportDecl {
             span: DUMMY_SP,
             specifiers: vec![ImportSpecifier::Namespace(ImportStarAsSpecifier {
-                local: name,
+                local: quote_ident!(alias.clone()),
                 span: DUMMY_SP,
             })],
             src: Box::new(Str {
@@ -251,9 +276,24 @@ impl<'a> Context<'a> {
             asserts: None,
         };
         self.imports.lock().unwrap().push(decl);
-        self.import_identifier_map.insert(String::from(source), counter);
+        self.import_identifier_map
+            .insert(String::from(source), alias.clone());
 
-        return quote_ident!(format!("imp_{}", counter));
+        quote_ident!(alias)
+    }
+
+    fn is_local_taken(&self, alias: &str) -> bool {
+        if RUNTIME_NAMED_IMPORTS.contains(&alias)
+            || self.import_identifier_map.iter().any(|e| *e.value() == alias)
+        {
+            return true;
+        }
+        self.imports.lock().unwrap().iter().any(|decl| {
+            decl.specifiers.iter().any(|s| match s {
+                ImportSpecifier::Named(named) => &*named.local.sym == alias,
+                _ => false,
+            })
+        })
     }
 
     pub fn wrap_if_needed(&mut self, modules: Vec<ModuleItem>) -> Vec<ModuleItem> {
@@ -292,39 +332,125 @@ impl<'a> Context<'a> {
         None
     }
 
+    pub fn is_well_known_file(&self, name: &str) -> bool {
+        name.starts_with("google/protobuf/")
+    }
+
+    /// Finds the module specifier configured for the proto file `provided_by`,
+    /// either by path prefix or by package. The longest matching key wins,
+    /// well-known types fall back to `wkt_package`.
+    pub fn find_module_mapping(&self, provided_by: &str) -> Option<String> {
+        let package = self.file_reg.get(provided_by).map(|f| f.package().to_string());
+        let mapping = self
+            .options
+            .module_mappings
+            .iter()
+            .filter(|(key, _)| {
+                if key.ends_with(".proto") || key.contains('/') {
+                    provided_by.starts_with(key.as_str())
+                } else if let Some(package) = &package {
+                    package == key || package.starts_with(&format!("{}.", key))
+                } else {
+                    false
+                }
+            })
+            .max_by_key(|(key, _)| key.len())
+            .map(|(_, module)| module.clone());
+
+        if mapping.is_none()
+            && !self.options.wkt_package.is_empty()
+            && self.is_well_known_file(provided_by)
+        {
+            return Some(self.options.wkt_package.clone());
+        }
+        mapping
+    }
+
+    /// Returns the import specifier for the proto file `provided_by` along with
+    /// the hint used to name its namespace import.
+    fn import_source(&self, provided_by: &str) -> (String, String) {
+        if let Some(module) = self.find_module_mapping(provided_by) {
+            return (module.clone(), module);
+        }
+
+        let import_from =
+            resolve_relative(provided_by.into(), PathBuf::from_str(&self.name).unwrap());
+        let mut import_from = import_from
+            .to_str()
+            .expect("invalid path conversion")
+            .strip_suffix(".proto")
+            .expect("expected path to have .proto suffix.")
+            .to_string();
+
+        import_from.push_str(self.options.import_suffix.as_str());
+
+        (import_from, provided_by.to_string())
+    }
+
     pub fn lazy_type_ref(&self, type_name: &str) -> Ident {
+        self.lazy_companion_type_ref(type_name, "")
+    }
+
+    /// Refers to a symbol generated next to the class of `type_name`, named after it with `suffix`.
+    pub fn lazy_companion_type_ref(&self, type_name: &str, suffix: &str) -> Ident {
         let provided_by = self.find_type_provider(&type_name.to_string());
         if let Some(provided_by) = provided_by {
             if self.name == provided_by {
-                return quote_ident!(type_name
-                    .strip_prefix(".")
-                    .expect("expected type to have leading dot")
-                    .replace(".", "_"));
+                return quote_ident!(format!(
+                    "{}{}",
+                    type_name
+                        .strip_prefix(".")
+                        .expect("expected type to have leading dot")
+                        .replace(".", "_"),
+                    suffix
+                ));
             } else {
-                let import_from =
-                    resolve_relative(provided_by.into(), PathBuf::from_str(&self.name).unwrap());
-                let mut import_from = import_from
-                    .to_str()
-                    .expect("invalid path conversion")
-                    .strip_suffix(".proto")
-                    .expect("expected path to have .proto suffix.")
-                    .to_string();
-
-                import_from.push_str(self.options.import_suffix.as_str());
-
-                let import_id = self.get_import(import_from.as_str());
                 let type_name = self.normalize_type_name(
                     type_name
                         .strip_prefix(".")
                         .expect("expected type name to have leading dot"),
                 );
-                return quote_ident!(format!("{}.{}", import_id.sym.to_string(), type_name));
+                return self.lazy_symbol_ref(&provided_by, &format!("{}{}", type_name, suffix));
             }
         } else {
             panic!("no proto provides {}", &type_name)
         }
     }
 
+    /// Refers to a symbol exported by the output of `provided_by`, importing it when needed.
+    pub fn lazy_symbol_ref(&self, provided_by: &str, symbol: &str) -> Ident {
+        if self.name == provided_by {
+            return quote_ident!(symbol);
+        }
+        let (import_from, alias_hint) = self.import_source(provided_by);
+        self.import_symbol(import_from.as_str(), &alias_hint, symbol)
+    }
+
+    /// Refers to a symbol exported by the support module emitted at `path` of the output root.
+    pub fn lazy_support_ref(&self, path: &str, symbol: &str) -> Ident {
+        self.support_reg.insert(path.to_string(), ());
+        let import_from = resolve_relative(path.into(), PathBuf::from_str(&self.name).unwrap());
+        let mut import_from = import_from
+            .to_str()
+            .expect("invalid path conversion")
+            .to_string();
+        import_from.push_str(self.options.import_suffix.as_str());
+        self.import_symbol(import_from.as_str(), path, symbol)
+    }
+
+    pub fn uses_support(&self, path: &str) -> bool {
+        self.support_reg.contains_key(path)
+    }
+
+    fn import_symbol(&self, import_from: &str, alias_hint: &str, symbol: &str) -> Ident {
+        // namespaced output exports nested modules, so it keeps namespace imports.
+        if self.options.named_imports && !self.options.namespaces {
+            return self.get_named_import(import_from, symbol);
+        }
+        let import_id = self.get_import_as(import_from, alias_hint);
+        quote_ident!(format!("{}.{}", import_id.sym.to_string(), symbol))
+    }
+
     pub fn calculate_type_name(&self, type_name: &str) -> String {
         let mut fns = String::from(".");
         if self.namespace.len() > 0 {
@@ -340,6 +466,24 @@ impl<'a> Context<'a> {
         self.type_reg.insert(fns, self.name.clone());
     }
 
+    pub fn register_file(&mut self, descriptor: &descriptor::FileDescriptorProto) {
+        self.file_reg.insert(self.name.clone(), descriptor.clone());
+    }
+
+    pub fn get_file(&self, name: &str) -> Option<descriptor::FileDescriptorProto> {
+        self.file_reg.get(name).map(|f| f.clone())
+    }
+
+    /// Name of the proto file being generated.
+    pub fn file_name(&self) -> &str {
+        &self.name
+    }
+
+    /// Identifier derived from the path of the proto file being generated.
+    pub fn file_alias(&self) -> String {
+        import_alias(&self.name)
+    }
+
     pub fn register_map_type(&mut self, descriptor: &descriptor::DescriptorProto) {
         let fns = self.calculate_type_name(descriptor.name());
         self.map_type_reg.insert(fns, descriptor.clone());
@@ -353,15 +497,23 @@ impl<'a> Context<'a> {
         None
     }
 
-    pub fn register_leading_enum_member(&mut self, descriptor: &descriptor::EnumDescriptorProto) {
+    pub fn register_enum_type(&mut self, descriptor: &descriptor::EnumDescriptorProto) {
         let fns = self.calculate_type_name(descriptor.name());
-        self.leading_enum_member_reg.insert(fns, descriptor.value.get(0).unwrap().number());
+        self.enum_type_reg.insert(fns, descriptor.clone());
+    }
+
+    pub fn get_enum_type(&self, type_name: &str) -> Option<descriptor::EnumDescriptorProto> {
+        let res = self.enum_type_reg.get(type_name);
+        if let Some(descriptor) = res {
+            return Some(descriptor.clone());
+        }
+        None
     }
 
     pub fn get_leading_enum_member(&self, type_name: &str) -> i32 {
-        let res = self.leading_enum_member_reg.get(type_name);
-        if let Some(num) = res {
-            return *num
+        let res = self.enum_type_reg.get(type_name);
+        if let Some(descriptor) = res {
+            return descriptor.value.first().unwrap().number()
         }
         panic!("no proto provides enum {}", &type_name)
     }
@@ -19,6 +19,8 @@ impl Mapper for CodeGeneratorRequest {
 
 impl Mapper for FileDescriptorProto {
     fn map(&self, ctx: &mut Context) {
+        ctx.register_file(self);
+
         let mut ctx = context::descend_if_necessary!(ctx, self);
 
         for r#enum in &self.enum_type {
@@ -34,7 +36,7 @@ impl Mapper for FileDescriptorProto {
 impl Mapper for EnumDescriptorProto {
     fn map(&self, ctx: &mut Context) {
         ctx.register_type_name(self.name());
-        ctx.register_leading_enum_member(self)
+        ctx.register_enum_type(self)
     }
 }
 
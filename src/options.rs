@@ -20,7 +20,28 @@ pub struct Options {
     pub namespaces: bool,
     pub import_suffix: String,
     pub with_namespace: bool,
-    pub with_sendable: bool
+    pub with_sendable: bool,
+    pub named_imports: bool,
+    pub module_mappings: Vec<(String, String)>,
+    pub wkt_package: String,
+    pub with_descriptor: bool,
+    pub unbox_wrappers: bool,
+    pub plain_struct: bool,
+    pub with_field_mask: bool,
+    pub arkts_strict: bool,
+    pub max_method_fields: usize,
+    pub with_text_format: bool,
+    pub lazy_fields: bool,
+    pub with_byte_size: bool,
+    pub with_delimited: bool,
+}
+
+fn parse_module_mapping(raw: &str) -> (String, String) {
+    let (key, module) = raw
+        .trim()
+        .split_once("=")
+        .expect("expected module mapping in the form <proto path prefix or package>=<module>");
+    (key.trim().to_string(), module.trim().to_string())
 }
 
 impl Options {
@@ -35,11 +56,24 @@ impl Options {
         let mut import_suffix = "";
         let mut with_namespace = true;
         let mut with_sendable = false;
+        let mut named_imports = false;
+        let mut module_mappings = vec![];
+        let mut wkt_package = "";
+        let mut with_descriptor = false;
+        let mut unbox_wrappers = false;
+        let mut plain_struct = false;
+        let mut with_field_mask = false;
+        let mut arkts_strict = false;
+        let mut max_method_fields = 30;
+        let mut with_text_format = false;
+        let mut lazy_fields = false;
+        let mut with_byte_size = false;
+        let mut with_delimited = false;
 
         let parts = raw.split(",");
 
         for part in parts {
-            let mut kv = part.trim().split("=");
+            let mut kv = part.trim().splitn(2, "=");
             let key = kv.next();
             if key.is_none() {
                 panic!("option key can not be empty.")
@@ -80,6 +114,63 @@ impl Options {
                 "with_sendable" => {
                     with_sendable = kv.next().expect("expected a value for extend namespace ") == "true";
                 },
+                "named_imports" => {
+                    named_imports = kv.next().expect("expected a value for named_imports") == "true";
+                },
+                "module_mapping" => {
+                    module_mappings.push(parse_module_mapping(
+                        kv.next().expect("expected a value for module_mapping"),
+                    ));
+                },
+                "with_descriptor" => {
+                    with_descriptor = kv.next().expect("expected a value for with_descriptor") == "true";
+                },
+                "wkt_package" => {
+                    wkt_package = kv.next().expect("expected a value for wkt_package")
+                },
+                "module_mapping_file" => {
+                    let path = kv.next().expect("expected a value for module_mapping_file");
+                    let contents = std::fs::read_to_string(path)
+                        .unwrap_or_else(|_| panic!("can not read module mapping file {}", path));
+                    for line in contents.lines() {
+                        let line = line.trim();
+                        if line.is_empty() || line.starts_with("#") {
+                            continue;
+                        }
+                        module_mappings.push(parse_module_mapping(line));
+                    }
+                },
+                "unbox_wrappers" => {
+                    unbox_wrappers = kv.next().expect("expected a value for unbox_wrappers") == "true";
+                },
+                "plain_struct" => {
+                    plain_struct = kv.next().expect("expected a value for plain_struct") == "true";
+                },
+                "with_field_mask" => {
+                    with_field_mask = kv.next().expect("expected a value for with_field_mask") == "true";
+                },
+                "arkts_strict" => {
+                    arkts_strict = kv.next().expect("expected a value for arkts_strict") == "true";
+                },
+                "max_method_fields" => {
+                    max_method_fields = kv
+                        .next()
+                        .expect("expected a value for max_method_fields")
+                        .parse::<usize>()
+                        .expect("expected a number of fields for max_method_fields");
+                },
+                "with_text_format" => {
+                    with_text_format = kv.next().expect("expected a value for with_text_format") == "true";
+                },
+                "lazy_fields" => {
+                    lazy_fields = kv.next().expect("expected a value for lazy_fields") == "true";
+                },
+                "with_byte_size" => {
+                    with_byte_size = kv.next().expect("expected a value for with_byte_size") == "true";
+                },
+                "with_delimited" => {
+                    with_delimited = kv.next().expect("expected a value for with_delimited") == "true";
+                },
                 // just silently ignore
                 option => {
                     eprintln!("WARNING: unknown option {}", option)
@@ -97,7 +188,20 @@ impl Options {
             namespaces,
             unary_rpc_promise,
             with_namespace,
-            with_sendable
+            with_sendable,
+            named_imports,
+            module_mappings,
+            wkt_package: wkt_package.to_string(),
+            with_descriptor,
+            unbox_wrappers,
+            plain_struct,
+            with_field_mask,
+            arkts_strict,
+            max_method_fields,
+            with_text_format,
+            lazy_fields,
+            with_byte_size,
+            with_delimited,
         }
     }
 }
@@ -154,6 +258,110 @@ fn should_parse_import_suffix() {
     assert_eq!(opt.import_suffix, ".ts");
 }
 
+#[test]
+fn should_parse_named_imports() {
+    let opt = Options::parse("named_imports=true");
+    assert!(opt.named_imports);
+    let opt = Options::parse("");
+    assert!(!opt.named_imports);
+}
+
+#[test]
+fn should_parse_module_mappings() {
+    let opt = Options::parse("module_mapping=google/protobuf/=@ourco/wkt,module_mapping=ourco.common=@ourco/common");
+    assert_eq!(
+        opt.module_mappings,
+        vec![
+            ("google/protobuf/".to_string(), "@ourco/wkt".to_string()),
+            ("ourco.common".to_string(), "@ourco/common".to_string()),
+        ]
+    );
+}
+
+#[test]
+fn should_parse_module_mapping_file() {
+    let path = std::env::temp_dir().join("protoc_gen_arkts_module_mapping.txt");
+    std::fs::write(&path, "# shared modules\ncommon/ = @ourco/common\n\ngoogle/protobuf/=@ourco/wkt\n").unwrap();
+    let opt = Options::parse(&format!("module_mapping_file={}", path.display()));
+    assert_eq!(
+        opt.module_mappings,
+        vec![
+            ("common/".to_string(), "@ourco/common".to_string()),
+            ("google/protobuf/".to_string(), "@ourco/wkt".to_string()),
+        ]
+    );
+}
+
+#[test]
+fn should_parse_wkt_package() {
+    let opt = Options::parse("wkt_package=@ourco/wkt");
+    assert_eq!(opt.wkt_package, "@ourco/wkt");
+    let opt = Options::parse("");
+    assert_eq!(opt.wkt_package, "");
+}
+
+#[test]
+fn should_parse_with_descriptor() {
+    let opt = Options::parse("with_descriptor=true");
+    assert!(opt.with_descriptor);
+}
+
+#[test]
+fn should_parse_unbox_wrappers() {
+    let opt = Options::parse("unbox_wrappers=true");
+    assert!(opt.unbox_wrappers);
+}
+
+#[test]
+fn should_parse_plain_struct() {
+    let opt = Options::parse("plain_struct=true");
+    assert!(opt.plain_struct);
+}
+
+#[test]
+fn should_parse_with_field_mask() {
+    let opt = Options::parse("with_field_mask=true");
+    assert!(opt.with_field_mask);
+}
+
+#[test]
+fn should_parse_arkts_strict() {
+    let opt = Options::parse("arkts_strict=true");
+    assert!(opt.arkts_strict);
+}
+
+#[test]
+fn should_parse_max_method_fields() {
+    let opt = Options::parse("max_method_fields=10");
+    assert_eq!(opt.max_method_fields, 10);
+    let opt = Options::parse("");
+    assert_eq!(opt.max_method_fields, 30);
+}
+
+#[test]
+fn should_parse_with_text_format() {
+    let opt = Options::parse("with_text_format=true");
+    assert!(opt.with_text_format);
+}
+
+#[test]
+fn should_parse_lazy_fields() {
+    let opt = Options::parse("lazy_fields=true");
+    assert!(opt.lazy_fields);
+}
+
+#[test]
+fn should_parse_with_byte_size() {
+    let opt = Options::parse("with_byte_size=true");
+    assert!(opt.with_byte_size);
+}
+
+#[test]
+fn should_parse_with_delimited() {
+    let opt = Options::parse("with_delimited=true");
+    assert!(opt.with_delimited);
+}
+
 #[test]
 fn should_parse_grpc_web_package() {
     let opt = Options::parse("grpc_web_package=grpc-web-my");
@@ -14,8 +14,9 @@ use crate::{context::Context, descriptor};
 use std::vec;
 use swc_common::DUMMY_SP;
 use swc_ecma_ast::{
-    AssignOp, BinaryOp, BlockStmt, BreakStmt, Expr, KeyValueProp, ObjectLit, PatOrExpr, Prop,
-    PropName, PropOrSpread, Stmt, SwitchCase, SwitchStmt, ThrowStmt, TsNonNullExpr, WhileStmt,
+    AssignOp, BinaryOp, BlockStmt, BreakStmt, ClassMember, Expr, KeyValueProp, ObjectLit, Param,
+    PatOrExpr, Prop, PropName, PropOrSpread, Stmt, SwitchCase, SwitchStmt, ThrowStmt,
+    TsKeywordTypeKind, TsNonNullExpr, WhileStmt,
 };
 use swc_ecma_utils::{quote_ident, quote_str};
 
@@ -29,16 +30,7 @@ impl GooglePBRuntime {
         let mut stmts = vec![];
 
         if create_br {
-            ctx.get_protobuf_import(&ctx.options.runtime_package);
-            let br_decl_init = crate::new_expr!(
-                Expr::Ident(quote_ident!("BinaryReader")),
-                vec![crate::expr_or_spread!(quote_ident!("bytes").into())]
-            );
-
-            let br_decl = Stmt::Decl(
-                crate::const_decl!(format!("{}: BinaryReader", "br"),
-                br_decl_init));
-            stmts.push(br_decl)
+            stmts.push(self.binary_reader_decl(ctx))
         }
 
         stmts.push(self.deserialize_stmt(ctx, descriptor, field::this_field_member, true));
@@ -46,6 +38,89 @@ impl GooglePBRuntime {
         stmts
     }
 
+    fn binary_reader_decl(&self, ctx: &mut Context) -> Stmt {
+        ctx.get_protobuf_import(&ctx.options.runtime_package);
+        let br_decl_init = crate::new_expr!(
+            Expr::Ident(quote_ident!("BinaryReader")),
+            vec![crate::expr_or_spread!(quote_ident!("bytes").into())]
+        );
+
+        Stmt::Decl(
+            crate::const_decl!(format!("{}: BinaryReader", "br"),
+            br_decl_init))
+    }
+
+    // every chunk of fields is read by a private mergeFrom_N(br): boolean method returning whether
+    // it knew the field, unknown fields are skipped once none of them did.
+    pub(super) fn deserialize_split_setup(
+        &self,
+        ctx: &mut Context,
+        descriptor: &descriptor::DescriptorProto,
+    ) -> (Vec<Stmt>, Vec<ClassMember>) {
+        let mut members = vec![];
+        let mut handled: Vec<Expr> = vec![];
+
+        for (index, fields) in descriptor.method_field_chunks(ctx).into_iter().enumerate() {
+            let mut chunk = descriptor.clone();
+            chunk.field = fields.to_vec();
+
+            let cases = self
+                .deserialize_field_cases(ctx, &chunk, field::this_field_member)
+                .into_iter()
+                .map(|mut case| {
+                    if let Some(last @ Stmt::Break(_)) = case.cons.last_mut() {
+                        *last = crate::return_stmt!(crate::lit_bool!(true).into());
+                    }
+                    case
+                })
+                .collect();
+            let stmts = vec![
+                Stmt::Switch(SwitchStmt {
+                    span: DUMMY_SP,
+                    discriminant: Box::new(crate::call_expr!(crate::member_expr!(
+                        "br",
+                        "getFieldNumber"
+                    ))),
+                    cases,
+                }),
+                crate::return_stmt!(crate::lit_bool!(false).into()),
+            ];
+
+            let helper = format!("mergeFrom_{}", index);
+            members.push(descriptor.print_private_method(
+                helper.clone(),
+                false,
+                vec![Param {
+                    span: DUMMY_SP,
+                    decorators: vec![],
+                    pat: crate::pat_ident!(quote_ident!("br"), crate::type_annotation!("BinaryReader")),
+                }],
+                stmts,
+                Some(crate::keyword_type!(TsKeywordTypeKind::TsBooleanKeyword)),
+            ));
+            handled.push(crate::call_expr!(
+                crate::member_expr!("this", helper),
+                vec![crate::expr_or_spread!(quote_ident!("br").into())]
+            ));
+        }
+
+        let known = handled
+            .into_iter()
+            .reduce(|left, right| crate::bin_expr!(left, right, BinaryOp::LogicalOr))
+            .expect("expected at least one chunk of fields");
+        let stmts = vec![
+            self.binary_reader_decl(ctx),
+            self.deserialize_loop(
+                vec![],
+                vec![crate::if_stmt!(
+                    crate::unary_expr!(crate::paren_expr!(known)),
+                    crate::expr_stmt!(crate::call_expr!(crate::member_expr!("br", "skipField")))
+                )],
+            ),
+        ];
+        (stmts, members)
+    }
+
     fn deserialize_message_field_preread_expr(
         &self,
         ctx: &mut Context,
@@ -78,6 +153,39 @@ impl GooglePBRuntime {
         )
     }
 
+    // the first occurrence of a lazy field keeps its bytes, later ones are merged into the decoded message.
+    // if (this._field === undefined && this._field_bytes === undefined) this._field_bytes = br.readBytes();
+    // else { this.field ??= new Msg; this.field.mergeFrom(br.readBytes()); }
+    fn deserialize_lazy_field_stmt(
+        &self,
+        ctx: &mut Context,
+        field: &descriptor::FieldDescriptorProto,
+        accessor: field::FieldAccessorFn,
+    ) -> Stmt {
+        let unset = |name: String| {
+            crate::bin_expr!(
+                crate::member_expr!("this", name),
+                quote_ident!("undefined").into(),
+                BinaryOp::EqEqEq
+            )
+        };
+        crate::if_stmt!(
+            crate::bin_expr!(
+                unset(field.lazy_value_name()),
+                unset(field.lazy_bytes_name()),
+                BinaryOp::LogicalAnd
+            ),
+            crate::expr_stmt!(crate::assign_expr!(
+                PatOrExpr::Expr(Box::new(crate::member_expr!("this", field.lazy_bytes_name()))),
+                crate::call_expr!(crate::member_expr!("br", "readBytes"))
+            )),
+            crate::block_stmt!(vec![
+                crate::expr_stmt!(self.deserialize_message_field_preread_expr(ctx, field, accessor)),
+                crate::expr_stmt!(self.deserialize_message_field_expr(ctx, field, accessor)),
+            ])
+        )
+    }
+
     fn deserialize_primitive_field_expr(
         &self,
         ctx: &mut Context,
@@ -128,6 +236,58 @@ impl GooglePBRuntime {
         call
     }
 
+    // br.readMessage(undefined, () => {
+    //     this.count = 0;
+    //     while (br.nextField() && !br.isEndGroup()) { ... }
+    // })
+    fn deserialize_unboxed_wrapper_field_expr(
+        &self,
+        ctx: &mut Context,
+        unboxed: &descriptor::FieldDescriptorProto,
+        accessor: field::FieldAccessorFn,
+    ) -> Expr {
+        // the wrapper message has the wrapped value as field 1, stored straight into the unboxed field.
+        let mut value_field = unboxed.clone();
+        value_field.set_number(1);
+        value_field.set_proto3_optional(false);
+        let mut wrapper = descriptor::DescriptorProto::new();
+        wrapper.field.push(value_field.clone());
+
+        crate::call_expr!(
+            crate::member_expr!("br", "readMessage"),
+            vec![
+                crate::expr_or_spread!(quote_ident!("undefined").into()),
+                crate::expr_or_spread!(crate::arrow_func!(
+                    vec![],
+                    vec![
+                        crate::expr_stmt!(crate::assign_expr!(
+                            PatOrExpr::Expr(Box::new(accessor(&value_field))),
+                            value_field.default_value_expr(ctx, true)
+                        )),
+                        self.deserialize_stmt(ctx, &wrapper, accessor, false),
+                    ]
+                ))
+            ]
+        )
+    }
+
+    // google_protobuf_Struct.fromBinary(br.readBytes()).toJson()
+    fn deserialize_plain_struct_field_expr(
+        &self,
+        ctx: &mut Context,
+        field: &descriptor::FieldDescriptorProto,
+    ) -> Expr {
+        crate::call_expr!(crate::member_expr_bare!(
+            crate::call_expr!(
+                crate::member_expr!(ctx.lazy_type_ref(field.type_name()), "fromBinary"),
+                vec![crate::expr_or_spread!(crate::call_expr!(
+                    crate::member_expr!("br", "readBytes")
+                ))]
+            ),
+            "toJson"
+        ))
+    }
+
     fn deserialize_map_field_expr(
         &self,
         ctx: &mut Context,
@@ -200,8 +360,58 @@ impl GooglePBRuntime {
         accessor: field::FieldAccessorFn,
         add_unknown_fields: bool,
     ) -> Stmt {
+        let cases = self.deserialize_field_cases(ctx, descriptor, accessor);
+        self.deserialize_loop(
+            cases,
+            vec![crate::expr_stmt!(crate::call_expr!(crate::member_expr!(
+                "br",
+                "skipField"
+            )))],
+        )
+    }
+
+    fn deserialize_field_cases(
+        &self,
+        ctx: &mut Context,
+        descriptor: &descriptor::DescriptorProto,
+        accessor: field::FieldAccessorFn,
+    ) -> Vec<SwitchCase> {
         let mut cases: Vec<SwitchCase> = vec![];
         for field in &descriptor.field {
+            let unboxed = if descriptor.options.map_entry() {
+                None
+            } else {
+                field.unboxed_wrapper(ctx)
+            };
+            let converted_read_stmt = if field.is_lazy(ctx) && !descriptor.options.map_entry() {
+                Some(self.deserialize_lazy_field_stmt(ctx, field, accessor))
+            } else if let Some(unboxed) = unboxed {
+                Some(crate::expr_stmt!(
+                    self.deserialize_unboxed_wrapper_field_expr(ctx, &unboxed, accessor)
+                ))
+            } else if field.is_plain_struct(ctx) && !descriptor.options.map_entry() {
+                Some(crate::expr_stmt!(crate::assign_expr!(
+                    PatOrExpr::Expr(Box::new(accessor(field))),
+                    self.deserialize_plain_struct_field_expr(ctx, field)
+                )))
+            } else {
+                None
+            };
+            if let Some(read_stmt) = converted_read_stmt {
+                cases.push(SwitchCase {
+                    span: DUMMY_SP,
+                    test: Some(Box::new(crate::lit_num!(field.number() as f64).into())),
+                    cons: vec![
+                        read_stmt,
+                        Stmt::Break(BreakStmt {
+                            label: None,
+                            span: DUMMY_SP,
+                        }),
+                    ],
+                });
+                continue;
+            }
+
             let mut read_expr = self.deserialize_field_expr(ctx, field, accessor, false);
             if field.is_bytes() && ctx.options.with_sendable {
                 read_expr = crate::call_expr!(
@@ -270,6 +480,11 @@ impl GooglePBRuntime {
                 cons: stmts,
             })
         }
+        cases
+    }
+
+    // while (br.nextField() && !br.isEndGroup()) { switch (br.getFieldNumber()) { ... } }
+    fn deserialize_loop(&self, mut cases: Vec<SwitchCase>, unknown_field: Vec<Stmt>) -> Stmt {
         // illegal zero case
         cases.push(SwitchCase {
             span: DUMMY_SP,
@@ -290,10 +505,7 @@ impl GooglePBRuntime {
         cases.push(SwitchCase {
             span: DUMMY_SP,
             test: None,
-            cons: vec![crate::expr_stmt!(crate::call_expr!(crate::member_expr!(
-                "br",
-                "skipField"
-            )))]
+            cons: unknown_field,
         });
 
         let switch_stmt = Stmt::Switch(SwitchStmt {
@@ -12,7 +12,7 @@ use crate::{
     descriptor::{self, field_descriptor_proto::Type, FieldDescriptorProto},
     runtime::Runtime,
 };
-use swc_ecma_ast::Stmt;
+use swc_ecma_ast::{ClassMember, ModuleItem, Stmt};
 use swc_ecma_visit::VisitMutWith;
 
 #[derive(Clone)]
@@ -23,16 +23,19 @@ impl Runtime for GooglePBRuntime {
         &self,
         ctx: &mut Context,
         descriptor: &descriptor::DescriptorProto,
-    ) -> Vec<Stmt> {
-        self.deserialize_setup_inner(ctx, descriptor, true)
+    ) -> (Vec<Stmt>, Vec<ClassMember>) {
+        if descriptor.method_field_chunks(ctx).len() > 1 {
+            return self.deserialize_split_setup(ctx, descriptor);
+        }
+        (self.deserialize_setup_inner(ctx, descriptor, true), vec![])
     }
 
     fn to_binary(
         &self,
         ctx: &mut Context,
         descriptor: &descriptor::DescriptorProto,
-    ) -> Vec<Stmt> {
-        self.serialize_setup_inner(ctx, descriptor, field::this_field_member, true, true)
+    ) -> (Vec<Stmt>, Vec<ClassMember>) {
+        self.serialize_split_setup(ctx, descriptor)
     }
 
     fn from_json<'a>(
@@ -45,7 +48,12 @@ impl Runtime for GooglePBRuntime {
             let proto = ctx
                 .find_type_provider(&type_name)
                 .expect("expected to find a proto file for the type");
-            let member = well_known::get_member(proto.as_str(), descriptor.name(), "from_json");
+            let member = well_known::get_member(
+                proto.as_str(),
+                descriptor.name(),
+                "from_json",
+                ctx.options.arkts_strict,
+            );
 
             if member.is_some() {
                 let mut member = member.unwrap();
@@ -67,7 +75,12 @@ impl Runtime for GooglePBRuntime {
             let proto = ctx
                 .find_type_provider(&type_name)
                 .expect("expected to find a proto file for the type");
-            let member = well_known::get_member(proto.as_str(), descriptor.name(), "to_json");
+            let member = well_known::get_member(
+                proto.as_str(),
+                descriptor.name(),
+                "to_json",
+                ctx.options.arkts_strict,
+            );
 
             if member.is_some() {
                 let mut member = member.unwrap();
@@ -78,6 +91,35 @@ impl Runtime for GooglePBRuntime {
         }
         None
     }
+
+    fn print_members(
+        &self,
+        ctx: &mut Context,
+        descriptor: &descriptor::DescriptorProto,
+    ) -> Vec<swc_ecma_ast::ClassMember> {
+        if !descriptor.is_well_known(ctx) {
+            return vec![];
+        }
+        let type_name = ctx.calculate_type_name(descriptor.name());
+        let proto = ctx
+            .find_type_provider(&type_name)
+            .expect("expected to find a proto file for the type");
+        let mut members = well_known::get_members(proto.as_str(), descriptor.name(), ctx.options.arkts_strict);
+        let mut visit = well_known::LazyTypeRefWkt { ctx };
+        members.visit_mut_with(&mut visit);
+        members
+    }
+
+    fn print_prelude(
+        &self,
+        ctx: &mut Context,
+        descriptor: &descriptor::FileDescriptorProto,
+    ) -> Vec<ModuleItem> {
+        let mut prelude = well_known::get_prelude(descriptor.name(), ctx.options.arkts_strict);
+        let mut visit = well_known::LazyTypeRefWkt { ctx };
+        prelude.visit_mut_with(&mut visit);
+        prelude
+    }
 }
 
 impl GooglePBRuntime {
@@ -7,13 +7,15 @@
  */
 
 use super::GooglePBRuntime;
+use crate::common::binary::binary_options_param;
 use crate::common::field;
 use crate::{context::Context, descriptor};
 
 use std::vec;
 use swc_common::DUMMY_SP;
 use swc_ecma_ast::{
-    BindingIdent, BlockStmt, Expr, ForHead, ForOfStmt, Stmt, TsNonNullExpr, VarDecl,
+    BinaryOp, BindingIdent, BlockStmt, ClassMember, Expr, ForHead, ForOfStmt, Param, Stmt, TsNonNullExpr,
+    VarDecl,
 };
 use swc_ecma_utils::quote_ident;
 
@@ -83,17 +85,128 @@ impl GooglePBRuntime {
             crate::member_expr!("bw", "writeBytes"),
             vec![
                 crate::expr_or_spread!(crate::lit_num!(field.number()).into()),
-                crate::expr_or_spread!(crate::call_expr!(crate::member_expr_bare!(
-                    Expr::TsNonNull(TsNonNullExpr {
-                        expr: Box::new(field_accessor(field)),
-                        span: DUMMY_SP
-                    }),
-                    "toBinary"
-                ))),
+                crate::expr_or_spread!(crate::call_expr!(
+                    crate::member_expr_bare!(
+                        Expr::TsNonNull(TsNonNullExpr {
+                            expr: Box::new(field_accessor(field)),
+                            span: DUMMY_SP
+                        }),
+                        "toBinary"
+                    ),
+                    vec![crate::expr_or_spread!(quote_ident!("options").into())]
+                )),
             ]
         ))
     }
 
+    // untouched lazy fields write back the bytes they were read from, unless the output has to be
+    // deterministic and the field is decoded through its getter.
+    // if (this._field_bytes !== undefined && options?.deterministic !== true) { bw.writeBytes(3, this._field_bytes); }
+    // else if (this.field !== undefined) { bw.writeBytes(3, this.field.toBinary(options)); }
+    pub fn serialize_lazy_field_stmt(&self, field: &descriptor::FieldDescriptorProto) -> Stmt {
+        let value_member = |field: &descriptor::FieldDescriptorProto| crate::member_expr!("this", field.name());
+        let bytes_member = crate::member_expr!("this", field.lazy_bytes_name());
+        crate::if_stmt!(
+            crate::bin_expr!(
+                crate::bin_expr!(bytes_member.clone(), quote_ident!("undefined").into(), BinaryOp::NotEqEq),
+                crate::bin_expr!(
+                    crate::member_expr!("options?", "deterministic"),
+                    quote_ident!("true").into(),
+                    BinaryOp::NotEqEq
+                ),
+                BinaryOp::LogicalAnd
+            ),
+            crate::block_stmt!(vec![crate::expr_stmt!(crate::call_expr!(
+                crate::member_expr!("bw", "writeBytes"),
+                vec![
+                    crate::expr_or_spread!(crate::lit_num!(field.number()).into()),
+                    crate::expr_or_spread!(bytes_member),
+                ]
+            ))]),
+            crate::if_stmt!(
+                crate::bin_expr!(value_member(field), quote_ident!("undefined").into(), BinaryOp::NotEqEq),
+                crate::block_stmt!(vec![self.serialize_message_field_stmt(field, value_member)])
+            )
+        )
+    }
+
+    // bw.beginSubMessage(3);
+    // if (this.count !== undefined && this.count !== 0) {
+    //     bw.writeInt32(1, this.count);
+    // }
+    // bw.endSubMessage();
+    pub fn serialize_unboxed_wrapper_field_stmt(
+        &self,
+        ctx: &mut Context,
+        field: &descriptor::FieldDescriptorProto,
+        unboxed: &descriptor::FieldDescriptorProto,
+        field_accessor: field::FieldAccessorFn,
+    ) -> Stmt {
+        // the value field of the wrapper message, read through the accessor of the unboxed field.
+        let mut value_field = unboxed.clone();
+        value_field.set_number(1);
+        value_field.set_proto3_optional(false);
+
+        let access_normalizer: Option<field::AccessNormalizerFn> = if value_field.is_bigint() {
+            Some(field::to_string_normalizer)
+        } else {
+            None
+        };
+
+        Stmt::Block(BlockStmt {
+            span: DUMMY_SP,
+            stmts: vec![
+                crate::expr_stmt!(crate::call_expr!(
+                    crate::member_expr!("bw", "beginSubMessage"),
+                    vec![crate::expr_or_spread!(crate::lit_num!(field.number()).into())]
+                )),
+                crate::if_stmt!(
+                    value_field.default_value_bin_expr(ctx, field_accessor),
+                    crate::block_stmt!(vec![self.serialize_primitive_field_stmt(
+                        ctx,
+                        &value_field,
+                        field_accessor,
+                        access_normalizer,
+                    )])
+                ),
+                crate::expr_stmt!(crate::call_expr!(
+                    crate::member_expr!("bw", "endSubMessage"),
+                    vec![]
+                )),
+            ],
+        })
+    }
+
+    // bw.writeBytes(10, google_protobuf_Struct.fromJson(this.attrs).toBinary(options));
+    pub fn serialize_plain_struct_field_stmt(
+        &self,
+        ctx: &mut Context,
+        field: &descriptor::FieldDescriptorProto,
+        field_accessor: field::FieldAccessorFn,
+    ) -> Stmt {
+        crate::expr_stmt!(crate::call_expr!(
+            crate::member_expr!("bw", "writeBytes"),
+            vec![
+                crate::expr_or_spread!(crate::lit_num!(field.number()).into()),
+                crate::expr_or_spread!(crate::call_expr!(
+                    crate::member_expr_bare!(
+                        crate::call_expr!(
+                            crate::member_expr!(ctx.lazy_type_ref(field.type_name()), "fromJson"),
+                            vec![crate::expr_or_spread!(field_accessor(field))]
+                        ),
+                        "toBinary"
+                    ),
+                    vec![crate::expr_or_spread!(quote_ident!("options").into())]
+                )),
+            ]
+        ))
+    }
+
+    // for (let entry of binary_support.orderedEntries(this.m.entries(), options?.deterministic === true, ...)) {
+    //     bw.beginSubMessage(7);
+    //     ...
+    //     bw.endSubMessage();
+    // }
     fn serialize_map_field_stmt(
         &self,
         ctx: &mut Context,
@@ -132,7 +245,7 @@ impl GooglePBRuntime {
         Stmt::ForOf(ForOfStmt {
             is_await: false,
             left: ForHead::Pat(Box::new(crate::pat_ident!(quote_ident!("let entry")))),
-            right: Box::new(crate::member_expr_bare!(crate::member_expr!("this", field.name()), "entries()")),
+            right: Box::new(field.ordered_entries_expr(ctx, crate::member_expr!("this", field.name()))),
             body: Box::new(Stmt::Block(BlockStmt {
                 span: DUMMY_SP,
                 stmts,
@@ -143,6 +256,39 @@ impl GooglePBRuntime {
 }
 
 impl GooglePBRuntime {
+    fn binary_writer_decl(&self, ctx: &mut Context) -> Stmt {
+        ctx.get_protobuf_import(&ctx.options.runtime_package);
+        let bw_decl_init = crate::new_expr!(Expr::Ident(quote_ident!("BinaryWriter")));
+        Stmt::Decl(
+            crate::const_decl!(
+                format!("{}: BinaryWriter", "bw"), bw_decl_init))
+    }
+
+    // large messages write each chunk of fields in a private toBinary_N(bw, options) method.
+    pub(super) fn serialize_split_setup(
+        &self,
+        ctx: &mut Context,
+        descriptor: &descriptor::DescriptorProto,
+    ) -> (Vec<Stmt>, Vec<ClassMember>) {
+        let mut stmts = vec![self.binary_writer_decl(ctx)];
+        let params = vec![
+            Param {
+                span: DUMMY_SP,
+                decorators: vec![],
+                pat: crate::pat_ident!(quote_ident!("bw"), crate::type_annotation!("BinaryWriter")),
+            },
+            binary_options_param(ctx),
+        ];
+        let (mut field_stmts, members) =
+            descriptor.print_method_chunks(ctx, "toBinary", false, params, |ctx, fields| {
+                let mut chunk = descriptor.clone();
+                chunk.field = fields.to_vec();
+                self.serialize_setup_inner(ctx, &chunk, field::this_field_member, false, true)
+            });
+        stmts.append(&mut field_stmts);
+        (stmts, members)
+    }
+
     pub(super) fn serialize_setup_inner(
         &self,
         ctx: &mut Context,
@@ -154,12 +300,7 @@ impl GooglePBRuntime {
         let mut stmts = vec![];
 
         if create_bw {
-            ctx.get_protobuf_import(&ctx.options.runtime_package);
-            let bw_decl_init = crate::new_expr!(Expr::Ident(quote_ident!("BinaryWriter")));
-            let bw_decl = Stmt::Decl(
-                crate::const_decl!(
-                    format!("{}: BinaryWriter", "bw"), bw_decl_init));
-            stmts.push(bw_decl)
+            stmts.push(self.binary_writer_decl(ctx))
         }
 
         for field in &descriptor.field {
@@ -180,7 +321,24 @@ impl GooglePBRuntime {
 
             let mut field_stmt: Stmt;
 
-            if field.is_message() {
+            let unboxed = if descriptor.options.map_entry() {
+                None
+            } else {
+                field.unboxed_wrapper(ctx)
+            };
+
+            // lazy fields are checked without decoding them, so they skip the default value check below.
+            if field.is_lazy(ctx) && !descriptor.options.map_entry() {
+                stmts.push(self.serialize_lazy_field_stmt(field));
+                continue;
+            }
+
+            if let Some(unboxed) = &unboxed {
+                field_stmt =
+                    self.serialize_unboxed_wrapper_field_stmt(ctx, field, unboxed, field_accessor)
+            } else if field.is_plain_struct(ctx) && !descriptor.options.map_entry() {
+                field_stmt = self.serialize_plain_struct_field_stmt(ctx, field, field_accessor)
+            } else if field.is_message() {
                 field_stmt = self.serialize_message_field_stmt(field, field_accessor)
             } else if field.type_() == descriptor::field_descriptor_proto::Type::TYPE_SFIXED64
                 && field.is_packed(ctx)
@@ -199,10 +357,15 @@ impl GooglePBRuntime {
                 field_stmt = self.serialize_map_field_stmt(ctx, field)
             } else if field.is_repeated() && !field.is_packed(ctx) {
                 if ctx.options.with_sendable {
+                    let mut element = field.clone();
+                    element.set_label(descriptor::field_descriptor_proto::Label::LABEL_OPTIONAL);
                     field_stmt = crate::expr_stmt!(crate::call_expr!(
                         crate::member_expr_bare!(super::field::this_field_member(field), "forEach"),
                         vec![crate::expr_or_spread!(crate::arrow_func!(
-                            vec![crate::pat_ident!(quote_ident!(field.name()))],
+                            vec![crate::pat_ident!(
+                                quote_ident!(field::local_name(field)),
+                                element.type_annotation(ctx);
+                            )],
                             vec![
                                 field_stmt
                             ]
@@ -212,7 +375,7 @@ impl GooglePBRuntime {
                 } else {
                     field_stmt = Stmt::ForOf(ForOfStmt {
                         is_await: false,
-                        left: ForHead::VarDecl(Box::new(crate::const_decl_uinit!(field.name()))),
+                        left: ForHead::VarDecl(Box::new(crate::const_decl_uinit!(field::local_name(field)))),
                         right: Box::new(accessor(field)),
                         body: Box::new(Stmt::Block(BlockStmt {
                             span: DUMMY_SP,
@@ -224,11 +387,15 @@ impl GooglePBRuntime {
             }
 
             if prevent_defaults {
+                let field_stmts = match field_stmt {
+                    Stmt::Block(block) => block.stmts,
+                    field_stmt => vec![field_stmt],
+                };
                 stmts.push(crate::if_stmt!(
                     field.default_value_bin_expr(ctx, accessor),
                     Stmt::Block(BlockStmt {
                         span: DUMMY_SP,
-                        stmts: vec![field_stmt]
+                        stmts: field_stmts
                     })
                 ));
             } else {
@@ -1,15 +1,13 @@
 use crate::context::Context;
 use swc_ecma_ast::Ident;
-use swc_ecma_utils::quote_ident;
-use swc_ecma_visit::{noop_visit_mut_type, VisitMut};
+use swc_ecma_visit::VisitMut;
 
 pub struct LazyTypeRefWkt<'a, 'b> {
     pub ctx: &'a mut Context<'b>,
 }
 
+// types are visited as well, the runtime of descriptor.proto refers to the generated classes in annotations.
 impl<'a, 'b> VisitMut for LazyTypeRefWkt<'a, 'b> {
-    noop_visit_mut_type!();
-
     fn visit_mut_ident(&mut self, s: &mut Ident) {
         if s.sym.starts_with("$wkt_") {
             let v = format!(
@@ -24,8 +22,6 @@ impl<'a, 'b> VisitMut for LazyTypeRefWkt<'a, 'b> {
             *s = self
                 .ctx
                 .get_import(self.ctx.options.base64_package.as_str())
-        } else if s.sym.to_string() == "$type_registry$" {
-            *s = quote_ident!("globalThis.protobuf")
         }
     }
 }
@@ -1,14 +1,20 @@
-use crate::{context::Context, descriptor::{DescriptorProto, MethodDescriptorProto, ServiceDescriptorProto}};
-use swc_ecma_ast::{ClassMember, Stmt};
+use crate::{context::Context, descriptor::{DescriptorProto, FileDescriptorProto, MethodDescriptorProto, ServiceDescriptorProto}};
+use swc_ecma_ast::{ClassMember, ModuleItem, Stmt};
 
 pub trait Runtime {
-    // binary
-    fn from_binary(&self, ctx: &mut Context, descriptor: &DescriptorProto) -> Vec<Stmt>;
-    fn to_binary(&self, ctx: &mut Context, descriptor: &DescriptorProto) -> Vec<Stmt>;
+    // binary, along with the private helpers of messages split by max_method_fields
+    fn from_binary(&self, ctx: &mut Context, descriptor: &DescriptorProto) -> (Vec<Stmt>, Vec<ClassMember>);
+    fn to_binary(&self, ctx: &mut Context, descriptor: &DescriptorProto) -> (Vec<Stmt>, Vec<ClassMember>);
 
     // json for well known types
     fn from_json(&self, ctx: &mut Context, descriptor: &DescriptorProto) -> Option<ClassMember>;
     fn to_json(&self, ctx: &mut Context, descriptor: &DescriptorProto) -> Option<ClassMember>;
+
+    // additional members of well known types
+    fn print_members(&self, ctx: &mut Context, descriptor: &DescriptorProto) -> Vec<ClassMember>;
+
+    // module level declarations for well known types
+    fn print_prelude(&self, ctx: &mut Context, descriptor: &FileDescriptorProto) -> Vec<ModuleItem>;
 }
 
 pub trait GrpcRuntime {
@@ -0,0 +1,188 @@
+mod common;
+
+use common::{generate_files, parse, INPUTS, WELL_KNOWN};
+use swc_ecma_ast::{
+    Expr, ForInStmt, MemberProp, NewExpr, Pat, TsEntityName, TsIndexSignature, TsKeywordType,
+    TsKeywordTypeKind, TsTypeRef, VarDecl, VarDeclKind,
+};
+use swc_ecma_visit::{Visit, VisitWith};
+
+// the methods of Object which are not available in ArkTS, Object.keys is.
+const LIMITED_OBJECT_METHODS: [&str; 6] = [
+    "assign",
+    "create",
+    "defineProperty",
+    "entries",
+    "fromEntries",
+    "getOwnPropertyNames",
+];
+
+// a subset of the ArkTS strict mode rules which can be checked on the syntax tree.
+#[derive(Default)]
+struct StrictLint {
+    violations: Vec<String>,
+}
+
+impl StrictLint {
+    fn report(&mut self, rule: &str) {
+        self.violations.push(rule.to_string());
+    }
+
+    fn check_param(&mut self, pat: &Pat) {
+        match pat {
+            Pat::Ident(ident) if ident.type_ann.is_none() => self.report("arkts-no-untyped-params"),
+            Pat::Array(_) | Pat::Object(_) => self.report("arkts-no-destruct-params"),
+            _ => {}
+        }
+    }
+}
+
+impl Visit for StrictLint {
+    fn visit_ts_keyword_type(&mut self, n: &TsKeywordType) {
+        if matches!(
+            n.kind,
+            TsKeywordTypeKind::TsAnyKeyword | TsKeywordTypeKind::TsUnknownKeyword
+        ) {
+            self.report("arkts-no-any-unknown");
+        }
+    }
+
+    fn visit_ts_type_ref(&mut self, n: &TsTypeRef) {
+        if matches!(&n.type_name, TsEntityName::Ident(ident) if &*ident.sym == "ESObject") {
+            self.report("arkts-no-esobject");
+        }
+        n.visit_children_with(self);
+    }
+
+    fn visit_member_prop(&mut self, n: &MemberProp) {
+        if let MemberProp::Computed(computed) = n {
+            if let Expr::Lit(swc_ecma_ast::Lit::Str(_)) = &*computed.expr {
+                self.report("arkts-no-props-by-index");
+            }
+        }
+        n.visit_children_with(self);
+    }
+
+    fn visit_member_expr(&mut self, n: &swc_ecma_ast::MemberExpr) {
+        if let (Expr::Ident(object), MemberProp::Ident(prop)) = (&*n.obj, &n.prop) {
+            if &*object.sym == "Object" && LIMITED_OBJECT_METHODS.contains(&&*prop.sym) {
+                self.report("arkts-limited-stdlib");
+            }
+        }
+        n.visit_children_with(self);
+    }
+
+    fn visit_arrow_expr(&mut self, n: &swc_ecma_ast::ArrowExpr) {
+        for param in &n.params {
+            self.check_param(param);
+        }
+        n.visit_children_with(self);
+    }
+
+    fn visit_function(&mut self, n: &swc_ecma_ast::Function) {
+        for param in &n.params {
+            self.check_param(&param.pat);
+        }
+        n.visit_children_with(self);
+    }
+
+    fn visit_ts_index_signature(&mut self, n: &TsIndexSignature) {
+        self.report("arkts-no-indexed-signatures");
+        n.visit_children_with(self);
+    }
+
+    fn visit_new_expr(&mut self, n: &NewExpr) {
+        if matches!(&*n.callee, Expr::Ident(ident) if &*ident.sym == "Object") {
+            self.report("arkts-no-untyped-obj-literals");
+        }
+        n.visit_children_with(self);
+    }
+
+    fn visit_var_decl(&mut self, n: &VarDecl) {
+        if n.kind == VarDeclKind::Var {
+            self.report("arkts-no-var");
+        }
+        n.visit_children_with(self);
+    }
+
+    fn visit_for_in_stmt(&mut self, n: &ForInStmt) {
+        self.report("arkts-no-for-in");
+        n.visit_children_with(self);
+    }
+}
+
+fn lint(name: &str, source: &str) -> Vec<String> {
+    let module = parse(name, source);
+    let mut lint = StrictLint::default();
+    module.visit_with(&mut lint);
+    lint.violations
+        .into_iter()
+        .map(|rule| format!("{}: {}", name, rule))
+        .collect()
+}
+
+// protos importing the well-known types which the inputs do not use.
+const WELL_KNOWN_USERS: [&str; 3] = ["registry/payload.proto", "registry/envelope.proto", "wkt/times.proto"];
+
+// lints the test protos together with the well-known types, their runtimes are strict as well.
+fn lint_generated(parameter: &str) -> Vec<String> {
+    let files: Vec<&str> = INPUTS
+        .iter()
+        .chain(WELL_KNOWN_USERS.iter())
+        .chain(WELL_KNOWN.iter())
+        .copied()
+        .collect();
+    generate_files(&files, parameter)
+        .iter()
+        .flat_map(|(name, content)| lint(name, content))
+        .collect()
+}
+
+#[test]
+fn default_output_is_not_strict() {
+    assert!(!lint_generated("").is_empty());
+}
+
+#[test]
+fn strict_output() {
+    let violations = lint_generated("arkts_strict=true");
+    assert!(violations.is_empty(), "{:#?}", violations);
+}
+
+#[test]
+fn strict_output_with_options() {
+    let violations = lint_generated(
+        "arkts_strict=true,with_sendable=true,with_field_mask=true,unbox_wrappers=true,plain_struct=true,with_delimited=true",
+    );
+    assert!(violations.is_empty(), "{:#?}", violations);
+}
+
+#[test]
+fn strict_output_with_split_methods() {
+    let violations = lint_generated("arkts_strict=true,max_method_fields=2");
+    assert!(violations.is_empty(), "{:#?}", violations);
+}
+
+#[test]
+fn strict_output_with_text_format() {
+    let violations = lint_generated("arkts_strict=true,with_text_format=true,max_method_fields=2");
+    assert!(violations.is_empty(), "{:#?}", violations);
+}
+
+#[test]
+fn strict_output_with_lazy_fields() {
+    let violations = lint_generated("arkts_strict=true,lazy_fields=true,max_method_fields=2");
+    assert!(violations.is_empty(), "{:#?}", violations);
+}
+
+#[test]
+fn strict_output_with_descriptor() {
+    let violations = lint_generated("arkts_strict=true,with_descriptor=true");
+    assert!(violations.is_empty(), "{:#?}", violations);
+}
+
+#[test]
+fn strict_output_with_byte_size() {
+    let violations = lint_generated("arkts_strict=true,with_byte_size=true,lazy_fields=true,max_method_fields=2");
+    assert!(violations.is_empty(), "{:#?}", violations);
+}
@@ -0,0 +1,161 @@
+mod common;
+
+use common::{class, file_names, generate, has_member, method_signature, module, run_node, INPUTS, WELL_KNOWN};
+
+#[test]
+fn sized_support_module_is_emitted_with_byte_size() {
+    let files = generate("");
+    assert!(!file_names(&files).contains(&"sized_support.ets"));
+
+    let files = generate("with_byte_size=true,max_method_fields=2");
+    assert!(file_names(&files).contains(&"sized_support.ets"));
+    let strict = module(&files, "strict.ets");
+    assert_eq!(
+        method_signature(&strict, "strict_pkg_Node", "byteSize"),
+        "byteSize(options?: binary_support.BinaryWriteOptions, cache?: sized_support.SizeCache): number"
+    );
+    assert_eq!(
+        method_signature(&strict, "strict_pkg_Node", "writeTo"),
+        "writeTo(w: sized_support.SizedWriter, options?: binary_support.BinaryWriteOptions): void"
+    );
+    assert_eq!(
+        method_signature(&strict, "strict_pkg_Node", "toBinaryInto"),
+        "toBinaryInto(buffer?: Uint8Array, offset?: number, options?: binary_support.BinaryWriteOptions): Uint8Array"
+    );
+    // split messages sum the sizes of their chunks.
+    assert!(has_member(class(&strict, "strict_pkg_Node"), "private byteSize_1"));
+}
+
+#[test]
+fn byte_size_matches_the_binary_encoding() {
+    let script = r#"
+import assert from "node:assert/strict";
+import { strict_pkg_Node, strict_pkg_Node_Leaf } from "./strict.mjs";
+
+const node = new strict_pkg_Node();
+node.id = "héllo";
+node.weight = 2.5;
+node.levels = [1, 0, 1];
+node.offsets = [-1n, 300n];
+node.leaves.set(-5n, new strict_pkg_Node_Leaf());
+node.flags.set(false, "no");
+node.chunks.set("k", new Uint8Array(200));
+node.counter = 1n << 40n;
+node.root = new strict_pkg_Node_Leaf();
+node.root.payload = new Uint8Array([7]);
+
+const bytes = node.toBinary();
+assert.equal(node.byteSize(), bytes.length);
+assert.deepEqual(node.toBinaryInto(), bytes);
+
+const buffer = new Uint8Array(bytes.length + 3);
+assert.deepEqual(node.toBinaryInto(buffer, 3), bytes);
+assert.throws(() => node.toBinaryInto(buffer, 4), RangeError);
+"#;
+    let inputs: Vec<&str> = INPUTS.iter().chain(WELL_KNOWN.iter()).copied().collect();
+    run_node("byte_size", &inputs, "with_byte_size=true,max_method_fields=2", script);
+}
+
+// toBinaryInto sizes every nested message once, writeTo takes the sizes byteSize recorded.
+const DEEP_SCRIPT: &str = r#"
+import assert from "node:assert/strict";
+import { strict_pkg_Node, strict_pkg_Node_Leaf } from "./strict.mjs";
+
+const depth = 200;
+const chain = () => {
+  const top = new strict_pkg_Node_Leaf();
+  let leaf = top;
+  for (let i = 1; i < depth; i++) {
+    const child = new strict_pkg_Node_Leaf();
+    child.payload = new Uint8Array([i & 0xff]);
+    leaf.children.push(child);
+    leaf = child;
+  }
+  return top;
+};
+const node = new strict_pkg_Node();
+node.root = chain();
+node.leaves.set(1n, chain());
+
+const byteSize = strict_pkg_Node_Leaf.prototype.byteSize;
+let calls = 0;
+strict_pkg_Node_Leaf.prototype.byteSize = function (options, cache) {
+  calls++;
+  return byteSize.call(this, options, cache);
+};
+const bytes = node.toBinaryInto();
+assert.equal(calls, 2 * depth);
+strict_pkg_Node_Leaf.prototype.byteSize = byteSize;
+
+assert.deepEqual(bytes, node.toBinary());
+assert.ok(strict_pkg_Node.fromBinary(bytes).equals(node));
+"#;
+
+#[test]
+fn to_binary_into_sizes_nested_messages_once() {
+    let inputs: Vec<&str> = INPUTS.iter().chain(WELL_KNOWN.iter()).copied().collect();
+    run_node("byte_size_deep", &inputs, "with_byte_size=true,max_method_fields=2", DEEP_SCRIPT);
+}
+
+// a SizeCache is only valid until the message changes, toBinaryInto fills a fresh one on every call.
+const MUTATED_SCRIPT: &str = r#"
+import assert from "node:assert/strict";
+import { strict_pkg_Node, strict_pkg_Node_Leaf } from "./strict.mjs";
+import { SizeCache, SizedWriter } from "./sized_support.mjs";
+
+const node = new strict_pkg_Node();
+node.root = new strict_pkg_Node_Leaf();
+node.root.children.push(new strict_pkg_Node_Leaf());
+node.root.children[0].payload = new Uint8Array([1]);
+const before = node.toBinaryInto();
+
+const cache = new SizeCache();
+node.byteSize(undefined, cache);
+node.root.children[0].payload = new Uint8Array(300);
+node.leaves.set(1n, node.root.children[0]);
+
+const after = node.toBinaryInto();
+assert.notDeepEqual(after, before);
+assert.deepEqual(after, node.toBinary());
+assert.ok(strict_pkg_Node.fromBinary(after).equals(node));
+
+// without the stale cache writeTo computes the nested sizes again.
+const w = new SizedWriter(node.byteSize());
+node.writeTo(w);
+assert.deepEqual(w.finish(), node.toBinary());
+"#;
+
+#[test]
+fn to_binary_into_sizes_again_after_a_mutation() {
+    let inputs: Vec<&str> = INPUTS.iter().chain(WELL_KNOWN.iter()).copied().collect();
+    run_node("byte_size_mutated", &inputs, "with_byte_size=true", MUTATED_SCRIPT);
+}
+
+// the messages converted from plain values by byteSize are written as they are.
+const PLAIN_SCRIPT: &str = r#"
+import assert from "node:assert/strict";
+import { google_protobuf_Struct } from "./google/protobuf/struct.mjs";
+import { wkt_Plain } from "./wkt/plain.mjs";
+
+const m = wkt_Plain.create({ attrs: { a: [1, "x", null] }, value: "v", items: [true, 2] });
+const fromJson = google_protobuf_Struct.fromJson;
+let conversions = 0;
+google_protobuf_Struct.fromJson = (json, options) => {
+  conversions++;
+  return fromJson(json, options);
+};
+const bytes = m.toBinaryInto();
+assert.equal(conversions, 1);
+google_protobuf_Struct.fromJson = fromJson;
+assert.deepEqual(bytes, m.toBinary());
+"#;
+
+#[test]
+fn to_binary_into_converts_plain_structs_once() {
+    run_node(
+        "byte_size_plain",
+        &["wkt/plain.proto", "google/protobuf/struct.proto"],
+        "plain_struct=true,with_byte_size=true",
+        PLAIN_SCRIPT,
+    );
+}
@@ -0,0 +1,409 @@
+// helpers shared by the integration tests: generation of the test protos, lookups in the
+// syntax tree of the output and a runner executing the output with node.
+#![allow(dead_code)]
+
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use protobuf::plugin::{CodeGeneratorRequest, CodeGeneratorResponse};
+use protobuf::Message;
+use swc_common::{sync::Lrc, FileName, SourceMap};
+use swc_ecma_ast::{
+    BindingIdent, Class, ClassMember, ClassMethod, Decl, EsVersion, ExportDecl,
+    ImportDecl, ImportSpecifier, Module, ModuleDecl, ModuleExportName, ModuleItem, Param,
+    ParamOrTsParamProp, Pat, PropName, Stmt, TsInterfaceDecl, TsTypeAliasDecl,
+};
+use swc_ecma_codegen::{text_writer::JsWriter, Emitter};
+use swc_ecma_parser::{parse_file_as_module, Syntax, TsConfig};
+
+mod strip;
+
+pub const INPUTS: [&str; 2] = ["gen.proto", "strict.proto"];
+
+// the well-known types referenced by the test protos, generated next to them for the node runs.
+pub const WELL_KNOWN: [&str; 7] = [
+    "google/protobuf/any.proto",
+    "google/protobuf/duration.proto",
+    "google/protobuf/empty.proto",
+    "google/protobuf/field_mask.proto",
+    "google/protobuf/struct.proto",
+    "google/protobuf/timestamp.proto",
+    "google/protobuf/wrappers.proto",
+];
+
+pub fn generate(parameter: &str) -> Vec<(String, String)> {
+    generate_files(&INPUTS, parameter)
+}
+
+// generates the given files of tests/, the well-known types among them come with the parser.
+pub fn generate_files(files: &[&str], parameter: &str) -> Vec<(String, String)> {
+    generate_files_in(Path::new("tests"), files, parameter)
+}
+
+// generates the given files of an include directory.
+pub fn generate_files_in(include: &Path, files: &[&str], parameter: &str) -> Vec<(String, String)> {
+    let inputs: Vec<_> = files
+        .iter()
+        .filter(|f| !f.starts_with("google/protobuf/"))
+        .map(|f| include.join(f))
+        .collect();
+    let parsed = protobuf_parse::Parser::new()
+        .pure()
+        .include(include)
+        .inputs(&inputs)
+        .parse_and_typecheck()
+        .expect("failed to parse the test protos");
+
+    let mut request = CodeGeneratorRequest::new();
+    request.file_to_generate = files.iter().map(|f| f.to_string()).collect();
+    request.proto_file = parsed.file_descriptors;
+    request.set_parameter(parameter.to_string());
+
+    let buffer = protoc_gen_arkts::compile::compile(request.write_to_bytes().unwrap());
+    let response = CodeGeneratorResponse::parse_from_bytes(&buffer).unwrap();
+    assert!(!response.has_error(), "{}", response.error());
+    response
+        .file
+        .iter()
+        .map(|f| (f.name().to_string(), f.content().to_string()))
+        .collect()
+}
+
+pub fn file<'a>(files: &'a [(String, String)], name: &str) -> &'a str {
+    files
+        .iter()
+        .find(|(file, _)| file == name)
+        .map(|(_, content)| content.as_str())
+        .unwrap_or_else(|| panic!("{} was not generated", name))
+}
+
+pub fn file_names(files: &[(String, String)]) -> Vec<&str> {
+    files.iter().map(|(name, _)| name.as_str()).collect()
+}
+
+pub fn parse(name: &str, source: &str) -> Module {
+    let cm: Lrc<SourceMap> = Default::default();
+    let fm = cm.new_source_file(FileName::Custom(name.to_string()), source.to_string());
+    let mut errors = vec![];
+    let module = parse_file_as_module(
+        &fm,
+        Syntax::Typescript(TsConfig {
+            decorators: true,
+            ..Default::default()
+        }),
+        EsVersion::latest(),
+        None,
+        &mut errors,
+    )
+    .unwrap_or_else(|e| panic!("failed to parse {}: {:?}", name, e));
+    assert!(errors.is_empty(), "failed to parse {}: {:?}", name, errors);
+    module
+}
+
+// parses a generated file.
+pub fn module(files: &[(String, String)], name: &str) -> Module {
+    parse(name, file(files, name))
+}
+
+fn decls(module: &Module) -> impl Iterator<Item = &Decl> {
+    module.body.iter().filter_map(|item| match item {
+        ModuleItem::Stmt(Stmt::Decl(decl)) => Some(decl),
+        ModuleItem::ModuleDecl(ModuleDecl::ExportDecl(ExportDecl { decl, .. })) => Some(decl),
+        _ => None,
+    })
+}
+
+pub fn find_class<'a>(module: &'a Module, name: &str) -> Option<&'a Class> {
+    decls(module).find_map(|decl| match decl {
+        Decl::Class(class) if &*class.ident.sym == name => Some(&*class.class),
+        _ => None,
+    })
+}
+
+pub fn class<'a>(module: &'a Module, name: &str) -> &'a Class {
+    find_class(module, name).unwrap_or_else(|| panic!("missing class {}", name))
+}
+
+pub fn interface<'a>(module: &'a Module, name: &str) -> &'a TsInterfaceDecl {
+    decls(module)
+        .find_map(|decl| match decl {
+            Decl::TsInterface(interface) if &*interface.id.sym == name => Some(&**interface),
+            _ => None,
+        })
+        .unwrap_or_else(|| panic!("missing interface {}", name))
+}
+
+pub fn type_alias<'a>(module: &'a Module, name: &str) -> &'a TsTypeAliasDecl {
+    decls(module)
+        .find_map(|decl| match decl {
+            Decl::TsTypeAlias(alias) if &*alias.id.sym == name => Some(&**alias),
+            _ => None,
+        })
+        .unwrap_or_else(|| panic!("missing type alias {}", name))
+}
+
+// the names of the top level functions, classes, interfaces, enums and variables.
+pub fn declared_names(module: &Module) -> Vec<String> {
+    decls(module)
+        .flat_map(|decl| match decl {
+            Decl::Class(class) => vec![class.ident.sym.to_string()],
+            Decl::Fn(function) => vec![function.ident.sym.to_string()],
+            Decl::TsInterface(interface) => vec![interface.id.sym.to_string()],
+            Decl::TsTypeAlias(alias) => vec![alias.id.sym.to_string()],
+            Decl::TsEnum(e) => vec![e.id.sym.to_string()],
+            Decl::Var(var) => var
+                .decls
+                .iter()
+                .filter_map(|d| d.name.as_ident().map(|ident| ident.id.sym.to_string()))
+                .collect(),
+            _ => vec![],
+        })
+        .collect()
+}
+
+fn prop_name(key: &PropName) -> String {
+    match key {
+        PropName::Ident(ident) => ident.sym.to_string(),
+        PropName::Str(s) => s.value.to_string(),
+        PropName::Num(n) => n.value.to_string(),
+        _ => print(key),
+    }
+}
+
+pub fn find_method<'a>(class: &'a Class, name: &str) -> Option<&'a ClassMethod> {
+    class.body.iter().find_map(|member| match member {
+        ClassMember::Method(method) if prop_name(&method.key) == name => Some(method),
+        _ => None,
+    })
+}
+
+pub fn method<'a>(class: &'a Class, name: &str) -> &'a ClassMethod {
+    find_method(class, name).unwrap_or_else(|| panic!("missing method {}", name))
+}
+
+// the members of a class in declaration order, like `private static fromJson_0` or `get root`.
+pub fn members(class: &Class) -> Vec<String> {
+    class
+        .body
+        .iter()
+        .filter_map(|member| match member {
+            ClassMember::Method(method) => {
+                let mut words = vec![];
+                if method.accessibility == Some(swc_ecma_ast::Accessibility::Private) {
+                    words.push("private".to_string());
+                }
+                if method.is_static {
+                    words.push("static".to_string());
+                }
+                match method.kind {
+                    swc_ecma_ast::MethodKind::Getter => words.push("get".to_string()),
+                    swc_ecma_ast::MethodKind::Setter => words.push("set".to_string()),
+                    _ => {}
+                }
+                words.push(prop_name(&method.key));
+                Some(words.join(" "))
+            }
+            ClassMember::ClassProp(prop) => {
+                let mut words = vec![];
+                if prop.accessibility == Some(swc_ecma_ast::Accessibility::Private) {
+                    words.push("private".to_string());
+                }
+                if prop.is_static {
+                    words.push("static".to_string());
+                }
+                words.push(prop_name(&prop.key));
+                Some(words.join(" "))
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+pub fn has_member(class: &Class, member: &str) -> bool {
+    members(class).iter().any(|m| m == member)
+}
+
+// the signature of a method as written in the source, like `toBinary(options?: Options): Uint8Array`.
+pub fn signature(method: &ClassMethod) -> String {
+    let params: Vec<String> = method.function.params.iter().map(print).collect();
+    let mut signature = format!("{}({})", prop_name(&method.key), params.join(", "));
+    if let Some(return_type) = &method.function.return_type {
+        signature += &format!(": {}", print(&*return_type.type_ann));
+    }
+    signature
+}
+
+pub fn method_signature(module: &Module, class_name: &str, method_name: &str) -> String {
+    signature(method(class(module, class_name), method_name))
+}
+
+// `name: type` of every property of an interface.
+pub fn interface_props(interface: &TsInterfaceDecl) -> Vec<String> {
+    interface
+        .body
+        .body
+        .iter()
+        .map(|element| {
+            let source = print(element);
+            source.trim_end_matches(';').to_string()
+        })
+        .collect()
+}
+
+// the imports of a module as `specifiers from "source"`.
+pub fn imports(module: &Module) -> Vec<String> {
+    module
+        .body
+        .iter()
+        .filter_map(|item| match item {
+            ModuleItem::ModuleDecl(ModuleDecl::Import(import)) => Some(import),
+            _ => None,
+        })
+        .map(|import| {
+            let specifiers: Vec<String> = import
+                .specifiers
+                .iter()
+                .map(|specifier| match specifier {
+                    ImportSpecifier::Namespace(ns) => format!("* as {}", ns.local.sym),
+                    ImportSpecifier::Default(default) => default.local.sym.to_string(),
+                    ImportSpecifier::Named(named) => match &named.imported {
+                        Some(ModuleExportName::Ident(imported)) => {
+                            format!("{} as {}", imported.sym, named.local.sym)
+                        }
+                        _ => named.local.sym.to_string(),
+                    },
+                })
+                .collect();
+            format!("{} from \"{}\"", specifiers.join(", "), import.src.value)
+        })
+        .collect()
+}
+
+// the source of a syntax node.
+pub fn print<N: swc_ecma_codegen::Node>(node: &N) -> String {
+    let cm: Lrc<SourceMap> = Default::default();
+    let mut buf = vec![];
+    {
+        let mut emitter = Emitter {
+            cfg: Default::default(),
+            cm: cm.clone(),
+            comments: None,
+            wr: JsWriter::new(cm, "\n", &mut buf, None),
+        };
+        node.emit_with(&mut emitter).unwrap();
+    }
+    String::from_utf8(buf).unwrap().trim().to_string()
+}
+
+// the parameters of a constructor or function, without their types.
+pub fn param_names(params: &[Param]) -> Vec<String> {
+    params
+        .iter()
+        .map(|param| match &param.pat {
+            Pat::Ident(BindingIdent { id, .. }) => id.sym.to_string(),
+            pat => print(pat),
+        })
+        .collect()
+}
+
+pub fn constructor_params(class: &Class) -> Vec<String> {
+    class
+        .body
+        .iter()
+        .find_map(|member| match member {
+            ClassMember::Constructor(constructor) => Some(
+                constructor
+                    .params
+                    .iter()
+                    .map(|param| match param {
+                        ParamOrTsParamProp::Param(param) => print(param),
+                        ParamOrTsParamProp::TsParamProp(prop) => print(prop),
+                    })
+                    .collect(),
+            ),
+            _ => None,
+        })
+        .unwrap_or_default()
+}
+
+pub fn import_sources(module: &Module) -> Vec<String> {
+    module
+        .body
+        .iter()
+        .filter_map(|item| match item {
+            ModuleItem::ModuleDecl(ModuleDecl::Import(ImportDecl { src, .. })) => {
+                Some(src.value.to_string())
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+fn node_available() -> bool {
+    Command::new("node")
+        .arg("--version")
+        .output()
+        .map(|output| output.status.success())
+        .unwrap_or(false)
+}
+
+fn scratch_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!(
+        "protoc_gen_arkts_{}_{}",
+        name,
+        std::process::id()
+    ));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+fn write(path: &Path, content: &str) {
+    std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+    std::fs::write(path, content).unwrap();
+}
+
+// writes the output of generate_files with the types stripped, next to stand-ins of the
+// google-protobuf and js-base64 packages, and runs the script with node. The script imports
+// the generated files as `./<name>.mjs` and fails through node:assert. Returns what the
+// script printed, or None when node is not installed.
+pub fn run_node(name: &str, files: &[&str], parameter: &str, script: &str) -> Option<String> {
+    if !node_available() {
+        eprintln!("skipping {}: node is not installed", name);
+        return None;
+    }
+    let dir = scratch_dir(name);
+    for (file, content) in generate_files(files, parameter) {
+        let js = strip::strip(&file, &content);
+        write(&dir.join(file.replace(".ets", ".mjs")), &js);
+    }
+    for (package, source) in [
+        ("google-protobuf", include_str!("../js/google-protobuf.mjs")),
+        ("js-base64", include_str!("../js/js-base64.mjs")),
+    ] {
+        let package_dir = dir.join("node_modules").join(package);
+        write(
+            &package_dir.join("package.json"),
+            &format!("{{\"name\": \"{}\", \"type\": \"module\", \"main\": \"index.mjs\"}}", package),
+        );
+        write(&package_dir.join("index.mjs"), source);
+    }
+    write(&dir.join("main.mjs"), script);
+
+    let output = Command::new("node")
+        .arg("main.mjs")
+        .current_dir(&dir)
+        .output()
+        .expect("failed to run node");
+    let stdout = String::from_utf8_lossy(&output.stdout).to_string();
+    assert!(
+        output.status.success(),
+        "{} failed, the output is kept in {}\n{}{}",
+        name,
+        dir.display(),
+        stdout,
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let _ = std::fs::remove_dir_all(&dir);
+    Some(stdout)
+}
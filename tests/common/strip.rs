@@ -0,0 +1,265 @@
+// turns the generated ArkTS into JavaScript node can run: removes the type annotations,
+// interfaces, type aliases and the imports only used as types, lowers enums to objects and
+// points relative imports at the `.mjs` files the runner writes.
+
+use std::collections::HashSet;
+
+use swc_common::{sync::Lrc, FileName, SourceMap, DUMMY_SP};
+use swc_ecma_ast::{
+    BindingIdent, Class, ClassMember, Decl, EsVersion, ExportDecl, Expr, Ident, ImportDecl, ImportSpecifier, KeyValueProp,
+    Lit, Module, ModuleDecl, ModuleItem, Number, ObjectLit, Param, Pat, PatOrExpr, Prop, PropName,
+    PropOrSpread, Stmt, Str, TsEnumMemberId, TsTypeAnn, TsTypeParamDecl, TsTypeParamInstantiation,
+    UnaryExpr, UnaryOp, VarDecl, VarDeclKind, VarDeclarator,
+};
+use swc_ecma_codegen::{text_writer::JsWriter, Emitter};
+use swc_ecma_parser::{parse_file_as_module, Syntax, TsConfig};
+use swc_ecma_visit::{Visit, VisitMut, VisitMutWith, VisitWith};
+
+struct Strip;
+
+// the identifiers referenced outside of the imports.
+#[derive(Default)]
+struct Used(HashSet<String>);
+
+impl Visit for Used {
+    fn visit_import_decl(&mut self, _: &ImportDecl) {}
+
+    fn visit_ident(&mut self, ident: &Ident) {
+        self.0.insert(ident.sym.to_string());
+    }
+}
+
+// drops the named imports which were only used as types, like typescript elides them.
+fn elide_type_imports(module: &mut Module) {
+    let mut used = Used::default();
+    module.visit_with(&mut used);
+    module.body.retain_mut(|item| {
+        let ModuleItem::ModuleDecl(ModuleDecl::Import(import)) = item else {
+            return true;
+        };
+        if import.specifiers.is_empty() {
+            return true;
+        }
+        import.specifiers.retain(|specifier| match specifier {
+            ImportSpecifier::Named(named) => used.0.contains(&*named.local.sym),
+            _ => true,
+        });
+        !import.specifiers.is_empty()
+    });
+}
+
+fn unwrap_ts(expr: &mut Expr) {
+    loop {
+        let inner = match expr {
+            Expr::TsAs(e) => e.expr.clone(),
+            Expr::TsNonNull(e) => e.expr.clone(),
+            Expr::TsTypeAssertion(e) => e.expr.clone(),
+            Expr::TsConstAssertion(e) => e.expr.clone(),
+            Expr::TsSatisfies(e) => e.expr.clone(),
+            Expr::TsInstantiation(e) => e.expr.clone(),
+            _ => return,
+        };
+        *expr = *inner;
+    }
+}
+
+fn is_type_decl(decl: &Decl) -> bool {
+    match decl {
+        Decl::TsInterface(_) | Decl::TsTypeAlias(_) => true,
+        Decl::Class(class) => class.declare,
+        Decl::Fn(function) => function.declare,
+        Decl::Var(var) => var.declare,
+        _ => false,
+    }
+}
+
+// { A: 0, B: 1 }, the reverse mapping of numeric enums is not used by the generated code.
+fn lower_enum(decl: &mut Decl) {
+    let Decl::TsEnum(e) = decl else { return };
+    let mut props = vec![];
+    let mut next = 0f64;
+    for member in &e.members {
+        let key = match &member.id {
+            TsEnumMemberId::Ident(ident) => ident.sym.to_string(),
+            TsEnumMemberId::Str(s) => s.value.to_string(),
+        };
+        let value = match member.init.as_deref() {
+            Some(Expr::Lit(Lit::Num(n))) => {
+                next = n.value + 1.0;
+                Expr::Lit(Lit::Num(n.clone()))
+            }
+            Some(Expr::Unary(UnaryExpr { op: UnaryOp::Minus, arg, .. })) => {
+                if let Expr::Lit(Lit::Num(n)) = &**arg {
+                    next = -n.value + 1.0;
+                }
+                member.init.as_deref().unwrap().clone()
+            }
+            Some(init) => init.clone(),
+            None => {
+                next += 1.0;
+                Expr::Lit(Lit::Num(Number { span: DUMMY_SP, value: next - 1.0, raw: None }))
+            }
+        };
+        props.push(PropOrSpread::Prop(Box::new(Prop::KeyValue(KeyValueProp {
+            key: PropName::Str(Str { span: DUMMY_SP, value: key.into(), raw: None }),
+            value: Box::new(value),
+        }))));
+    }
+    *decl = Decl::Var(Box::new(VarDecl {
+        span: DUMMY_SP,
+        kind: VarDeclKind::Const,
+        declare: false,
+        decls: vec![VarDeclarator {
+            span: DUMMY_SP,
+            name: Pat::Ident(BindingIdent { id: e.id.clone(), type_ann: None }),
+            init: Some(Box::new(Expr::Object(ObjectLit { span: DUMMY_SP, props }))),
+            definite: false,
+        }],
+    }));
+}
+
+impl VisitMut for Strip {
+    fn visit_mut_expr(&mut self, expr: &mut Expr) {
+        unwrap_ts(expr);
+        expr.visit_mut_children_with(self);
+    }
+
+    fn visit_mut_pat_or_expr(&mut self, target: &mut PatOrExpr) {
+        match target {
+            PatOrExpr::Expr(expr) => unwrap_ts(expr),
+            PatOrExpr::Pat(pat) => {
+                if let Pat::Expr(expr) = &mut **pat {
+                    unwrap_ts(expr);
+                }
+            }
+        }
+        target.visit_mut_children_with(self);
+    }
+
+    fn visit_mut_binding_ident(&mut self, ident: &mut BindingIdent) {
+        ident.type_ann = None;
+        ident.id.optional = false;
+    }
+
+    fn visit_mut_opt_ts_type_ann(&mut self, ann: &mut Option<Box<TsTypeAnn>>) {
+        *ann = None;
+    }
+
+    fn visit_mut_opt_ts_type_param_decl(&mut self, params: &mut Option<Box<TsTypeParamDecl>>) {
+        *params = None;
+    }
+
+    fn visit_mut_opt_ts_type_param_instantiation(
+        &mut self,
+        params: &mut Option<Box<TsTypeParamInstantiation>>,
+    ) {
+        *params = None;
+    }
+
+    fn visit_mut_class(&mut self, class: &mut Class) {
+        class.implements.clear();
+        class.super_type_params = None;
+        class.decorators.clear();
+        class.visit_mut_children_with(self);
+    }
+
+    fn visit_mut_class_members(&mut self, members: &mut Vec<ClassMember>) {
+        members.retain(|member| match member {
+            ClassMember::TsIndexSignature(_) => false,
+            ClassMember::ClassProp(prop) => !prop.declare,
+            ClassMember::Method(method) => method.function.body.is_some(),
+            _ => true,
+        });
+        for member in members.iter_mut() {
+            match member {
+                ClassMember::Method(method) => {
+                    method.accessibility = None;
+                    method.is_optional = false;
+                    method.is_override = false;
+                    method.is_abstract = false;
+                }
+                ClassMember::ClassProp(prop) => {
+                    prop.accessibility = None;
+                    prop.is_optional = false;
+                    prop.readonly = false;
+                    prop.definite = false;
+                    prop.is_override = false;
+                    prop.decorators.clear();
+                    if let PropName::Ident(ident) = &mut prop.key {
+                        ident.optional = false;
+                    }
+                }
+                ClassMember::Constructor(constructor) => constructor.accessibility = None,
+                _ => {}
+            }
+        }
+        members.visit_mut_children_with(self);
+    }
+
+    fn visit_mut_module_items(&mut self, items: &mut Vec<ModuleItem>) {
+        items.retain(|item| match item {
+            ModuleItem::Stmt(Stmt::Decl(decl)) => !is_type_decl(decl),
+            ModuleItem::ModuleDecl(ModuleDecl::ExportDecl(ExportDecl { decl, .. })) => {
+                !is_type_decl(decl)
+            }
+            _ => true,
+        });
+        items.visit_mut_children_with(self);
+    }
+
+    fn visit_mut_import_decl(&mut self, import: &mut ImportDecl) {
+        import.specifiers.retain(|specifier| match specifier {
+            ImportSpecifier::Named(named) => !named.is_type_only,
+            _ => true,
+        });
+        if import.src.value.starts_with('.') {
+            *import.src = Str::from(format!("{}.mjs", import.src.value));
+        }
+    }
+
+    fn visit_mut_decl(&mut self, decl: &mut Decl) {
+        lower_enum(decl);
+        decl.visit_mut_children_with(self);
+    }
+
+    fn visit_mut_stmts(&mut self, stmts: &mut Vec<Stmt>) {
+        stmts.retain(|stmt| !matches!(stmt, Stmt::Decl(decl) if is_type_decl(decl)));
+        stmts.visit_mut_children_with(self);
+    }
+
+    fn visit_mut_param(&mut self, param: &mut Param) {
+        param.decorators.clear();
+        param.visit_mut_children_with(self);
+    }
+}
+
+pub fn strip(name: &str, source: &str) -> String {
+    let cm: Lrc<SourceMap> = Default::default();
+    let fm = cm.new_source_file(FileName::Custom(name.to_string()), source.to_string());
+    let mut errors = vec![];
+    let mut module = parse_file_as_module(
+        &fm,
+        Syntax::Typescript(TsConfig {
+            decorators: true,
+            ..Default::default()
+        }),
+        EsVersion::latest(),
+        None,
+        &mut errors,
+    )
+    .unwrap_or_else(|e| panic!("failed to parse {}: {:?}", name, e));
+    module.visit_mut_with(&mut Strip);
+    elide_type_imports(&mut module);
+
+    let mut buf = vec![];
+    {
+        let mut emitter = Emitter {
+            cfg: Default::default(),
+            cm: cm.clone(),
+            comments: None,
+            wr: JsWriter::new(cm, "\n", &mut buf, None),
+        };
+        emitter.emit_module(&module).unwrap();
+    }
+    String::from_utf8(buf).unwrap()
+}
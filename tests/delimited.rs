@@ -0,0 +1,79 @@
+mod common;
+
+use common::{class, file_names, generate, has_member, method_signature, module, run_node, INPUTS, WELL_KNOWN};
+
+#[test]
+fn delimited_support_is_not_generated_by_default() {
+    let files = generate("");
+    assert!(!file_names(&files).contains(&"delimited_support.ets"));
+    let gen = module(&files, "gen.ets");
+    let common = class(&gen, "struct_pkg_Common");
+    assert!(!has_member(common, "toBinaryDelimited"));
+    assert!(!has_member(common, "fromBinaryDelimited"));
+}
+
+#[test]
+fn delimited_support_module_is_emitted_once() {
+    let files = generate("with_delimited=true");
+    let names = file_names(&files);
+    assert_eq!(names.iter().filter(|name| **name == "delimited_support.ets").count(), 1);
+    let support = module(&files, "delimited_support.ets");
+    assert!(common::find_class(&support, "DelimitedReader").is_some());
+
+    let gen = module(&files, "gen.ets");
+    assert_eq!(
+        method_signature(&gen, "struct_pkg_Common", "toBinaryDelimited"),
+        "toBinaryDelimited(options?: binary_support.BinaryWriteOptions): Uint8Array"
+    );
+    assert_eq!(
+        method_signature(&gen, "struct_pkg_Common", "fromBinaryDelimited"),
+        "fromBinaryDelimited(bytes: Uint8Array): struct_pkg_Common"
+    );
+}
+
+#[test]
+fn delimited_messages_are_read_back_from_chunks() {
+    let script = r#"
+import assert from "node:assert/strict";
+import { struct_pkg_Common } from "./gen.mjs";
+import { DelimitedReader } from "./delimited_support.mjs";
+
+const messages = ["a", "b".repeat(200), "c"].map((id) => {
+  const common = new struct_pkg_Common();
+  common.common_id = id;
+  return common;
+});
+const frames = messages.map((m) => m.toBinaryDelimited());
+const stream = Buffer.concat(frames);
+
+assert.equal(struct_pkg_Common.fromBinaryDelimited(stream).common_id, "a");
+assert.deepEqual(
+  DelimitedReader.readAll(struct_pkg_Common, stream).map((m) => m.common_id),
+  messages.map((m) => m.common_id)
+);
+
+// split inside of the length prefix of the second message and inside of its body.
+const reader = new DelimitedReader(struct_pkg_Common);
+const ids = [];
+for (const chunk of [stream.subarray(0, frames[0].length + 1), stream.subarray(frames[0].length + 1, 100), stream.subarray(100)]) {
+  ids.push(...reader.push(chunk).map((m) => m.common_id));
+}
+reader.end();
+assert.deepEqual(ids, messages.map((m) => m.common_id));
+
+// one byte at a time, the split messages are joined only once they are complete.
+const byteReader = new DelimitedReader(struct_pkg_Common);
+const byteIds = [];
+for (let i = 0; i < stream.length; i++) {
+  byteIds.push(...byteReader.push(stream.subarray(i, i + 1)).map((m) => m.common_id));
+}
+byteReader.end();
+assert.deepEqual(byteIds, messages.map((m) => m.common_id));
+
+assert.throws(() => DelimitedReader.readAll(struct_pkg_Common, stream.subarray(0, stream.length - 1)));
+assert.throws(() => DelimitedReader.readAll(struct_pkg_Common, new Uint8Array([0xff, 0xff, 0xff, 0xff, 0x0f])), /exceeds/);
+assert.throws(() => new DelimitedReader(struct_pkg_Common).push(new Uint8Array([0x80, 0x80, 0x80, 0x80, 0x80])), /exceeds/);
+"#;
+    let inputs: Vec<&str> = INPUTS.iter().chain(WELL_KNOWN.iter()).copied().collect();
+    run_node("delimited_messages", &inputs, "with_delimited=true", script);
+}
@@ -0,0 +1,94 @@
+mod common;
+
+use common::{file_names, generate, method_signature, module, run_node, INPUTS, WELL_KNOWN};
+
+#[test]
+fn binary_support_module_is_emitted_once() {
+    let files = generate("");
+    assert_eq!(file_names(&files).iter().filter(|name| **name == "binary_support.ets").count(), 1);
+
+    let strict = module(&files, "strict.ets");
+    assert_eq!(
+        method_signature(&strict, "strict_pkg_Node", "toBinary"),
+        "toBinary(options?: binary_support.BinaryWriteOptions): Uint8Array"
+    );
+}
+
+const BUILD_SCRIPT: &str = r#"
+import assert from "node:assert/strict";
+import { strict_pkg_Node, strict_pkg_Node_Leaf } from "./strict.mjs";
+import { struct_pkg_Common } from "./gen.mjs";
+
+function build(reverse) {
+  const node = new strict_pkg_Node();
+  const leaves = [[-1n, 1], [2n, 2], [1n << 40n, 3]];
+  const flags = [[false, "no"], [true, "yes"]];
+  // code point order puts the surrogate pair after U+FFFD, utf-16 code unit order does not.
+  const chunks = [["a", 1], ["b", 2], ["\uFFFD", 3], ["\u{1F600}", 4]];
+  for (const [k, v] of reverse ? leaves.reverse() : leaves) {
+    const leaf = new strict_pkg_Node_Leaf();
+    leaf.payload = new Uint8Array([v]);
+    node.leaves.set(k, leaf);
+  }
+  for (const [k, v] of reverse ? flags.reverse() : flags) node.flags.set(k, v);
+  for (const [k, v] of reverse ? chunks.reverse() : chunks) node.chunks.set(k, new Uint8Array([v]));
+  node.common = new struct_pkg_Common();
+  return node;
+}
+"#;
+
+#[test]
+fn map_entries_are_ordered_when_deterministic() {
+    let script = format!(
+        "{}{}",
+        BUILD_SCRIPT,
+        r#"
+const forward = build(false);
+const backward = build(true);
+assert.notDeepEqual(forward.toBinary(), backward.toBinary());
+assert.deepEqual(forward.toBinary({ deterministic: true }), backward.toBinary({ deterministic: true }));
+// the keys in the order they were added are already sorted.
+assert.deepEqual(forward.toBinary({ deterministic: true }), forward.toBinary());
+
+const decoded = strict_pkg_Node.fromBinary(backward.toBinary({ deterministic: true }));
+assert.deepEqual([...decoded.leaves.keys()], [-1n, 2n, 1n << 40n]);
+assert.deepEqual([...decoded.flags.keys()], [false, true]);
+assert.deepEqual([...decoded.chunks.keys()], ["a", "b", "\uFFFD", "\u{1F600}"]);
+assert.deepEqual(Object.keys(backward.toJson({ deterministic: true }).chunks), ["a", "b", "\uFFFD", "\u{1F600}"]);
+"#
+    );
+    let inputs: Vec<&str> = INPUTS.iter().chain(WELL_KNOWN.iter()).copied().collect();
+    run_node("deterministic", &inputs, "", &script);
+}
+
+#[test]
+fn to_binary_into_is_deterministic_like_to_binary() {
+    let script = format!(
+        "{}{}",
+        BUILD_SCRIPT,
+        r#"
+const forward = build(false);
+const backward = build(true);
+for (const node of [forward, backward]) {
+  assert.deepEqual(node.toBinaryInto(), node.toBinary());
+  const options = { deterministic: true };
+  const bytes = node.toBinary(options);
+  assert.equal(node.byteSize(options), bytes.length);
+  assert.deepEqual(node.toBinaryInto(undefined, undefined, options), bytes);
+}
+assert.deepEqual(
+  backward.toBinaryInto(undefined, undefined, { deterministic: true }),
+  forward.toBinaryInto(undefined, undefined, { deterministic: true })
+);
+
+// a lazy field read with its children before its payload is written back as it is, unless deterministic.
+const unordered = new Uint8Array([0x92, 0x01, 0x05, 0x12, 0x00, 0x0a, 0x01, 0x07]);
+const ordered = new Uint8Array([0x92, 0x01, 0x05, 0x0a, 0x01, 0x07, 0x12, 0x00]);
+assert.deepEqual(strict_pkg_Node.fromBinary(unordered).toBinaryInto(), unordered);
+assert.deepEqual(strict_pkg_Node.fromBinary(unordered).toBinary({ deterministic: true }), ordered);
+assert.deepEqual(strict_pkg_Node.fromBinary(unordered).toBinaryInto(undefined, undefined, { deterministic: true }), ordered);
+"#
+    );
+    let inputs: Vec<&str> = INPUTS.iter().chain(WELL_KNOWN.iter()).copied().collect();
+    run_node("deterministic_sized", &inputs, "with_byte_size=true,lazy_fields=true", &script);
+}
@@ -0,0 +1,135 @@
+mod common;
+
+use common::{class, file_names, generate_files, has_member, method_signature, module, run_node};
+
+const PROFILE: [&str; 1] = ["field_mask/profile.proto"];
+
+#[test]
+fn field_mask_members_are_generated_with_the_option() {
+    let files = generate_files(&PROFILE, "with_field_mask=true");
+    let profile = module(&files, "field_mask/profile.ets");
+    let class = class(&profile, "masks_Profile");
+    assert!(has_member(class, "static validateMaskPath"));
+    assert!(has_member(class, "mergeFromWithMask"));
+    assert!(has_member(class, "pruneWithMask"));
+    assert_eq!(
+        method_signature(&profile, "masks_Profile", "validateMaskPath"),
+        "validateMaskPath(path: string): boolean"
+    );
+    assert_eq!(
+        method_signature(&profile, "masks_Profile", "mergeFromWithMask"),
+        "mergeFromWithMask(src: masks_Profile, mask: google_protobuf_field_mask.google_protobuf_FieldMask)"
+    );
+    assert_eq!(
+        method_signature(&profile, "masks_Profile", "pruneWithMask"),
+        "pruneWithMask(mask: google_protobuf_field_mask.google_protobuf_FieldMask)"
+    );
+}
+
+#[test]
+fn field_mask_proto_is_bundled() {
+    let files = generate_files(&PROFILE, "with_field_mask=true");
+    assert!(file_names(&files).contains(&"google/protobuf/field_mask.ets"));
+}
+
+#[test]
+fn field_mask_members_are_not_generated_by_default() {
+    let files = generate_files(&PROFILE, "");
+    let profile = module(&files, "field_mask/profile.ets");
+    let class = class(&profile, "masks_Profile");
+    assert!(!has_member(class, "static validateMaskPath"));
+    assert!(!has_member(class, "mergeFromWithMask"));
+    assert!(!has_member(class, "pruneWithMask"));
+    assert!(!file_names(&files).contains(&"google/protobuf/field_mask.ets"));
+}
+
+#[test]
+fn field_masks_merge_and_prune() {
+    let script = r#"
+import assert from "node:assert/strict";
+import { masks_Profile } from "./field_mask/profile.mjs";
+import { google_protobuf_FieldMask as FieldMask } from "./google/protobuf/field_mask.mjs";
+
+const mask = (...paths) => FieldMask.fromPaths(paths);
+const profile = () => masks_Profile.create({
+  name: "ann",
+  age: 30,
+  address: { city: "Oslo", zip: "0150" },
+  tags: ["a"],
+  scores: new Map([["x", 1]]),
+  email: "ann@example.com",
+});
+
+assert.ok(masks_Profile.validateMaskPath("address.city"));
+assert.ok(!masks_Profile.validateMaskPath("address.street"));
+assert.ok(!masks_Profile.validateMaskPath("name.first"));
+assert.ok(!masks_Profile.validateMaskPath("unknown"));
+
+// scalars in the mask are replaced, even by the default value.
+const target = profile();
+target.mergeFromWithMask(masks_Profile.create({ name: "", age: 31 }), mask("name", "age"));
+assert.equal(target.name, "");
+assert.equal(target.age, 31);
+assert.equal(target.address.city, "Oslo");
+
+// a nested path merges a single field of the message.
+const nested = profile();
+nested.mergeFromWithMask(masks_Profile.create({ address: { city: "Bergen" } }), mask("address.city"));
+assert.deepEqual(nested.address.toJson(), { city: "Bergen", zip: "0150" });
+const created = new masks_Profile();
+created.mergeFromWithMask(masks_Profile.create({ address: { zip: "5003" } }), mask("address.zip"));
+assert.deepEqual(created.address.toJson(), { zip: "5003" });
+
+// a message in the last position is merged as a whole.
+const whole = profile();
+whole.mergeFromWithMask(masks_Profile.create({ address: { zip: "5003" } }), mask("address"));
+assert.deepEqual(whole.address.toJson(), { city: "Oslo", zip: "5003" });
+
+// repeated fields are appended and maps are merged per key.
+const lists = profile();
+lists.mergeFromWithMask(masks_Profile.create({ tags: ["b"], scores: new Map([["x", 2], ["y", 3]]) }), mask("tags", "scores"));
+assert.deepEqual(lists.tags, ["a", "b"]);
+assert.deepEqual([...lists.scores.entries()], [["x", 2], ["y", 3]]);
+
+// an unset oneof member in the source clears it.
+const contact = profile();
+contact.mergeFromWithMask(masks_Profile.create({ phone: "123" }), mask("email"));
+assert.equal(contact.email, undefined);
+assert.equal(contact.phone, undefined);
+contact.mergeFromWithMask(masks_Profile.create({ phone: "123" }), mask("phone"));
+assert.equal(contact.phone, "123");
+
+assert.throws(() => profile().mergeFromWithMask(new masks_Profile(), mask("address.street")), /invalid field mask path address\.street for masks\.Profile/);
+
+// pruning keeps the fields in the mask and resets the others.
+const pruned = profile().pruneWithMask(mask("name", "address.zip", "email"));
+assert.deepEqual(pruned.toJson(), { name: "ann", address: { zip: "0150" }, email: "ann@example.com" });
+const empty = profile().pruneWithMask(mask());
+assert.deepEqual(empty.toJson(), {});
+assert.equal(empty.address, undefined);
+assert.throws(() => profile().pruneWithMask(mask("tags.first")), /invalid field mask path/);
+"#;
+    run_node("field_masks", &PROFILE, "with_field_mask=true", script);
+}
+
+const JSON_SCRIPT: &str = r#"
+import assert from "node:assert/strict";
+import { google_protobuf_FieldMask as FieldMask } from "./google/protobuf/field_mask.mjs";
+
+const mask = FieldMask.fromPaths(["name", "address.zip_code", "user_2fa"]);
+assert.equal(mask.toJson(), "name,address.zipCode,user2fa");
+assert.deepEqual(FieldMask.fromJson("name,address.zipCode").paths, ["name", "address.zip_code"]);
+assert.deepEqual(FieldMask.fromJson("").paths, []);
+assert.throws(() => FieldMask.fromJson("zip_code"), /must be lowerCamelCase/);
+assert.throws(() => FieldMask.fromPaths(["zipCode"]).toJson(), /is irreversible/);
+"#;
+
+#[test]
+fn field_masks_convert_to_json() {
+    run_node("field_mask_json", &PROFILE, "with_field_mask=true", JSON_SCRIPT);
+}
+
+#[test]
+fn strict_field_masks_convert_to_json() {
+    run_node("field_mask_json_strict", &PROFILE, "with_field_mask=true,arkts_strict=true", JSON_SCRIPT);
+}
@@ -0,0 +1,203 @@
+mod common;
+
+use std::path::Path;
+
+use common::{generate_files, generate_files_in, imports, module, run_node, WELL_KNOWN};
+
+const SHOP: [&str; 4] = [
+    "imports/shop/order.proto",
+    "imports/shop/money.proto",
+    "imports/shop/money-v2.proto",
+    "imports/shop/money_v2.proto",
+];
+
+fn shop_inputs() -> Vec<&'static str> {
+    SHOP.iter().chain(WELL_KNOWN.iter()).copied().collect()
+}
+
+fn order_imports(parameter: &str) -> Vec<String> {
+    let files = generate_files(&shop_inputs(), parameter);
+    imports(&module(&files, "imports/shop/order.ets"))
+        .into_iter()
+        .filter(|import| !import.contains("_support\""))
+        .collect()
+}
+
+#[test]
+fn namespace_imports_are_named_after_the_proto_path() {
+    assert_eq!(
+        order_imports(""),
+        [
+            "* as google_protobuf_timestamp from \"./../../google/protobuf/timestamp\"",
+            "* as imports_shop_money from \"./money\"",
+            // money-v2 and money_v2 derive the same alias, the second one is numbered.
+            "* as imports_shop_money_v2 from \"./money-v2\"",
+            "* as imports_shop_money_v2_1 from \"./money_v2\"",
+            "BinaryReader, BinaryWriter from \"google-protobuf\"",
+        ]
+    );
+}
+
+#[test]
+fn named_imports_import_the_referenced_symbols() {
+    assert_eq!(
+        order_imports("named_imports=true"),
+        [
+            "google_protobuf_Timestamp, google_protobuf_TimestampInit from \"./../../google/protobuf/timestamp\"",
+            "shop_common_Money, shop_common_MoneyJson, shop_common_MoneyInit from \"./money\"",
+            "shop_v2_Money, shop_v2_MoneyJson, shop_v2_MoneyInit from \"./money-v2\"",
+            "shop_v3_Money, shop_v3_MoneyJson, shop_v3_MoneyInit from \"./money_v2\"",
+            "BinaryReader, BinaryWriter from \"google-protobuf\"",
+        ]
+    );
+}
+
+fn holder_imports(parameter: &str) -> Vec<String> {
+    let files = generate_files_in(
+        Path::new("tests/imports/clash"),
+        &["holder.proto", "BinaryReader.proto", "toUint8Array.proto", "collections.proto"],
+        parameter,
+    );
+    imports(&module(&files, "holder.ets"))
+        .into_iter()
+        .filter(|import| !import.contains("_support\""))
+        .collect()
+}
+
+#[test]
+fn namespace_imports_do_not_shadow_the_runtime_named_imports() {
+    assert_eq!(
+        holder_imports(""),
+        [
+            "* as BinaryReader_1 from \"./BinaryReader\"",
+            "* as collections_1 from \"./collections\"",
+            "* as toUint8Array_1 from \"./toUint8Array\"",
+            "BinaryReader, BinaryWriter from \"google-protobuf\"",
+            "toUint8Array, fromUint8Array from \"js-base64\"",
+        ]
+    );
+    let sendable = holder_imports("with_sendable=true");
+    assert!(sendable.contains(&"* as collections_1 from \"./collections\"".to_string()));
+    assert!(sendable.contains(&"collections from \"@kit.ArkTS\"".to_string()), "{:?}", sendable);
+}
+
+#[test]
+fn imported_types_resolve_at_runtime() {
+    let script = r#"
+import assert from "node:assert/strict";
+import { shop_Order } from "./imports/shop/order.mjs";
+import { shop_common_Money } from "./imports/shop/money.mjs";
+import { shop_v3_Money } from "./imports/shop/money_v2.mjs";
+
+const order = new shop_Order();
+order.total = new shop_common_Money();
+order.total.units = 12n;
+order.tip = new shop_v3_Money();
+order.tip.micros = -5n;
+order.items = [order.total];
+
+const decoded = shop_Order.fromBinary(order.toBinary());
+assert.equal(decoded.total.units, 12n);
+assert.equal(decoded.tip.micros, -5n);
+assert.ok(decoded.items[0] instanceof shop_common_Money);
+assert.deepEqual(shop_Order.fromJson(order.toJson()).toBinary(), order.toBinary());
+"#;
+    for parameter in ["", "named_imports=true"] {
+        run_node("imported_types", &shop_inputs(), parameter, script);
+    }
+}
+
+#[test]
+fn module_mappings_match_paths_and_packages() {
+    assert_eq!(
+        order_imports(
+            "module_mapping=imports/shop/money.proto=@shop/money,module_mapping=shop.v2=@shop/v2,module_mapping=shop=@shop/all,module_mapping=google/protobuf/=@ourco/wkt"
+        ),
+        [
+            "* as ourco_wkt from \"@ourco/wkt\"",
+            // shop.v3 falls back to the shorter package key.
+            "* as shop_all from \"@shop/all\"",
+            "* as shop_money from \"@shop/money\"",
+            "* as shop_v2 from \"@shop/v2\"",
+            "BinaryReader, BinaryWriter from \"google-protobuf\"",
+        ]
+    );
+}
+
+#[test]
+fn the_longest_module_mapping_wins() {
+    assert_eq!(
+        order_imports(
+            "module_mapping=imports/shop/=@shop/types,module_mapping=imports/shop/money.proto=@shop/money,named_imports=true"
+        ),
+        [
+            "google_protobuf_Timestamp, google_protobuf_TimestampInit from \"./../../google/protobuf/timestamp\"",
+            "shop_common_Money, shop_common_MoneyJson, shop_common_MoneyInit from \"@shop/money\"",
+            "shop_v2_Money, shop_v3_Money, shop_v2_MoneyJson, shop_v3_MoneyJson, shop_v2_MoneyInit, shop_v3_MoneyInit from \"@shop/types\"",
+            "BinaryReader, BinaryWriter from \"google-protobuf\"",
+        ]
+    );
+}
+
+#[test]
+fn module_mappings_are_read_from_a_file() {
+    let path = std::env::temp_dir().join(format!("protoc_gen_arkts_mappings_{}.txt", std::process::id()));
+    std::fs::write(&path, "# shared types\nimports/shop/ = @shop/types\n").unwrap();
+    let from_file = order_imports(&format!("module_mapping_file={}", path.display()));
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(from_file, order_imports("module_mapping=imports/shop/=@shop/types"));
+    assert!(from_file.contains(&"* as shop_types from \"@shop/types\"".to_string()));
+}
+
+#[test]
+fn wkt_package_skips_the_well_known_types() {
+    let files = generate_files(&shop_inputs(), "wkt_package=@ourco/wkt,with_field_mask=true,with_descriptor=true");
+    let names = common::file_names(&files);
+    assert!(names.contains(&"imports/shop/order.ets"));
+    assert!(!names.iter().any(|name| name.starts_with("google/protobuf/")), "{:?}", names);
+
+    let order = imports(&module(&files, "imports/shop/order.ets"));
+    assert!(order.contains(&"* as ourco_wkt from \"@ourco/wkt\"".to_string()), "{:?}", order);
+    assert!(!order.iter().any(|import| import.contains("google/protobuf")), "{:?}", order);
+}
+
+#[test]
+fn module_mappings_take_precedence_over_wkt_package() {
+    assert_eq!(
+        order_imports("wkt_package=@ourco/wkt,module_mapping=google/protobuf/timestamp.proto=@ourco/time"),
+        [
+            "* as imports_shop_money from \"./money\"",
+            "* as imports_shop_money_v2 from \"./money-v2\"",
+            "* as imports_shop_money_v2_1 from \"./money_v2\"",
+            "* as ourco_time from \"@ourco/time\"",
+            "BinaryReader, BinaryWriter from \"google-protobuf\"",
+        ]
+    );
+}
+
+#[test]
+fn output_does_not_depend_on_the_input_order() {
+    let mut inputs: Vec<&str> = shop_inputs();
+    inputs.extend(common::INPUTS);
+    inputs.extend(["registry/envelope.proto", "registry/payload.proto", "enums/level.proto"]);
+    // a fixed shuffle, every input moves and the importers come before and after their imports.
+    let mut shuffled = inputs.clone();
+    shuffled.reverse();
+    shuffled.rotate_left(inputs.len() / 3);
+
+    for parameter in ["", "named_imports=true", "with_text_format=true,with_byte_size=true,with_delimited=true"] {
+        let first = generate_files(&inputs, parameter);
+        // files are generated concurrently, a second run in the same order is byte identical.
+        assert_eq!(generate_files(&inputs, parameter), first, "{}", parameter);
+
+        let mut sorted = first.clone();
+        sorted.sort();
+        let mut reordered = generate_files(&shuffled, parameter);
+        reordered.sort();
+        assert_eq!(reordered.len(), sorted.len(), "{}", parameter);
+        for ((name, content), (reordered_name, reordered_content)) in sorted.iter().zip(&reordered) {
+            assert_eq!(name, reordered_name, "{}", parameter);
+            assert!(content == reordered_content, "{} differs with the inputs shuffled ({})", name, parameter);
+        }
+    }
+}
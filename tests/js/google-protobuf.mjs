@@ -0,0 +1,293 @@
+// the subset of the google-protobuf BinaryWriter, BinaryReader, BinaryEncoder and BinaryDecoder
+// used by the generated code and the support modules,
+// written from the wire format spec so that the behavior tests check the generated code only.
+
+const WIRE_VARINT = 0;
+const WIRE_FIXED64 = 1;
+const WIRE_DELIMITED = 2;
+const WIRE_END_GROUP = 4;
+const WIRE_FIXED32 = 5;
+
+export class BinaryWriter {
+  constructor() {
+    this.bytes = [];
+    this.stack = [];
+  }
+
+  varint(value) {
+    let v = BigInt.asUintN(64, BigInt(value));
+    while (v >= 0x80n) {
+      this.bytes.push(Number(v & 0x7fn) | 0x80);
+      v >>= 7n;
+    }
+    this.bytes.push(Number(v));
+  }
+
+  tag(field, wireType) {
+    this.varint(field * 8 + wireType);
+  }
+
+  fixed(value, size, write) {
+    const view = new DataView(new ArrayBuffer(size));
+    write(view, value);
+    this.bytes.push(...new Uint8Array(view.buffer));
+  }
+
+  beginSubMessage(field) {
+    this.tag(field, WIRE_DELIMITED);
+    this.stack.push(this.bytes);
+    this.bytes = [];
+  }
+
+  endSubMessage() {
+    const body = this.bytes;
+    this.bytes = this.stack.pop();
+    this.varint(body.length);
+    this.bytes.push(...body);
+  }
+
+  getResultBuffer() {
+    return new Uint8Array(this.bytes);
+  }
+
+  writeBytes(field, value) {
+    this.tag(field, WIRE_DELIMITED);
+    this.varint(value.length);
+    this.bytes.push(...value);
+  }
+
+  writeString(field, value) {
+    this.writeBytes(field, new TextEncoder().encode(value));
+  }
+
+  writeVarint(field, value) {
+    this.tag(field, WIRE_VARINT);
+    this.varint(typeof value === "boolean" ? Number(value) : value);
+  }
+
+  writeZigzag(field, value) {
+    const v = BigInt(value);
+    this.tag(field, WIRE_VARINT);
+    this.varint((v << 1n) ^ (v >> 63n));
+  }
+
+  writeInt32(field, value) { this.writeVarint(field, value); }
+  writeInt64(field, value) { this.writeVarint(field, value); }
+  writeInt64String(field, value) { this.writeVarint(field, value); }
+  writeUint32(field, value) { this.writeVarint(field, value); }
+  writeUint64(field, value) { this.writeVarint(field, value); }
+  writeUint64String(field, value) { this.writeVarint(field, value); }
+  writeBool(field, value) { this.writeVarint(field, value); }
+  writeEnum(field, value) { this.writeVarint(field, value); }
+  writeSint32(field, value) { this.writeZigzag(field, value); }
+  writeSint64(field, value) { this.writeZigzag(field, value); }
+  writeSint64String(field, value) { this.writeZigzag(field, value); }
+
+  writeFixed32(field, value) {
+    this.tag(field, WIRE_FIXED32);
+    this.fixed(value, 4, (view, v) => view.setUint32(0, v, true));
+  }
+  writeSfixed32(field, value) {
+    this.tag(field, WIRE_FIXED32);
+    this.fixed(value, 4, (view, v) => view.setInt32(0, v, true));
+  }
+  writeFloat(field, value) {
+    this.tag(field, WIRE_FIXED32);
+    this.fixed(value, 4, (view, v) => view.setFloat32(0, v, true));
+  }
+  writeFixed64String(field, value) {
+    this.tag(field, WIRE_FIXED64);
+    this.fixed(value, 8, (view, v) => view.setBigUint64(0, BigInt.asUintN(64, BigInt(v)), true));
+  }
+  writeSfixed64String(field, value) {
+    this.tag(field, WIRE_FIXED64);
+    this.fixed(value, 8, (view, v) => view.setBigInt64(0, BigInt.asIntN(64, BigInt(v)), true));
+  }
+  writeDouble(field, value) {
+    this.tag(field, WIRE_FIXED64);
+    this.fixed(value, 8, (view, v) => view.setFloat64(0, v, true));
+  }
+
+  writePacked(field, values, write) {
+    if (values.length === 0) {
+      return;
+    }
+    this.beginSubMessage(field);
+    const bytes = this.bytes;
+    for (const value of values) {
+      const element = new BinaryWriter();
+      write.call(element, 0, value);
+      // drops the tag of field 0, a single byte.
+      bytes.push(...element.bytes.slice(1));
+    }
+    this.endSubMessage();
+  }
+
+  writePackedInt32(field, values) { this.writePacked(field, values, this.writeInt32); }
+  writePackedInt64(field, values) { this.writePacked(field, values, this.writeInt64); }
+  writePackedInt64String(field, values) { this.writePacked(field, values, this.writeInt64String); }
+  writePackedUint32(field, values) { this.writePacked(field, values, this.writeUint32); }
+  writePackedUint64String(field, values) { this.writePacked(field, values, this.writeUint64String); }
+  writePackedSint32(field, values) { this.writePacked(field, values, this.writeSint32); }
+  writePackedSint64String(field, values) { this.writePacked(field, values, this.writeSint64String); }
+  writePackedBool(field, values) { this.writePacked(field, values, this.writeBool); }
+  writePackedEnum(field, values) { this.writePacked(field, values, this.writeEnum); }
+  writePackedFixed32(field, values) { this.writePacked(field, values, this.writeFixed32); }
+  writePackedSfixed32(field, values) { this.writePacked(field, values, this.writeSfixed32); }
+  writePackedFloat(field, values) { this.writePacked(field, values, this.writeFloat); }
+  writePackedFixed64String(field, values) { this.writePacked(field, values, this.writeFixed64String); }
+  writePackedSfixed64String(field, values) { this.writePacked(field, values, this.writeSfixed64String); }
+  writePackedDouble(field, values) { this.writePacked(field, values, this.writeDouble); }
+}
+
+export class BinaryReader {
+  constructor(bytes) {
+    this.bytes = bytes;
+    this.pos = 0;
+    this.end = bytes.length;
+    this.field = 0;
+    this.wireType = 0;
+  }
+
+  varint() {
+    let value = 0n;
+    let shift = 0n;
+    for (;;) {
+      if (this.pos >= this.end) {
+        throw new Error("truncated varint");
+      }
+      const byte = this.bytes[this.pos++];
+      value |= BigInt(byte & 0x7f) << shift;
+      shift += 7n;
+      if ((byte & 0x80) === 0) {
+        return value;
+      }
+    }
+  }
+
+  fixed(size, read) {
+    const view = new DataView(this.bytes.buffer, this.bytes.byteOffset + this.pos, size);
+    this.pos += size;
+    return read(view);
+  }
+
+  nextField() {
+    if (this.pos >= this.end) {
+      return false;
+    }
+    const tag = Number(this.varint());
+    this.field = tag >>> 3;
+    this.wireType = tag & 7;
+    return true;
+  }
+
+  isEndGroup() { return this.wireType === WIRE_END_GROUP; }
+  isDelimited() { return this.wireType === WIRE_DELIMITED; }
+  getFieldNumber() { return this.field; }
+  getWireType() { return this.wireType; }
+
+  skipField() {
+    switch (this.wireType) {
+      case WIRE_VARINT: this.varint(); break;
+      case WIRE_FIXED64: this.pos += 8; break;
+      case WIRE_DELIMITED: this.pos += Number(this.varint()); break;
+      case WIRE_FIXED32: this.pos += 4; break;
+      default: throw new Error("unsupported wire type " + this.wireType);
+    }
+  }
+
+  readBytes() {
+    const length = Number(this.varint());
+    const bytes = this.bytes.subarray(this.pos, this.pos + length);
+    this.pos += length;
+    return bytes;
+  }
+
+  readString() { return new TextDecoder().decode(this.readBytes()); }
+
+  readMessage(_message, read) {
+    const length = Number(this.varint());
+    const end = this.end;
+    this.end = this.pos + length;
+    read();
+    this.pos = this.end;
+    this.end = end;
+  }
+
+  readInt32() { return Number(BigInt.asIntN(32, this.varint())); }
+  readInt64() { return Number(BigInt.asIntN(64, this.varint())); }
+  readInt64String() { return BigInt.asIntN(64, this.varint()).toString(); }
+  readUint32() { return Number(BigInt.asUintN(32, this.varint())); }
+  readUint64() { return Number(BigInt.asUintN(64, this.varint())); }
+  readUint64String() { return BigInt.asUintN(64, this.varint()).toString(); }
+  readBool() { return this.varint() !== 0n; }
+  readEnum() { return this.readInt32(); }
+  readSint32() { return Number(this.zigzag()); }
+  readSint64() { return Number(this.zigzag()); }
+  readSint64String() { return this.zigzag().toString(); }
+  zigzag() {
+    const v = this.varint();
+    return (v >> 1n) ^ -(v & 1n);
+  }
+  readFixed32() { return this.fixed(4, (view) => view.getUint32(0, true)); }
+  readSfixed32() { return this.fixed(4, (view) => view.getInt32(0, true)); }
+  readFloat() { return this.fixed(4, (view) => view.getFloat32(0, true)); }
+  readFixed64String() { return this.fixed(8, (view) => view.getBigUint64(0, true)).toString(); }
+  readSfixed64String() { return this.fixed(8, (view) => view.getBigInt64(0, true)).toString(); }
+  readDouble() { return this.fixed(8, (view) => view.getFloat64(0, true)); }
+
+  readPacked(read) {
+    const length = Number(this.varint());
+    const end = this.pos + length;
+    const values = [];
+    while (this.pos < end) {
+      values.push(read.call(this));
+    }
+    return values;
+  }
+
+  readPackedInt32() { return this.readPacked(this.readInt32); }
+  readPackedInt64() { return this.readPacked(this.readInt64); }
+  readPackedInt64String() { return this.readPacked(this.readInt64String); }
+  readPackedUint32() { return this.readPacked(this.readUint32); }
+  readPackedUint64String() { return this.readPacked(this.readUint64String); }
+  readPackedSint32() { return this.readPacked(this.readSint32); }
+  readPackedSint64String() { return this.readPacked(this.readSint64String); }
+  readPackedBool() { return this.readPacked(this.readBool); }
+  readPackedEnum() { return this.readPacked(this.readEnum); }
+  readPackedFixed32() { return this.readPacked(this.readFixed32); }
+  readPackedSfixed32() { return this.readPacked(this.readSfixed32); }
+  readPackedFloat() { return this.readPacked(this.readFloat); }
+  readPackedFixed64String() { return this.readPacked(this.readFixed64String); }
+  readPackedSfixed64String() { return this.readPacked(this.readSfixed64String); }
+  readPackedDouble() { return this.readPacked(this.readDouble); }
+}
+
+export class BinaryEncoder {
+  constructor() {
+    this.writer = new BinaryWriter();
+  }
+
+  writeUnsignedVarint32(value) { this.writer.varint(value >>> 0); }
+
+  end() {
+    const bytes = this.writer.bytes;
+    this.writer.bytes = [];
+    return bytes;
+  }
+}
+
+export class BinaryDecoder {
+  static alloc(bytes, start, length) {
+    return new BinaryDecoder(bytes, start, length);
+  }
+
+  constructor(bytes, start, length) {
+    this.reader = new BinaryReader(bytes);
+    this.reader.pos = start;
+    this.reader.end = start + length;
+  }
+
+  readUnsignedVarint32() { return this.reader.readUint32(); }
+  free() {}
+}
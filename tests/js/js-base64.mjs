@@ -0,0 +1,9 @@
+// the js-base64 functions used by the generated code.
+export function fromUint8Array(bytes, urlsafe) {
+  const text = Buffer.from(bytes).toString("base64");
+  return urlsafe ? text.replace(/\+/g, "-").replace(/\//g, "_").replace(/=+$/, "") : text;
+}
+
+export function toUint8Array(text) {
+  return new Uint8Array(Buffer.from(text, "base64"));
+}
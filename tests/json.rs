@@ -0,0 +1,272 @@
+mod common;
+
+use common::{file_names, generate, generate_files, interface, interface_props, module, run_node, INPUTS, WELL_KNOWN};
+
+#[test]
+fn json_support_module_is_emitted_once() {
+    let files = generate("");
+    assert_eq!(file_names(&files).iter().filter(|name| **name == "json_support.ets").count(), 1);
+    let support = module(&files, "json_support.ets");
+    assert_eq!(
+        interface_props(interface(&support, "JsonWriteOptions")),
+        [
+            "emitDefaultValues?: boolean",
+            "enumAsInteger?: boolean",
+            "useProtoFieldName?: boolean",
+            "deterministic?: boolean",
+        ]
+    );
+}
+
+#[test]
+fn support_modules_are_emitted_only_when_imported() {
+    let files = generate_files(&["empty/empty.proto"], "");
+    assert_eq!(file_names(&files), ["empty/empty.ets"]);
+    let files = generate_files(
+        &["empty/empty.proto"],
+        "with_delimited=true,with_text_format=true,with_byte_size=true",
+    );
+    assert_eq!(file_names(&files), ["empty/empty.ets"]);
+
+    let files = generate("");
+    let names = file_names(&files);
+    assert!(names.contains(&"json_support.ets"));
+    assert!(names.contains(&"binary_support.ets"));
+}
+
+#[test]
+fn json_round_trips() {
+    let script = r#"
+import assert from "node:assert/strict";
+import { struct_pkg_Struct, struct_pkg_Common } from "./gen.mjs";
+
+const message = new struct_pkg_Struct();
+message.field_string = "text";
+message.field_i32 = -3;
+message.field_i64 = 1n << 60n;
+message.field_boolean = true;
+message.field_bytes = new Uint8Array([0xff, 0x00]);
+message.field_enum = 1;
+message.field_common_struct = new struct_pkg_Common();
+message.field_common_struct.common_id = "id";
+message.field_map_string_i32.set("a", 1);
+message.field_map_i64_string.set(-2n, "b");
+
+const json = message.toJson();
+assert.equal(json.fieldI64, "1152921504606846976");
+assert.equal(json.fieldEnum, "ENUM_1");
+assert.deepEqual(json.fieldMapI64String, { "-2": "b" });
+assert.deepEqual(struct_pkg_Struct.fromJson(json).toBinary(), message.toBinary());
+assert.deepEqual(struct_pkg_Struct.fromJson(JSON.parse(JSON.stringify(json))).toBinary(), message.toBinary());
+"#;
+    let inputs: Vec<&str> = INPUTS.iter().chain(WELL_KNOWN.iter()).copied().collect();
+    run_node("json_round_trips", &inputs, "", script);
+}
+
+const LEVEL: [&str; 1] = ["enums/level.proto"];
+
+// every row is an input with the value it converts to.
+const ENUM_SCRIPT: &str = r#"
+import assert from "node:assert/strict";
+import { enums_Level, enums_Level_toJson, enums_Level_fromJson, enums_Reading } from "./enums/level.mjs";
+import { ProtoJsonError } from "./json_support.mjs";
+
+const to_json = [
+  [enums_Level.LEVEL_UNSPECIFIED, {}, "LEVEL_UNSPECIFIED"],
+  [enums_Level.LEVEL_LOW, {}, "LEVEL_LOW"],
+  [enums_Level.LEVEL_TOP, {}, "LEVEL_HIGH"],
+  [7, {}, 7],
+  [enums_Level.LEVEL_LOW, { enumAsInteger: true }, 1],
+  [7, { enumAsInteger: true }, 7],
+];
+for (const [value, options, json] of to_json) {
+  assert.equal(enums_Level_toJson(value, options), json);
+}
+
+const from_json = [
+  ["LEVEL_LOW", {}, enums_Level.LEVEL_LOW],
+  ["LEVEL_TOP", {}, enums_Level.LEVEL_HIGH],
+  [1, {}, enums_Level.LEVEL_LOW],
+  [0, {}, enums_Level.LEVEL_UNSPECIFIED],
+  [7, {}, 7],
+  ["LEVEL_MISSING", { ignoreUnknownFields: true }, undefined],
+  [7, { ignoreUnknownFields: true }, 7],
+];
+for (const [json, options, value] of from_json) {
+  assert.equal(enums_Level_fromJson(json, options), value);
+}
+
+assert.throws(() => enums_Level_fromJson("LEVEL_MISSING"), (e) =>
+  e instanceof ProtoJsonError && e.path === "$" && /unknown value for enum enums\.Level: LEVEL_MISSING/.test(e.message));
+assert.throws(() => enums_Level_fromJson("level_low"), ProtoJsonError);
+assert.throws(() => enums_Reading.fromJson({ history: ["LEVEL_LOW", "LEVEL_MISSING"] }), (e) =>
+  e instanceof ProtoJsonError && e.path === "$.history[1]");
+
+const reading = enums_Reading.fromJson({ level: 2, history: ["LEVEL_LOW", 7], bySensor: { a: "LEVEL_TOP" } });
+assert.equal(reading.level, enums_Level.LEVEL_HIGH);
+assert.deepEqual(reading.history, [enums_Level.LEVEL_LOW, 7]);
+assert.deepEqual(reading.toJson(), { level: "LEVEL_HIGH", history: ["LEVEL_LOW", 7], bySensor: { a: "LEVEL_HIGH" } });
+assert.deepEqual(reading.toJson({ enumAsInteger: true }), { level: 2, history: [1, 7], bySensor: { a: 2 } });
+
+// unknown names are dropped from repeated fields and leave singular fields and map entries unset.
+const unknown = enums_Reading.fromJson({
+  level: "LEVEL_MISSING",
+  peak: "LEVEL_MISSING",
+  history: ["LEVEL_HIGH", "LEVEL_MISSING", 7],
+  bySensor: { a: "LEVEL_LOW", b: "LEVEL_MISSING" },
+}, { ignoreUnknownFields: true });
+assert.equal(unknown.level, enums_Level.LEVEL_UNSPECIFIED);
+assert.equal(unknown.peak, undefined);
+assert.deepEqual(unknown.history, [enums_Level.LEVEL_HIGH, 7]);
+assert.deepEqual([...unknown.by_sensor], [["a", enums_Level.LEVEL_LOW]]);
+assert.deepEqual(unknown.toJson(), { history: ["LEVEL_HIGH", 7], bySensor: { a: "LEVEL_LOW" } });
+assert.equal(enums_Reading.fromJson({ peak: "LEVEL_LOW" }).peak, enums_Level.LEVEL_LOW);
+"#;
+
+#[test]
+fn enums_convert_to_and_from_json() {
+    run_node("enum_json", &LEVEL, "", ENUM_SCRIPT);
+}
+
+#[test]
+fn strict_enums_convert_to_and_from_json() {
+    run_node("enum_json_strict", &LEVEL, "arkts_strict=true", ENUM_SCRIPT);
+}
+
+const UNKNOWN_KEYS_SCRIPT: &str = r#"
+import assert from "node:assert/strict";
+import { enums_Reading } from "./enums/level.mjs";
+import { ProtoJsonError } from "./json_support.mjs";
+
+const json = { level: "LEVEL_LOW", color: "red" };
+assert.equal(enums_Reading.fromJson(json).level, 1);
+assert.equal(enums_Reading.fromJson(json, { strict: false }).level, 1);
+assert.equal(enums_Reading.fromJsonString(JSON.stringify(json)).level, 1);
+assert.equal(enums_Reading.fromJson(json, { strict: true, ignoreUnknownFields: true }).level, 1);
+assert.throws(() => enums_Reading.fromJson(json, { strict: true }), (e) =>
+  e instanceof ProtoJsonError && e.path === "$.color" && /unknown field of enums\.Reading/.test(e.message));
+"#;
+
+#[test]
+fn unknown_keys_are_rejected_only_when_strict() {
+    run_node("unknown_keys", &LEVEL, "", UNKNOWN_KEYS_SCRIPT);
+}
+
+#[test]
+fn strict_output_rejects_unknown_keys_only_when_strict() {
+    run_node("unknown_keys_strict", &LEVEL, "arkts_strict=true", UNKNOWN_KEYS_SCRIPT);
+}
+
+const NUMBERS: [&str; 3] = [
+    "precision/numbers.proto",
+    "google/protobuf/struct.proto",
+    "google/protobuf/wrappers.proto",
+];
+
+const PRECISION_SCRIPT: &str = r#"
+import assert from "node:assert/strict";
+import { precision_Numbers } from "./precision/numbers.mjs";
+
+const unsafe = "9007199254740993";
+const text = `{
+  "big": ${unsafe}, "ubig": 18446744073709551615, "fixed": ${unsafe}, "signed_fixed": -${unsafe},
+  "ratio": ${unsafe}, "bigs": [1, ${unsafe}], "totals": {"a": ${unsafe}}, "ratios": {"big": ${unsafe}},
+  "value": ${unsafe}, "attrs": {"big": ${unsafe}, "list": [${unsafe}]}, "boxed": ${unsafe},
+  "child": {"big": ${unsafe}, "value": {"big": ${unsafe}}}, "children": [{"big": ${unsafe}}, {"ratio": ${unsafe}}]
+}`;
+const m = precision_Numbers.fromJsonString(text);
+
+// 64-bit integer fields keep every digit, wherever they are nested.
+assert.equal(m.big, 9007199254740993n);
+assert.equal(m.ubig, 18446744073709551615n);
+assert.equal(m.fixed, 9007199254740993n);
+assert.equal(m.signed_fixed, -9007199254740993n);
+assert.deepEqual(m.bigs, [1n, 9007199254740993n]);
+assert.equal(m.totals.get("a"), 9007199254740993n);
+assert.equal(m.boxed.value, 9007199254740993n);
+assert.equal(m.child.big, 9007199254740993n);
+assert.equal(m.children[0].big, 9007199254740993n);
+
+// every other number is a double, like JSON.parse reads it.
+assert.equal(m.ratio, 9007199254740992);
+assert.equal(m.ratios.get("big"), 9007199254740992);
+assert.equal(m.children[1].ratio, 9007199254740992);
+assert.equal(m.value.number_value, 9007199254740992);
+assert.equal(m.attrs.fields.get("big").number_value, 9007199254740992);
+assert.equal(m.attrs.fields.get("list").list_value.values[0].number_value, 9007199254740992);
+assert.equal(m.child.value.struct_value.fields.get("big").number_value, 9007199254740992);
+
+// keys and strings which look like numbers are left alone.
+const quoted = precision_Numbers.fromJsonString(`{"totals": {"${unsafe}": 1}, "value": "${unsafe}"}`);
+assert.equal(quoted.totals.get(unsafe), 1n);
+assert.equal(quoted.value.string_value, unsafe);
+"#;
+
+#[test]
+fn from_json_string_keeps_the_precision_of_64_bit_fields_only() {
+    run_node("json_precision", &NUMBERS, "", PRECISION_SCRIPT);
+}
+
+#[test]
+fn strict_from_json_string_keeps_the_precision_of_64_bit_fields_only() {
+    run_node("json_precision_strict", &NUMBERS, "arkts_strict=true", PRECISION_SCRIPT);
+}
+
+// every row is a JSON document read with strict, the path of the error and its message.
+const STRICT_REJECTIONS_SCRIPT: &str = r#"
+import assert from "node:assert/strict";
+import { struct_pkg_Struct } from "./gen.mjs";
+import { strict_pkg_Node } from "./strict.mjs";
+import { ProtoJsonError } from "./json_support.mjs";
+
+const rejections = [
+  // range
+  [struct_pkg_Struct, { fieldI32: 2147483648 }, "$.fieldI32", /illegal int32 value/],
+  [struct_pkg_Struct, { field_i32: 1.5 }, "$.fieldI32", /illegal int32 value/],
+  [strict_pkg_Node, { offsets: [1, "9223372036854775808"] }, "$.offsets[1]", /illegal sint64 value/],
+  [strict_pkg_Node, { counter: -1 }, "$.counter", /illegal uint64 value/],
+  [strict_pkg_Node, { weight: "heavy" }, "$.weight", /illegal double value/],
+  // type
+  [strict_pkg_Node, { id: 5 }, "$.id", /illegal string value/],
+  [strict_pkg_Node, { level: true }, "$.level", /illegal strict_pkg\.Level value/],
+  [strict_pkg_Node, { levels: "LEVEL_HIGH" }, "$.levels", /expected an array for levels/],
+  [strict_pkg_Node, { flags: [] }, "$.flags", /expected an object for flags/],
+  [strict_pkg_Node, { chunks: { a: 1 } }, '$.chunks["a"]', /illegal bytes value/],
+  [strict_pkg_Node, { leaves: { "1": { children: [{ payload: 3 }] } } }, '$.leaves["1"].children[0].payload', /illegal bytes value/],
+  [strict_pkg_Node, { others: [{}, 7] }, "$.others[1]", /illegal struct_pkg\.Struct2 value/],
+  [strict_pkg_Node, { levels: ["LEVEL_HIGH", "LEVEL_LOW"] }, "$.levels[1]", /unknown value for enum strict_pkg\.Level/],
+  // oneof
+  [strict_pkg_Node, { text: "a", counter: 1 }, "$", /multiple values for oneof choice/],
+  [strict_pkg_Node, { leaves: { "2": { children: [{ payload: "", children: [] }] } }, leaf: {}, text: "a" }, "$", /multiple values for oneof choice/],
+  // duplicate name
+  [struct_pkg_Struct, { fieldI32: 1, field_i32: 2 }, "$.fieldI32", /duplicate field fieldI32/],
+  [strict_pkg_Node, { common: { commonId: "a", common_id: "b" } }, "$.common.commonId", /duplicate field commonId/],
+  // unknown field
+  [strict_pkg_Node, { leaf: { color: 1 } }, "$.leaf.color", /unknown field of strict_pkg\.Node\.Leaf/],
+];
+
+for (const [type, json, path, message] of rejections) {
+  assert.throws(() => type.fromJson(json, { strict: true }), (e) => {
+    assert.ok(e instanceof ProtoJsonError, `${JSON.stringify(json)} threw ${e}`);
+    assert.equal(e.path, path, JSON.stringify(json));
+    assert.match(e.message, message);
+    assert.ok(e.message.startsWith(path + ": "));
+    return true;
+  });
+}
+
+const valid = { id: "n", counter: "18446744073709551615", offsets: [-1], levels: ["LEVEL_HIGH", 0], common: { commonId: "a" } };
+assert.equal(strict_pkg_Node.fromJson(valid, { strict: true }).counter, 18446744073709551615n);
+"#;
+
+#[test]
+fn strict_reading_rejects_with_the_json_path() {
+    let inputs: Vec<&str> = INPUTS.iter().chain(WELL_KNOWN.iter()).copied().collect();
+    run_node("strict_rejections", &inputs, "", STRICT_REJECTIONS_SCRIPT);
+}
+
+#[test]
+fn strict_output_rejects_with_the_json_path() {
+    let inputs: Vec<&str> = INPUTS.iter().chain(WELL_KNOWN.iter()).copied().collect();
+    run_node("strict_rejections_strict", &inputs, "arkts_strict=true", STRICT_REJECTIONS_SCRIPT);
+}
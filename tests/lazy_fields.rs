@@ -0,0 +1,47 @@
+mod common;
+
+use common::{class, generate, has_member, method_signature, module, run_node, INPUTS, WELL_KNOWN};
+
+#[test]
+fn lazy_fields_keep_their_bytes() {
+    let files = generate("lazy_fields=true");
+    let strict = module(&files, "strict.ets");
+    let node = class(&strict, "strict_pkg_Node");
+    assert!(has_member(node, "private _root_bytes"));
+    assert!(has_member(node, "get root"));
+    assert!(has_member(node, "set root"));
+    assert_eq!(
+        method_signature(&strict, "strict_pkg_Node", "root"),
+        "root(): strict_pkg_Node_Leaf | undefined"
+    );
+    // fields without [lazy = true] are decoded eagerly.
+    assert!(!has_member(node, "private _common_bytes"));
+
+    let files = generate("");
+    let strict = module(&files, "strict.ets");
+    assert!(!has_member(class(&strict, "strict_pkg_Node"), "private _root_bytes"));
+}
+
+#[test]
+fn lazy_fields_are_decoded_on_first_access() {
+    let script = r#"
+import assert from "node:assert/strict";
+import { strict_pkg_Node, strict_pkg_Node_Leaf } from "./strict.mjs";
+
+const node = new strict_pkg_Node();
+node.root = new strict_pkg_Node_Leaf();
+node.root.payload = new Uint8Array([1, 2, 3]);
+const bytes = node.toBinary();
+
+const decoded = strict_pkg_Node.fromBinary(bytes);
+// written back from the kept bytes without being decoded.
+assert.deepEqual(decoded.toBinary(), bytes);
+assert.deepEqual(decoded.root.payload, new Uint8Array([1, 2, 3]));
+
+decoded.root = undefined;
+assert.equal(decoded.root, undefined);
+assert.equal(decoded.toBinary().length, 0);
+"#;
+    let inputs: Vec<&str> = INPUTS.iter().chain(WELL_KNOWN.iter()).copied().collect();
+    run_node("lazy_fields", &inputs, "lazy_fields=true", script);
+}
@@ -0,0 +1,53 @@
+mod common;
+
+use common::{class, generate, members, module, run_node, INPUTS, WELL_KNOWN};
+
+#[test]
+fn large_messages_are_split_into_private_methods() {
+    let files = generate("max_method_fields=2");
+    let strict = module(&files, "strict.ets");
+    let node = members(class(&strict, "strict_pkg_Node"));
+    for helper in [
+        "private toBinary_0",
+        "private mergeFrom_0",
+        "private toJson_0",
+        "private static fromJson_0",
+    ] {
+        assert!(node.iter().any(|m| m == helper), "missing {} in {:?}", helper, node);
+    }
+
+    let files = generate("");
+    let strict = module(&files, "strict.ets");
+    let node = members(class(&strict, "strict_pkg_Node"));
+    assert!(!node.iter().any(|m| m.starts_with("private toJson_")), "{:?}", node);
+}
+
+#[test]
+fn split_methods_encode_like_the_whole_ones() {
+    let script = r#"
+import assert from "node:assert/strict";
+import { strict_pkg_Node, strict_pkg_Node_Leaf } from "./strict.mjs";
+
+const node = new strict_pkg_Node();
+node.id = "node";
+node.weight = 1.5;
+node.level = 1;
+node.levels = [0, 1];
+node.blobs = [new Uint8Array([1, 2])];
+node.flags.set(true, "yes");
+node.chunks.set("a", new Uint8Array([3]));
+node.text = "hello";
+node.root = new strict_pkg_Node_Leaf();
+node.root.payload = new Uint8Array([4, 5]);
+
+const bytes = node.toBinary();
+const decoded = strict_pkg_Node.fromBinary(bytes);
+assert.deepEqual(decoded.toBinary(), bytes);
+assert.deepEqual(strict_pkg_Node.fromJson(node.toJson()).toJson(), node.toJson());
+console.log(Buffer.from(bytes).toString("hex"));
+"#;
+    let inputs: Vec<&str> = INPUTS.iter().chain(WELL_KNOWN.iter()).copied().collect();
+    let whole = run_node("split_whole", &inputs, "", script);
+    let split = run_node("split_chunks", &inputs, "max_method_fields=2", script);
+    assert_eq!(whole, split);
+}
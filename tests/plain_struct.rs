@@ -0,0 +1,101 @@
+mod common;
+
+use common::{file, generate_files, interface, interface_props, module, print, run_node, type_alias};
+
+const PLAIN: [&str; 2] = ["wkt/plain.proto", "google/protobuf/struct.proto"];
+
+#[test]
+fn plain_struct_fields_use_the_json_types() {
+    let files = generate_files(&PLAIN, "plain_struct=true");
+    let plain = module(&files, "wkt/plain.ets");
+    assert_eq!(
+        interface_props(interface(&plain, "wkt_PlainInit")),
+        vec![
+            "attrs?: google_protobuf_struct.JsonObject",
+            "value?: google_protobuf_struct.JsonValue",
+            "items?: google_protobuf_struct.JsonValue[]",
+        ]
+    );
+}
+
+#[test]
+fn json_object_is_an_interface_by_default() {
+    let files = generate_files(&PLAIN, "plain_struct=true");
+    let r#struct = module(&files, "google/protobuf/struct.ets");
+    assert_eq!(
+        interface_props(interface(&r#struct, "JsonObject")),
+        vec!["[key: string]: JsonValue"]
+    );
+}
+
+#[test]
+fn json_object_is_a_record_under_arkts_strict() {
+    let files = generate_files(&PLAIN, "plain_struct=true,arkts_strict=true");
+    let r#struct = module(&files, "google/protobuf/struct.ets");
+    assert_eq!(
+        print(&*type_alias(&r#struct, "JsonObject").type_ann),
+        "Record<string, JsonValue>"
+    );
+    assert_eq!(
+        print(&*type_alias(&r#struct, "JsonValue").type_ann),
+        "null | number | string | boolean | JsonObject | JsonValue[]"
+    );
+    assert!(!file(&files, "google/protobuf/struct.ets").contains("[key: string]"));
+}
+
+// null is a value of its own in a Value and unsets a Struct or ListValue.
+const NULL_SCRIPT: &str = r#"
+import assert from "node:assert/strict";
+import { wkt_Plain } from "./wkt/plain.mjs";
+
+const attrs = { a: null, b: [1, null, "x"], c: { d: null } };
+const m = wkt_Plain.create({ attrs, value: null, items: [null, { e: null }] });
+const json = { attrs, value: null, items: [null, { e: null }] };
+assert.deepEqual(m.toJson(), json);
+
+const decoded = wkt_Plain.fromBinary(m.toBinary());
+assert.equal(decoded.value, null);
+assert.deepEqual(decoded.toJson(), json);
+
+const parsed = wkt_Plain.fromJson(json);
+assert.equal(parsed.value, null);
+assert.deepEqual(parsed.toJson(), json);
+assert.deepEqual(wkt_Plain.fromJsonString(JSON.stringify(json)).toJson(), json);
+
+assert.ok(m.equals(m.clone()));
+assert.ok(m.equals(decoded));
+assert.ok(!m.equals(wkt_Plain.create({ attrs, value: 0, items: [null, { e: null }] })));
+assert.deepEqual(new wkt_Plain().mergeFromMessage(m).toJson(), json);
+"#;
+
+#[test]
+fn plain_struct_keeps_json_null() {
+    let script = format!(
+        "{}{}",
+        NULL_SCRIPT,
+        r#"
+assert.equal(wkt_Plain.fromJson({ attrs: null, items: null }).attrs, undefined);
+assert.equal(m.byteSize(), m.toBinary().length);
+assert.deepEqual(m.toBinaryInto(), m.toBinary());
+const text = m.toText();
+assert.match(text, /null_value: NULL_VALUE/);
+assert.deepEqual(wkt_Plain.fromText(text).toJson(), json);
+"#
+    );
+    run_node(
+        "plain_struct_null",
+        &PLAIN,
+        "plain_struct=true,with_byte_size=true,with_text_format=true",
+        &script,
+    );
+}
+
+#[test]
+fn strict_plain_struct_keeps_json_null() {
+    run_node(
+        "strict_plain_struct_null",
+        &PLAIN,
+        "plain_struct=true,arkts_strict=true",
+        NULL_SCRIPT,
+    );
+}
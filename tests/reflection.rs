@@ -0,0 +1,96 @@
+mod common;
+
+use common::{
+    class, declared_names, generate, has_member, method_signature, module, run_node, INPUTS,
+    WELL_KNOWN,
+};
+use swc_ecma_ast::{Decl, ModuleDecl, ModuleItem};
+
+#[test]
+fn descriptors_are_embedded_with_descriptor() {
+    let files = generate("with_descriptor=true");
+    let strict = module(&files, "strict.ets");
+    let names = declared_names(&strict);
+    for name in ["file_strict", "strict_pkg_Level_descriptor"] {
+        assert!(names.iter().any(|n| n == name), "missing {} in {:?}", name, names);
+    }
+    assert!(has_member(class(&strict, "strict_pkg_Node_Leaf"), "static descriptor"));
+    assert_eq!(
+        method_signature(&strict, "strict_pkg_Node", "descriptor"),
+        "descriptor(): google_protobuf_descriptor.google_protobuf_DescriptorProto"
+    );
+
+    let descriptor = module(&files, "google/protobuf/descriptor.ets");
+    let registry = class(&descriptor, "DescriptorRegistry");
+    for method in ["register", "findFile", "findMessage", "findEnum"] {
+        assert!(has_member(registry, method), "missing {}", method);
+    }
+    let functions: Vec<String> = descriptor
+        .body
+        .iter()
+        .filter_map(|item| match item {
+            ModuleItem::ModuleDecl(ModuleDecl::ExportDecl(export)) => match &export.decl {
+                Decl::Fn(function) if function.ident.sym.starts_with("find") => Some(format!(
+                    "{}: {}",
+                    function.ident.sym,
+                    common::print(&*function.function.return_type.as_ref().unwrap().type_ann)
+                )),
+                _ => None,
+            },
+            _ => None,
+        })
+        .collect();
+    assert_eq!(
+        functions,
+        [
+            "findField: google_protobuf_FieldDescriptorProto | undefined",
+            "findFieldByNumber: google_protobuf_FieldDescriptorProto | undefined",
+            "findEnumValue: google_protobuf_EnumValueDescriptorProto | undefined",
+        ]
+    );
+
+    let files = generate("");
+    let strict = module(&files, "strict.ets");
+    assert!(!declared_names(&strict).iter().any(|n| n.ends_with("_descriptor")));
+    assert!(common::find_method(class(&strict, "strict_pkg_Node"), "descriptor").is_none());
+}
+
+#[test]
+fn descriptors_resolve_through_the_registry() {
+    let script = r#"
+import assert from "node:assert/strict";
+import { strict_pkg_Node, strict_pkg_Node_Leaf, strict_pkg_Level_descriptor, file_strict } from "./strict.mjs";
+import { struct_pkg_Struct, struct_pkg_Enum_descriptor } from "./gen.mjs";
+import {
+  descriptor_registry,
+  findField,
+  findFieldByNumber,
+  findEnumValue,
+} from "./google/protobuf/descriptor.mjs";
+
+assert.equal(descriptor_registry.findFile("strict.proto"), file_strict());
+assert.equal(descriptor_registry.findMessage("strict_pkg.Node"), strict_pkg_Node.descriptor());
+assert.equal(descriptor_registry.findMessage(".strict_pkg.Node.Leaf"), strict_pkg_Node_Leaf.descriptor());
+assert.equal(descriptor_registry.findMessage("google.protobuf.DescriptorProto").name, "DescriptorProto");
+assert.equal(descriptor_registry.findMessage("strict_pkg.Missing"), undefined);
+
+const level = descriptor_registry.findEnum("strict_pkg.Level");
+assert.equal(level, strict_pkg_Level_descriptor());
+assert.equal(findEnumValue(level, "LEVEL_HIGH").number, 1);
+assert.equal(findEnumValue(level, 0).name, "LEVEL_UNSPECIFIED");
+assert.equal(struct_pkg_Enum_descriptor().name, "Enum");
+
+const node = strict_pkg_Node.descriptor();
+assert.equal(findField(node, "weight").number, 2);
+assert.equal(findField(node, "created").type_name, ".google.protobuf.Timestamp");
+// json names resolve as well.
+assert.equal(findField(struct_pkg_Struct.descriptor(), "fieldString").number, 1);
+assert.equal(findFieldByNumber(node, 18).name, "root");
+assert.equal(findField(node, "missing"), undefined);
+
+const nested = descriptor_registry.findEnum("google.protobuf.FieldDescriptorProto.Type");
+assert.equal(findEnumValue(nested, "TYPE_MESSAGE").number, 11);
+"#;
+    let inputs: Vec<&str> = INPUTS.iter().chain(WELL_KNOWN.iter()).copied().collect();
+    run_node("reflection", &inputs, "with_descriptor=true", script);
+}
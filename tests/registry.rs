@@ -0,0 +1,181 @@
+mod common;
+
+use std::path::Path;
+
+use common::{declared_names, generate_files, generate_files_in, module, print, run_node};
+use swc_ecma_ast::{Decl, Module, ModuleDecl, ModuleItem, Stmt};
+
+const REGISTRY: [&str; 3] = [
+    "registry/envelope.proto",
+    "registry/payload.proto",
+    "google/protobuf/any.proto",
+];
+
+// the statements of an exported function, one line each.
+fn function_body(module: &Module, name: &str) -> Vec<String> {
+    module
+        .body
+        .iter()
+        .find_map(|item| match item {
+            ModuleItem::ModuleDecl(ModuleDecl::ExportDecl(export)) => match &export.decl {
+                Decl::Fn(function) if &*function.ident.sym == name => function.function.body.as_ref(),
+                _ => None,
+            },
+            _ => None,
+        })
+        .unwrap_or_else(|| panic!("missing function {}", name))
+        .stmts
+        .iter()
+        .map(|stmt| print(stmt).split_whitespace().collect::<Vec<_>>().join(" "))
+        .collect()
+}
+
+// the calls made when the module is loaded.
+fn top_level_calls(module: &Module) -> Vec<String> {
+    module
+        .body
+        .iter()
+        .filter_map(|item| match item {
+            ModuleItem::Stmt(stmt @ Stmt::Expr(_)) => Some(print(stmt)),
+            _ => None,
+        })
+        .collect()
+}
+
+#[test]
+fn files_register_their_messages_and_dependencies() {
+    let files = generate_files(&REGISTRY, "");
+    let envelope = module(&files, "registry/envelope.ets");
+    assert_eq!(
+        function_body(&envelope, "register_registry_envelope"),
+        [
+            "if (file_registry_envelope_registered) { return; }",
+            "file_registry_envelope_registered = true;",
+            "google_protobuf_any.register_google_protobuf_any();",
+            "registry_payload.register_registry_payload();",
+            "google_protobuf_any.type_registry.set(\"registry.Envelope\", registry_Envelope);",
+        ]
+    );
+    assert_eq!(top_level_calls(&envelope), ["register_registry_envelope();"]);
+
+    let payload = module(&files, "registry/payload.ets");
+    assert_eq!(
+        function_body(&payload, "register_registry_payload"),
+        [
+            "if (file_registry_payload_registered) { return; }",
+            "file_registry_payload_registered = true;",
+            "google_protobuf_any.type_registry.set(\"registry.Payload\", registry_Payload);",
+            "google_protobuf_any.type_registry.set(\"registry.Payload.Part\", registry_Payload_Part);",
+        ]
+    );
+}
+
+#[test]
+fn nothing_is_registered_without_any() {
+    let files = generate_files(&["registry/payload.proto"], "");
+    let payload = module(&files, "registry/payload.ets");
+    assert!(!declared_names(&payload).iter().any(|name| name.starts_with("register_")));
+    assert!(top_level_calls(&payload).is_empty());
+}
+
+// layer n holds two files importing both files of layer n - 1, every path through the
+// layers is a different import chain.
+fn write_diamonds(dir: &Path, layers: usize, base_has_messages: bool) -> Vec<String> {
+    let write = |name: &str, imports: &[String], body: &str| {
+        let mut source = format!("syntax = \"proto3\";\npackage {};\n", name.trim_end_matches(".proto"));
+        for import in imports {
+            source += &format!("import \"{}\";\n", import);
+        }
+        source += body;
+        std::fs::write(dir.join(name), source).unwrap();
+    };
+    let base = if base_has_messages { "message Base {}\n" } else { "" };
+    write("d0a.proto", &[], base);
+    write("d0b.proto", &[], base);
+    let mut files = vec!["d0a.proto".to_string(), "d0b.proto".to_string()];
+    for layer in 1..=layers {
+        let imports = vec![format!("d{}a.proto", layer - 1), format!("d{}b.proto", layer - 1)];
+        for side in ["a", "b"] {
+            let name = format!("d{}{}.proto", layer, side);
+            write(&name, &imports, "");
+            files.push(name);
+        }
+    }
+    let imports = vec![
+        "google/protobuf/any.proto".to_string(),
+        format!("d{}a.proto", layers),
+        format!("d{}b.proto", layers),
+    ];
+    write("top.proto", &imports, "message Top {}\n");
+    files.push("top.proto".to_string());
+    files
+}
+
+#[test]
+fn diamond_imports_are_searched_once() {
+    for base_has_messages in [false, true] {
+        let dir = std::env::temp_dir().join(format!(
+            "protoc_gen_arkts_diamonds_{}_{}",
+            base_has_messages,
+            std::process::id()
+        ));
+        std::fs::create_dir_all(&dir).unwrap();
+        // 2^40 import chains, searching each of them would not finish.
+        let files = write_diamonds(&dir, 40, base_has_messages);
+        let names: Vec<&str> = files.iter().map(|f| f.as_str()).collect();
+        let generated = generate_files_in(&dir, &names, "");
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        let top = module(&generated, "top.ets");
+        let calls: Vec<String> = function_body(&top, "register_top")
+            .into_iter()
+            .filter(|stmt| stmt.contains(".register_"))
+            .collect();
+        if base_has_messages {
+            assert_eq!(
+                calls,
+                [
+                    "google_protobuf_any.register_google_protobuf_any();",
+                    "d40a.register_d40a();",
+                    "d40b.register_d40b();",
+                ]
+            );
+        } else {
+            assert_eq!(calls, ["google_protobuf_any.register_google_protobuf_any();"]);
+            let layer = module(&generated, "d40a.ets");
+            assert!(!declared_names(&layer).iter().any(|name| name.starts_with("register_")));
+        }
+    }
+}
+
+const ANY_SCRIPT: &str = r#"
+import assert from "node:assert/strict";
+import { registry_Envelope } from "./registry/envelope.mjs";
+import { registry_Payload, registry_Payload_Part } from "./registry/payload.mjs";
+import { google_protobuf_Any, type_registry } from "./google/protobuf/any.mjs";
+
+assert.equal(type_registry.get("registry.Envelope"), registry_Envelope);
+assert.equal(type_registry.get("registry.Payload.Part"), registry_Payload_Part);
+
+const payload = new registry_Payload();
+payload.text = "hello";
+const envelope = new registry_Envelope();
+envelope.payload = google_protobuf_Any.pack(payload);
+
+const json = envelope.toJson();
+assert.deepEqual(json.payload, { "@type": "type.googleapis.com/registry.Payload", text: "hello" });
+const decoded = registry_Envelope.fromJson(json);
+assert.equal(decoded.payload.unpack(registry_Payload).text, "hello");
+assert.deepEqual(new google_protobuf_Any().toJson(), {});
+assert.throws(() => google_protobuf_Any.fromJson({ text: "hello" }), /@type is empty/);
+"#;
+
+#[test]
+fn any_resolves_registered_types_without_setup() {
+    run_node("any_registry", &REGISTRY, "", ANY_SCRIPT);
+}
+
+#[test]
+fn strict_any_resolves_registered_types() {
+    run_node("any_registry_strict", &REGISTRY, "arkts_strict=true", ANY_SCRIPT);
+}
@@ -0,0 +1,142 @@
+mod common;
+
+use common::{file_names, generate, method_signature, module, run_node, INPUTS, WELL_KNOWN};
+
+#[test]
+fn text_support_module_is_emitted_with_text_format() {
+    let files = generate("");
+    assert!(!file_names(&files).contains(&"text_support.ets"));
+
+    let files = generate("with_text_format=true");
+    assert!(file_names(&files).contains(&"text_support.ets"));
+    let strict = module(&files, "strict.ets");
+    assert_eq!(method_signature(&strict, "strict_pkg_Node", "toText"), "toText(): string");
+    assert_eq!(
+        method_signature(&strict, "strict_pkg_Node", "fromText"),
+        "fromText(text: string): strict_pkg_Node"
+    );
+}
+
+#[test]
+fn text_format_round_trips() {
+    let script = r#"
+import assert from "node:assert/strict";
+import { struct_pkg_Common } from "./gen.mjs";
+
+const common = new struct_pkg_Common();
+common.common_id = "id";
+common.common_type = 100n;
+const text = common.toText();
+assert.deepEqual(struct_pkg_Common.fromText(text).toBinary(), common.toBinary());
+assert.equal(struct_pkg_Common.fromText('common_id: "id" common_type: 100').common_id, "id");
+"#;
+    let inputs: Vec<&str> = INPUTS.iter().chain(WELL_KNOWN.iter()).copied().collect();
+    run_node("text_format_round_trips", &inputs, "with_text_format=true", script);
+}
+
+const NODE_SCRIPT: &str = r#"
+import assert from "node:assert/strict";
+import { strict_pkg_Node, strict_pkg_Node_Leaf, strict_pkg_Level } from "./strict.mjs";
+import { struct_pkg_Common } from "./gen.mjs";
+
+const roundTrip = (message) => {
+  const text = message.toText();
+  const parsed = strict_pkg_Node.fromText(text);
+  assert.ok(parsed.equals(message), text);
+  assert.equal(parsed.toText(), text);
+  return text;
+};
+
+// escapes: control characters, quotes and backslashes are escaped, other characters are kept.
+const escaped = new strict_pkg_Node();
+escaped.id = 'tab\tnew\nline "quoted" \'single\' back\\slash \x01 é € 😀';
+escaped.blobs = [new Uint8Array([0, 1, 0x22, 0x27, 0x5c, 0x41, 0x7f, 0x80, 0xff])];
+const text = roundTrip(escaped);
+assert.ok(text.includes('id: "tab\\tnew\\nline \\"quoted\\" \\\'single\\\' back\\\\slash \\001 é € 😀"'), text);
+assert.ok(text.includes('blobs: "\\000\\001\\"\\\'\\\\A\\177\\200\\377"'), text);
+assert.equal(strict_pkg_Node.fromText('id: "\\x41\\101\\u00e9"').id, "AAé");
+assert.equal(strict_pkg_Node.fromText("id: 'single' \"adjacent\"").id, "singleadjacent");
+
+// nested messages, repeated messages and a message in a oneof.
+const nested = new strict_pkg_Node();
+nested.root = strict_pkg_Node_Leaf.create({ payload: new Uint8Array([1]), children: [{ payload: new Uint8Array([2]) }, {}] });
+nested.leaf = strict_pkg_Node_Leaf.create({ children: [{ children: [{ payload: new Uint8Array([3]) }] }] });
+nested.common = struct_pkg_Common.create({ common_id: "c", common_type: -5n });
+roundTrip(nested);
+assert.equal(strict_pkg_Node.fromText("root < payload: '\\001' > common: { common_id: 'c' }").root.payload[0], 1);
+
+// maps, keys of every kind and the entries in insertion order.
+const maps = new strict_pkg_Node();
+maps.leaves.set(-1n, strict_pkg_Node_Leaf.create({ payload: new Uint8Array([9]) }));
+maps.leaves.set(9007199254740993n, new strict_pkg_Node_Leaf());
+maps.flags.set(true, "yes");
+maps.flags.set(false, "");
+maps.chunks.set("a\"b", new Uint8Array([0xff]));
+const mapText = roundTrip(maps);
+assert.ok(mapText.includes("key: 9007199254740993"), mapText);
+const listed = strict_pkg_Node.fromText('flags [{ key: true value: "a" }, { key: false }] flags { value: "c" }');
+assert.deepEqual([...listed.flags.entries()], [[true, "a"], [false, "c"]]);
+
+// enums by name, unknown values by number.
+const enums = new strict_pkg_Node();
+enums.level = strict_pkg_Level.LEVEL_HIGH;
+enums.levels = [strict_pkg_Level.LEVEL_UNSPECIFIED, strict_pkg_Level.LEVEL_HIGH, 7];
+const enumText = roundTrip(enums);
+assert.ok(enumText.includes("level: LEVEL_HIGH"), enumText);
+assert.ok(enumText.includes("levels: 7"), enumText);
+assert.deepEqual(strict_pkg_Node.fromText("level: 1 levels: [LEVEL_HIGH, 0]").levels, [1, 0]);
+assert.throws(() => strict_pkg_Node.fromText("level: LEVEL_LOW"), /LEVEL_LOW/);
+"#;
+
+#[test]
+fn text_format_round_trips_escapes_nesting_maps_and_enums() {
+    let inputs: Vec<&str> = INPUTS.iter().chain(WELL_KNOWN.iter()).copied().collect();
+    run_node("text_format_node", &inputs, "with_text_format=true", NODE_SCRIPT);
+}
+
+#[test]
+fn strict_text_format_round_trips_escapes_nesting_maps_and_enums() {
+    let inputs: Vec<&str> = INPUTS.iter().chain(WELL_KNOWN.iter()).copied().collect();
+    run_node("text_format_node_strict", &inputs, "with_text_format=true,arkts_strict=true", NODE_SCRIPT);
+}
+
+const ANY_SCRIPT: &str = r#"
+import assert from "node:assert/strict";
+import { registry_Envelope } from "./registry/envelope.mjs";
+import { registry_Payload } from "./registry/payload.mjs";
+import { google_protobuf_Any } from "./google/protobuf/any.mjs";
+
+const payload = registry_Payload.create({ text: "hi\n", parts: [{ index: 1 }, { index: 2 }] });
+const envelope = new registry_Envelope();
+envelope.payload = google_protobuf_Any.pack(payload);
+const text = envelope.toText();
+assert.ok(text.includes("payload {\n  [type.googleapis.com/registry.Payload] {\n    text: \"hi\\n\""), text);
+const parsed = registry_Envelope.fromText(text);
+assert.equal(parsed.payload.type_url, "type.googleapis.com/registry.Payload");
+assert.ok(parsed.payload.unpack(registry_Payload).equals(payload));
+assert.equal(parsed.toText(), text);
+
+// an unregistered type stays in the type_url and value form.
+const unknown = new registry_Envelope();
+unknown.payload = google_protobuf_Any.create({ type_url: "example.com/missing.Type", value: new Uint8Array([8, 1]) });
+const unknownText = unknown.toText();
+assert.ok(unknownText.includes('type_url: "example.com/missing.Type"'), unknownText);
+assert.deepEqual(registry_Envelope.fromText(unknownText).payload.value, new Uint8Array([8, 1]));
+assert.throws(() => registry_Envelope.fromText("payload { [example.com/missing.Type] {} }"), /unknown message type example\.com\/missing\.Type/);
+"#;
+
+const REGISTRY: [&str; 3] = [
+    "registry/envelope.proto",
+    "registry/payload.proto",
+    "google/protobuf/any.proto",
+];
+
+#[test]
+fn text_format_expands_any() {
+    run_node("text_format_any", &REGISTRY, "with_text_format=true", ANY_SCRIPT);
+}
+
+#[test]
+fn strict_text_format_expands_any() {
+    run_node("text_format_any_strict", &REGISTRY, "with_text_format=true,arkts_strict=true", ANY_SCRIPT);
+}
@@ -0,0 +1,98 @@
+mod common;
+
+use common::{class, generate_files, method, method_signature, module, print, run_node};
+
+const SAMPLE: [&str; 1] = ["values/sample.proto"];
+
+#[test]
+fn bytes_are_compared_with_a_loop() {
+    let files = generate_files(&SAMPLE, "");
+    let sample = module(&files, "values/sample.ets");
+    assert_eq!(
+        method_signature(&sample, "values_Sample", "equals"),
+        "equals(other: values_Sample): boolean"
+    );
+    let equals = print(method(class(&sample, "values_Sample"), "equals"));
+    assert!(!equals.contains(".some("), "{}", equals);
+    assert!(equals.contains("const otherValue = other.payload;"), "{}", equals);
+    assert!(equals.contains("const otherValue = other.chunks[i];"), "{}", equals);
+    assert!(equals.contains("for(let j: number = 0; j < otherValue.length; j++)"), "{}", equals);
+}
+
+#[test]
+fn equals_compares_values() {
+    let script = r#"
+import assert from "node:assert/strict";
+import { values_Sample } from "./values/sample.mjs";
+
+const sample = () => values_Sample.create({
+  ratio: 0.5,
+  weights: [1, 2],
+  payload: new Uint8Array([1, 2, 3]),
+  chunks: [new Uint8Array([4]), new Uint8Array()],
+  blobs: new Map([["a", new Uint8Array([5, 6])]]),
+  gauges: new Map([["g", 1.5]]),
+  maybe: new Uint8Array(),
+});
+
+assert.ok(sample().equals(sample()));
+assert.ok(sample().equals(sample().clone()));
+assert.ok(new values_Sample().equals(new values_Sample()));
+
+const differ = (change) => {
+  const other = sample();
+  change(other);
+  assert.ok(!sample().equals(other));
+  assert.ok(!other.equals(sample()));
+};
+differ((m) => { m.payload = new Uint8Array([1, 2, 4]); });
+differ((m) => { m.payload = new Uint8Array([1, 2]); });
+differ((m) => { m.chunks[1] = new Uint8Array([0]); });
+differ((m) => { m.chunks.pop(); });
+differ((m) => { m.blobs.set("a", new Uint8Array([5, 7])); });
+differ((m) => { m.blobs = new Map([["b", new Uint8Array([5, 6])]]); });
+differ((m) => { m.maybe = undefined; });
+differ((m) => { m.ratio = -0.5; });
+differ((m) => { m.gauges.set("g", NaN); });
+
+// NaN equals NaN, so a message holding it still equals its clone.
+const nan = values_Sample.create({ ratio: NaN, weights: [NaN], gauges: new Map([["g", NaN]]) });
+assert.ok(nan.equals(nan.clone()));
+assert.ok(!nan.equals(values_Sample.create({ ratio: 1, weights: [NaN], gauges: new Map([["g", NaN]]) })));
+"#;
+    run_node("value_methods", &SAMPLE, "", script);
+}
+
+// a message merged into itself appends its repeated values once.
+const MERGE_SCRIPT: &str = r#"
+import assert from "node:assert/strict";
+import { values_Sample } from "./values/sample.mjs";
+
+const merged = values_Sample.create({ ratio: 0.5, weights: [1], blobs: new Map([["a", new Uint8Array([1])]]) });
+merged.mergeFromMessage(values_Sample.create({
+  weights: [2],
+  chunks: [new Uint8Array([3])],
+  blobs: new Map([["b", new Uint8Array([2])]]),
+  gauges: new Map([["g", 1.5]]),
+}));
+assert.equal(merged.ratio, 0.5);
+assert.deepEqual(merged.weights, [1, 2]);
+assert.deepEqual([...merged.blobs.keys()], ["a", "b"]);
+
+const self = values_Sample.create({ weights: [1, 2], chunks: [new Uint8Array([3])], gauges: new Map([["g", 1.5]]) });
+assert.equal(self.mergeFromMessage(self), self);
+assert.deepEqual(self.weights, [1, 2, 1, 2]);
+assert.deepEqual(self.chunks, [new Uint8Array([3]), new Uint8Array([3])]);
+assert.notEqual(self.chunks[0], self.chunks[1]);
+assert.deepEqual([...self.gauges], [["g", 1.5]]);
+"#;
+
+#[test]
+fn merge_from_message_appends_a_copy_of_itself() {
+    run_node("merge_from_message", &SAMPLE, "", MERGE_SCRIPT);
+}
+
+#[test]
+fn strict_merge_from_message_appends_a_copy_of_itself() {
+    run_node("merge_from_message_strict", &SAMPLE, "arkts_strict=true", MERGE_SCRIPT);
+}
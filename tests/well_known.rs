@@ -0,0 +1,122 @@
+mod common;
+
+use common::{class, generate_files, members, method_signature, module, run_node};
+
+const TIMES: [&str; 3] = [
+    "wkt/times.proto",
+    "google/protobuf/timestamp.proto",
+    "google/protobuf/duration.proto",
+];
+
+#[test]
+fn timestamp_and_duration_get_their_helpers() {
+    let files = generate_files(&TIMES, "");
+    let timestamp = module(&files, "google/protobuf/timestamp.ets");
+    let helpers = members(class(&timestamp, "google_protobuf_Timestamp"));
+    for member in [
+        "static now",
+        "static fromDate",
+        "static fromMillis",
+        "toDate",
+        "private checkRange",
+        "static fromJson",
+        "toJson",
+    ] {
+        assert!(helpers.iter().any(|m| m == member), "missing {} in {:?}", member, helpers);
+    }
+    assert_eq!(
+        method_signature(&timestamp, "google_protobuf_Timestamp", "fromDate"),
+        "fromDate(date: Date)"
+    );
+    assert_eq!(method_signature(&timestamp, "google_protobuf_Timestamp", "toDate"), "toDate(): Date");
+
+    let duration = module(&files, "google/protobuf/duration.ets");
+    let helpers = members(class(&duration, "google_protobuf_Duration"));
+    for member in ["static fromMillis", "toMillis", "private checkRange"] {
+        assert!(helpers.iter().any(|m| m == member), "missing {} in {:?}", member, helpers);
+    }
+    assert_eq!(
+        method_signature(&duration, "google_protobuf_Duration", "toMillis"),
+        "toMillis(): number"
+    );
+}
+
+#[test]
+fn timestamps_convert_like_protobuf() {
+    let script = r#"
+import assert from "node:assert/strict";
+import { google_protobuf_Timestamp as Timestamp } from "./google/protobuf/timestamp.mjs";
+import { wkt_Times } from "./wkt/times.mjs";
+
+const epoch = Timestamp.fromMillis(0);
+assert.equal(epoch.toJson(), "1970-01-01T00:00:00Z");
+
+// nanos stay positive before the epoch.
+const before = Timestamp.fromMillis(-1500);
+assert.equal(before.seconds, -2n);
+assert.equal(before.nanos, 500000000);
+assert.equal(before.toDate().getTime(), -1500);
+assert.equal(before.toJson(), "1969-12-31T23:59:58.500Z");
+
+const date = new Date("2024-02-29T12:34:56.789Z");
+assert.equal(Timestamp.fromDate(date).toDate().getTime(), date.getTime());
+assert.throws(() => Timestamp.fromDate(new Date("invalid")));
+
+// fractions are written with 3, 6 or 9 digits.
+for (const [text, written] of [
+  ["2024-01-01T00:00:00.1Z", "2024-01-01T00:00:00.100Z"],
+  ["2024-01-01T00:00:00.000001Z", "2024-01-01T00:00:00.000001Z"],
+  ["2024-01-01T00:00:00.123456789Z", "2024-01-01T00:00:00.123456789Z"],
+  ["2024-01-01T01:00:00+01:00", "2024-01-01T00:00:00Z"],
+]) {
+  assert.equal(Timestamp.fromJson(text).toJson(), written);
+}
+assert.equal(Timestamp.fromJson("0001-01-01T00:00:00Z").seconds, -62135596800n);
+assert.throws(() => Timestamp.fromJson("2024-01-01"));
+assert.throws(() => Timestamp.fromJson("2024-01-01T00:00:00.1234567890Z"));
+const late = new Timestamp();
+late.seconds = 253402300800n;
+assert.throws(() => late.toJson(), /out of range/);
+
+const now = Timestamp.now();
+assert.ok(Math.abs(now.toDate().getTime() - Date.now()) < 1000);
+
+const times = new wkt_Times();
+times.at = Timestamp.fromJson("2024-01-01T00:00:00.5Z");
+assert.deepEqual(wkt_Times.fromJson(times.toJson()).toJson(), { at: "2024-01-01T00:00:00.500Z" });
+assert.equal(wkt_Times.fromBinary(times.toBinary()).at.nanos, 500000000);
+"#;
+    run_node("timestamps", &TIMES, "", script);
+}
+
+#[test]
+fn durations_convert_like_protobuf() {
+    let script = r#"
+import assert from "node:assert/strict";
+import { google_protobuf_Duration as Duration } from "./google/protobuf/duration.mjs";
+
+// seconds and nanos share the sign of the duration.
+const negative = Duration.fromMillis(-1500);
+assert.equal(negative.seconds, -1n);
+assert.equal(negative.nanos, -500000000);
+assert.equal(negative.toMillis(), -1500);
+assert.equal(negative.toJson(), "-1.500s");
+
+assert.equal(Duration.fromMillis(-500).toJson(), "-0.500s");
+assert.equal(Duration.fromMillis(1n).toJson(), "0.001s");
+assert.equal(Duration.fromMillis(60000).toJson(), "60s");
+
+for (const text of ["1.000000001s", "-0.000001s", "315576000000s"]) {
+  assert.equal(Duration.fromJson(text).toJson(), text);
+}
+assert.equal(Duration.fromJson("-0.5s").nanos, -500000000);
+assert.throws(() => Duration.fromJson("1.5"));
+assert.throws(() => Duration.fromJson("315576000001s"), /out of range/);
+
+const mixed = new Duration();
+mixed.seconds = 1n;
+mixed.nanos = -1;
+assert.throws(() => mixed.toJson(), /same sign/);
+"#;
+    run_node("durations", &TIMES, "", script);
+}
@@ -0,0 +1,118 @@
+mod common;
+
+use common::{class, generate_files, interface, interface_props, members, module, print, run_node};
+
+const WRAPPED: [&str; 2] = ["wkt/wrapped.proto", "google/protobuf/wrappers.proto"];
+
+#[test]
+fn wrappers_stay_messages_by_default() {
+    let files = generate_files(&WRAPPED, "");
+    let wrapped = module(&files, "wkt/wrapped.ets");
+    let props = interface_props(interface(&wrapped, "wkt_WrappedInit"));
+    assert_eq!(
+        props[0],
+        "count?: google_protobuf_wrappers.google_protobuf_Int32Value | google_protobuf_wrappers.google_protobuf_Int32ValueInit"
+    );
+    assert_eq!(props[4], "plain?: number");
+}
+
+#[test]
+fn unbox_wrappers_exposes_the_wrapped_types() {
+    let files = generate_files(&WRAPPED, "unbox_wrappers=true");
+    let wrapped = module(&files, "wkt/wrapped.ets");
+    assert_eq!(
+        interface_props(interface(&wrapped, "wkt_WrappedInit")),
+        vec![
+            "count?: number",
+            "label?: string",
+            "total?: bigint",
+            "enabled?: boolean",
+            "plain?: number",
+            "maybe?: number",
+        ]
+    );
+    // the json representation of a wrapper is the wrapped value either way.
+    assert_eq!(
+        interface_props(interface(&wrapped, "wkt_WrappedJson"))[2],
+        "total?: string"
+    );
+    assert!(members(class(&wrapped, "wkt_Wrapped")).iter().any(|m| m == "count"));
+}
+
+#[test]
+fn unboxed_wrappers_keep_the_default_of_the_wrapped_type() {
+    let files = generate_files(&WRAPPED, "unbox_wrappers=true");
+    let wrapped = module(&files, "wkt/wrapped.ets");
+    let to_json = print(common::method(class(&wrapped, "wkt_Wrapped"), "toJson"));
+    // only fields without presence compare against the default.
+    assert!(!to_json.contains("this.count !== 0"), "{}", to_json);
+    assert!(!to_json.contains("this.label !== \"\""), "{}", to_json);
+    assert!(to_json.contains("this.plain !== 0"), "{}", to_json);
+}
+
+#[test]
+fn unboxed_wrappers_round_trip_set_defaults() {
+    let script = r#"
+import assert from "node:assert/strict";
+import { wkt_Wrapped } from "./wkt/wrapped.mjs";
+
+const unset = new wkt_Wrapped();
+assert.deepEqual(unset.toJson(), {});
+assert.equal(wkt_Wrapped.fromBinary(unset.toBinary()).count, undefined);
+
+// a wrapper set to the default of the wrapped type is still set.
+const zero = wkt_Wrapped.create({ count: 0, label: "", total: 0n, enabled: false, plain: 0 });
+assert.deepEqual(zero.toJson(), { count: 0, label: "", total: "0", enabled: false });
+const decoded = wkt_Wrapped.fromBinary(zero.toBinary());
+assert.equal(decoded.count, 0);
+assert.equal(decoded.label, "");
+assert.equal(decoded.total, 0n);
+assert.equal(decoded.enabled, false);
+assert.equal(decoded.maybe, undefined);
+
+const parsed = wkt_Wrapped.fromJson({ count: 0, label: "", total: "0", enabled: false });
+assert.equal(parsed.count, 0);
+assert.equal(parsed.label, "");
+assert.equal(parsed.total, 0n);
+assert.equal(parsed.enabled, false);
+assert.ok(parsed.equals(decoded));
+
+const merged = wkt_Wrapped.create({ count: 7, label: "x" });
+merged.mergeFromMessage(zero);
+assert.equal(merged.count, 0);
+assert.equal(merged.label, "");
+
+const values = wkt_Wrapped.create({ count: -3, label: "a", total: 9007199254740993n, enabled: true });
+assert.deepEqual(wkt_Wrapped.fromBinary(values.toBinary()).toJson(), values.toJson());
+assert.deepEqual(values.toJson(), { count: -3, label: "a", total: "9007199254740993", enabled: true });
+"#;
+    run_node("unboxed_wrappers", &WRAPPED, "unbox_wrappers=true", script);
+}
+
+const BOXED_SCRIPT: &str = r#"
+import assert from "node:assert/strict";
+import { wkt_Wrapped } from "./wkt/wrapped.mjs";
+import { google_protobuf_Int32Value } from "./google/protobuf/wrappers.mjs";
+
+const zero = new wkt_Wrapped();
+zero.count = new google_protobuf_Int32Value();
+assert.deepEqual(zero.toJson(), { count: 0 });
+assert.equal(wkt_Wrapped.fromBinary(zero.toBinary()).count.value, 0);
+assert.equal(wkt_Wrapped.fromJson({ count: 5 }).count.value, 5);
+
+const json = { count: -3, label: "a", total: "9007199254740993", enabled: true };
+const values = wkt_Wrapped.fromJson(json);
+assert.equal(values.total.value, 9007199254740993n);
+assert.deepEqual(values.toJson(), json);
+assert.deepEqual(wkt_Wrapped.fromBinary(values.toBinary()).toJson(), json);
+"#;
+
+#[test]
+fn boxed_wrappers_round_trip() {
+    run_node("boxed_wrappers", &WRAPPED, "", BOXED_SCRIPT);
+}
+
+#[test]
+fn strict_boxed_wrappers_round_trip() {
+    run_node("boxed_wrappers_strict", &WRAPPED, "arkts_strict=true", BOXED_SCRIPT);
+}